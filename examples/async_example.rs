@@ -0,0 +1,65 @@
+// Copyright (c) 2025, vistone
+// All rights reserved.
+
+// 异步连接池使用示例
+// 运行: cargo run --example async_example --features async
+
+use netconnpool::*;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+
+#[tokio::main]
+async fn main() -> std::result::Result<(), Box<dyn std::error::Error>> {
+    // 启动一个本地回声服务器用于演示
+    let listener = TcpListener::bind("127.0.0.1:0").await?;
+    let addr = listener.local_addr()?;
+    tokio::spawn(async move {
+        while let Ok((mut stream, _)) = listener.accept().await {
+            tokio::spawn(async move {
+                let mut buf = [0u8; 1024];
+                while let Ok(n) = stream.read(&mut buf).await {
+                    if n == 0 || stream.write_all(&buf[..n]).await.is_err() {
+                        break;
+                    }
+                }
+            });
+        }
+    });
+
+    // 容量、超时等配置与同步版共用 Config
+    let mut config = default_config();
+    config.max_connections = 4;
+    config.min_connections = 0;
+
+    // 异步建连函数
+    let pool = AsyncPool::new(config, Box::new(move || Box::pin(TcpStream::connect(addr))))?;
+
+    // 并发借用：最多 4 条连接，其余请求异步等待归还
+    let pool = std::sync::Arc::new(pool);
+    let mut tasks = Vec::new();
+    for i in 0..16 {
+        let pool = pool.clone();
+        tasks.push(tokio::spawn(async move {
+            let mut conn = pool.get().await?;
+            let msg = format!("hello {}", i);
+            conn.write_all(msg.as_bytes()).await?;
+            let mut buf = vec![0u8; msg.len()];
+            conn.read_exact(&mut buf).await?;
+            assert_eq!(buf, msg.as_bytes());
+            // 归还连接 (RAII 自动归还)
+            Ok::<_, Box<dyn std::error::Error + Send + Sync>>(())
+        }));
+    }
+    for task in tasks {
+        task.await?.map_err(|e| e.to_string())?;
+    }
+
+    let stats = pool.stats();
+    println!(
+        "新建连接: {}, 复用次数: {}, 成功获取: {}",
+        stats.total_connections_created, stats.total_connections_reused, stats.successful_gets
+    );
+
+    pool.close();
+    Ok(())
+}
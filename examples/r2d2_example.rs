@@ -0,0 +1,64 @@
+// Copyright (c) 2025, vistone
+// All rights reserved.
+
+// 通过 r2d2 适配层使用本 crate 的 Dialer 与健康检查
+// 运行: cargo run --example r2d2_example --features r2d2
+
+use netconnpool::*;
+use std::io::{Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::thread;
+use std::time::Duration;
+
+fn main() -> std::result::Result<(), Box<dyn std::error::Error>> {
+    // 启动一个本地回声服务器用于演示
+    let listener = TcpListener::bind("127.0.0.1:0")?;
+    let addr = listener.local_addr()?;
+    thread::spawn(move || {
+        for mut sock in listener.incoming().flatten() {
+            thread::spawn(move || {
+                let mut buf = [0u8; 1024];
+                while let Ok(n) = sock.read(&mut buf) {
+                    if n == 0 || sock.write_all(&buf[..n]).is_err() {
+                        break;
+                    }
+                }
+            });
+        }
+    });
+
+    // 建连与健康检查沿用 netconnpool 的 Config；容量与超时交给 r2d2::Builder
+    let mut config = default_config();
+    config.dialer = Some(Box::new(move |_| {
+        TcpStream::connect(addr)
+            .map(ConnectionType::Tcp)
+            .map_err(|e| Box::new(e) as Box<dyn std::error::Error + Send + Sync>)
+    }));
+    config.health_checker = Some(Box::new(|conn| conn.peer_addr().is_ok()));
+    let manager = R2d2Manager::new(config)?;
+
+    let pool = r2d2::Pool::builder()
+        .max_size(2)
+        .connection_timeout(Duration::from_secs(3))
+        .build(manager)?;
+
+    for i in 0..3 {
+        let mut conn = pool.get()?;
+        let ConnectionType::Tcp(stream) = &mut *conn else {
+            unreachable!("Dialer 只创建 TCP 连接");
+        };
+        let msg = format!("hello r2d2 {}", i);
+        stream.write_all(msg.as_bytes())?;
+        let mut buf = vec![0u8; msg.len()];
+        stream.read_exact(&mut buf)?;
+        println!("收到回显: {}", String::from_utf8_lossy(&buf));
+        // 连接随 r2d2::PooledConnection 的 Drop 归还到 r2d2 连接池
+    }
+
+    let state = pool.state();
+    println!(
+        "连接数: {}, 空闲: {}",
+        state.connections, state.idle_connections
+    );
+    Ok(())
+}
@@ -0,0 +1,54 @@
+// Copyright (c) 2025, vistone
+// All rights reserved.
+
+// UDP 共享 socket 模式示例：100 个并发发送者共用 4 个 socket
+
+use netconnpool::*;
+use std::collections::HashSet;
+use std::net::UdpSocket;
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+const SENDERS: usize = 100;
+
+fn main() -> std::result::Result<(), Box<dyn std::error::Error>> {
+    // 接收端：统计收到的包数及来源端口
+    let server = UdpSocket::bind("127.0.0.1:0")?;
+    server.set_read_timeout(Some(Duration::from_secs(2)))?;
+    let target = server.local_addr()?;
+
+    let pool = Arc::new(SharedUdpPool::new("127.0.0.1:0", 4)?);
+
+    let handles: Vec<_> = (0..SENDERS)
+        .map(|i| {
+            let pool = pool.clone();
+            thread::spawn(move || {
+                let socket = pool.get();
+                socket.send_to(format!("metric-{}", i).as_bytes(), target)
+            })
+        })
+        .collect();
+    for handle in handles {
+        handle.join().expect("发送线程 panic")?;
+    }
+
+    let mut buf = [0u8; 64];
+    let mut sources = HashSet::new();
+    let mut received = 0;
+    while received < SENDERS {
+        let (_, from) = server.recv_from(&mut buf)?;
+        sources.insert(from);
+        received += 1;
+    }
+
+    println!(
+        "并发发送者: {}，收到包: {}，实际使用 socket 数: {}（池内 {} 个）",
+        SENDERS,
+        received,
+        sources.len(),
+        pool.socket_count()
+    );
+    assert!(sources.len() <= pool.socket_count());
+    Ok(())
+}
@@ -0,0 +1,84 @@
+// Copyright (c) 2025, vistone
+// All rights reserved.
+
+// TLS 连接池使用示例
+// 运行: cargo run --example tls_example --features tls
+
+use netconnpool::*;
+use rustls::pki_types::{CertificateDer, PrivateKeyDer, PrivatePkcs8KeyDer};
+use rustls::{ClientConfig, RootCertStore, ServerConfig, ServerConnection, StreamOwned};
+use std::io::{Read, Write};
+use std::net::TcpListener;
+use std::sync::Arc;
+use std::thread;
+
+fn main() -> std::result::Result<(), Box<dyn std::error::Error>> {
+    // 生成自签名证书，启动一个本地 TLS 回声服务器用于演示
+    let cert = rcgen::generate_simple_self_signed(vec!["localhost".to_string()])?;
+    let cert_der: CertificateDer<'static> = cert.cert.der().clone();
+    let key_der = PrivateKeyDer::Pkcs8(PrivatePkcs8KeyDer::from(cert.signing_key.serialize_der()));
+    let server_config = Arc::new(
+        ServerConfig::builder()
+            .with_no_client_auth()
+            .with_single_cert(vec![cert_der.clone()], key_der)?,
+    );
+
+    let listener = TcpListener::bind("127.0.0.1:0")?;
+    let port = listener.local_addr()?.port();
+    thread::spawn(move || {
+        for sock in listener.incoming().flatten() {
+            let server_config = server_config.clone();
+            thread::spawn(move || {
+                let Ok(conn) = ServerConnection::new(server_config) else {
+                    return;
+                };
+                let mut stream = StreamOwned::new(conn, sock);
+                let mut buf = [0u8; 1024];
+                while let Ok(n) = stream.read(&mut buf) {
+                    if n == 0 || stream.write_all(&buf[..n]).is_err() {
+                        break;
+                    }
+                }
+            });
+        }
+    });
+
+    // 客户端信任上面的自签名证书
+    let mut roots = RootCertStore::empty();
+    roots.add(cert_der)?;
+    let client_config = Arc::new(
+        ClientConfig::builder()
+            .with_root_certificates(roots)
+            .with_no_client_auth(),
+    );
+
+    let mut config = default_config();
+    config.max_connections = 2;
+    config.min_connections = 0;
+    config.dialer = Some(tls_dialer("127.0.0.1", port, "localhost", client_config));
+    let pool = Pool::new(config)?;
+
+    // 多次借用：握手只发生在第一次建连，之后复用同一条 TLS 连接
+    for i in 0..3 {
+        let mut conn = pool.get()?;
+        let msg = format!("hello tls {}", i);
+        conn.write_all(msg.as_bytes())?;
+        let mut buf = vec![0u8; msg.len()];
+        conn.read_exact(&mut buf)?;
+        println!(
+            "连接 {} 收到回显: {}",
+            conn.id(),
+            String::from_utf8_lossy(&buf)
+        );
+        // 归还连接 (RAII 自动归还)
+    }
+
+    let stats = pool.stats();
+    println!(
+        "创建连接: {}, 复用次数: {}",
+        stats.total_connections_created, stats.total_connections_reused
+    );
+
+    pool.close()?;
+    Ok(())
+}
@@ -0,0 +1,507 @@
+// Copyright (c) 2025, vistone
+// All rights reserved.
+
+//! 异步连接池模块（需启用 `async` feature）
+//!
+//! 基于 tokio 的异步连接池：`get().await` 通过 `Notify` 异步等待连接归还，
+//! 不占用阻塞线程；底层连接为 `tokio::net::TcpStream`。
+//!
+//! 当前阶段仅支持 TCP 客户端模式。容量、超时与统计相关配置与同步版 [`Pool`](crate::Pool)
+//! 共用 [`Config`]，但 `Config::dialer` 不参与异步建连，由 [`AsyncDialer`] 代替。
+//! 空闲连接的过期检查在借出时惰性执行，暂不启动后台清理任务。
+
+use crate::config::Config;
+use crate::errors::{NetConnPoolError, Result, WaitReason};
+use crate::ipversion::IPVersion;
+use crate::mode::PoolMode;
+use crate::stats::{Stats, StatsCollector};
+use std::collections::VecDeque;
+use std::fmt;
+use std::future::Future;
+use std::io;
+use std::ops::{Deref, DerefMut};
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, Weak};
+use std::time::{Duration, Instant};
+use tokio::net::TcpStream;
+use tokio::sync::Notify;
+
+/// AsyncDialer 异步连接创建函数类型
+pub type AsyncDialer =
+    Box<dyn Fn() -> Pin<Box<dyn Future<Output = io::Result<TcpStream>> + Send>> + Send + Sync>;
+
+/// 池内保存的异步连接及其元数据
+struct AsyncConn {
+    id: u64,
+    stream: TcpStream,
+    ip_version: IPVersion,
+    created_at: Instant,
+    idle_since: Instant,
+    reuse_count: u64,
+}
+
+impl AsyncConn {
+    fn is_expired(&self, config: &Config) -> bool {
+        // 与同步版一致：0 表示不限制
+        (!config.max_lifetime.is_zero() && self.created_at.elapsed() > config.max_lifetime)
+            || (!config.idle_timeout.is_zero() && self.idle_since.elapsed() > config.idle_timeout)
+    }
+}
+
+/// AsyncPool 异步连接池
+pub struct AsyncPool {
+    inner: Arc<AsyncPoolInner>,
+}
+
+struct AsyncPoolInner {
+    config: Config,
+    dialer: AsyncDialer,
+    // 空闲连接，按 IP 版本分桶（0: IPv4, 1: IPv6）
+    idle: [Mutex<VecDeque<AsyncConn>>; 2],
+    // 存活连接总数（含借出与建连中预留的名额）
+    total: AtomicUsize,
+    active: AtomicUsize,
+    closed: AtomicBool,
+    next_id: AtomicU64,
+    // 连接归还/名额释放/关闭时唤醒等待者
+    notify: Notify,
+    stats_collector: Option<Arc<StatsCollector>>,
+}
+
+impl fmt::Debug for AsyncPool {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AsyncPool")
+            .field("config", &self.inner.config)
+            .field("total", &self.inner.total.load(Ordering::Relaxed))
+            .field("active", &self.inner.active.load(Ordering::Relaxed))
+            .field("idle", &self.idle_count())
+            .field("closed", &self.inner.closed.load(Ordering::Relaxed))
+            .finish()
+    }
+}
+
+impl AsyncPool {
+    /// 创建异步连接池
+    ///
+    /// # 参数
+    /// - `config`: 连接池配置（仅支持客户端模式，`config.dialer` 可为空）
+    /// - `dialer`: 异步建连函数
+    ///
+    /// # 示例
+    /// ```rust,no_run
+    /// use netconnpool::*;
+    ///
+    /// let pool = AsyncPool::new(
+    ///     default_config(),
+    ///     Box::new(|| Box::pin(tokio::net::TcpStream::connect("127.0.0.1:8080"))),
+    /// )
+    /// .unwrap();
+    /// ```
+    pub fn new(mut config: Config, dialer: AsyncDialer) -> Result<Self> {
+        if config.mode != PoolMode::Client {
+            return Err(NetConnPoolError::InvalidConfig {
+                reason: "AsyncPool 目前仅支持客户端模式".to_string(),
+            });
+        }
+        config.apply_defaults();
+        config.validate_limits()?;
+
+        let stats_collector = if config.enable_stats {
+            Some(Arc::new(StatsCollector::with_ewma_window(
+                config.stats_ewma_window,
+            )))
+        } else {
+            None
+        };
+
+        Ok(Self {
+            inner: Arc::new(AsyncPoolInner {
+                config,
+                dialer,
+                idle: [Mutex::new(VecDeque::new()), Mutex::new(VecDeque::new())],
+                total: AtomicUsize::new(0),
+                active: AtomicUsize::new(0),
+                closed: AtomicBool::new(false),
+                next_id: AtomicU64::new(1),
+                notify: Notify::new(),
+                stats_collector,
+            }),
+        })
+    }
+
+    /// 获取一个连接（使用 `get_connection_timeout` 作为超时）
+    pub async fn get(&self) -> Result<AsyncPooledConnection> {
+        self.get_with_timeout(self.inner.config.get_connection_timeout)
+            .await
+    }
+
+    /// 立即获取连接，池满时不等待
+    pub async fn try_get(&self) -> Result<AsyncPooledConnection> {
+        self.get_with_timeout(Duration::ZERO).await
+    }
+
+    /// 获取一个连接（带超时）
+    ///
+    /// 优先复用空闲连接；池未满时异步建连；池满时异步等待连接归还，
+    /// 到期返回 `PoolExhausted`。取消（drop）返回的 Future 是安全的，不会泄漏连接名额。
+    pub async fn get_with_timeout(&self, timeout: Duration) -> Result<AsyncPooledConnection> {
+        let inner = &self.inner;
+        if inner.is_closed() {
+            return Err(NetConnPoolError::PoolClosed);
+        }
+        if let Some(stats) = &inner.stats_collector {
+            stats.increment_total_get_requests();
+        }
+
+        let start = Instant::now();
+        loop {
+            if inner.is_closed() {
+                return Err(NetConnPoolError::PoolClosed);
+            }
+
+            // 先登记等待，再检查状态，避免检查与等待之间的归还通知丢失
+            let mut notified = std::pin::pin!(inner.notify.notified());
+            notified.as_mut().enable();
+
+            if let Some(mut conn) = inner.take_idle() {
+                conn.reuse_count += 1;
+                inner.active.fetch_add(1, Ordering::Relaxed);
+                if let Some(stats) = &inner.stats_collector {
+                    stats.increment_successful_gets();
+                    stats.increment_current_active_connections(1);
+                    stats.increment_total_connections_reused();
+                    let elapsed = start.elapsed();
+                    stats.record_get_time(elapsed);
+                    stats.record_get_time_by_source(elapsed, true);
+                }
+                return Ok(AsyncPooledConnection::new(conn, Arc::downgrade(inner)));
+            }
+
+            if let Some(slot) = SlotGuard::reserve(inner) {
+                let conn = inner.dial(slot).await.inspect_err(|_| {
+                    if let Some(stats) = &inner.stats_collector {
+                        stats.increment_failed_gets();
+                        stats.increment_connection_errors();
+                    }
+                })?;
+                inner.active.fetch_add(1, Ordering::Relaxed);
+                if let Some(stats) = &inner.stats_collector {
+                    stats.increment_successful_gets();
+                    stats.increment_current_active_connections(1);
+                    let elapsed = start.elapsed();
+                    stats.record_get_time(elapsed);
+                    stats.record_get_time_by_source(elapsed, false);
+                }
+                return Ok(AsyncPooledConnection::new(conn, Arc::downgrade(inner)));
+            }
+
+            let waited = start.elapsed();
+            if waited >= timeout {
+                if let Some(stats) = &inner.stats_collector {
+                    stats.increment_failed_gets();
+                    if !timeout.is_zero() {
+                        stats.increment_timeout_gets();
+                    }
+                }
+                return Err(NetConnPoolError::PoolExhausted {
+                    current: inner.total.load(Ordering::Relaxed),
+                    max: inner.config.max_connections,
+                    waited,
+                    wait_reason: WaitReason::WaitingForIdle,
+                    last_dial_error: None,
+                });
+            }
+            let _ = tokio::time::timeout(timeout - waited, notified).await;
+        }
+    }
+
+    /// 关闭连接池：关闭所有空闲连接并唤醒全部等待者，借出的连接归还时直接关闭
+    pub fn close(&self) {
+        if self.inner.closed.swap(true, Ordering::SeqCst) {
+            return;
+        }
+        for bucket in &self.inner.idle {
+            let drained: Vec<AsyncConn> = bucket
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .drain(..)
+                .collect();
+            for conn in drained {
+                self.inner.update_stats_on_idle_pop(&conn);
+                self.inner.discard(conn);
+            }
+        }
+        self.inner.notify.notify_waiters();
+    }
+
+    /// 检查连接池是否已关闭
+    pub fn is_closed(&self) -> bool {
+        self.inner.is_closed()
+    }
+
+    /// 获取统计信息
+    pub fn stats(&self) -> Stats {
+        self.inner
+            .stats_collector
+            .as_ref()
+            .map(|s| s.get_stats())
+            .unwrap_or_default()
+    }
+
+    /// 获取当前借出的连接数
+    pub fn active_count(&self) -> usize {
+        self.inner.active.load(Ordering::Relaxed)
+    }
+
+    /// 获取当前空闲连接数
+    pub fn idle_count(&self) -> usize {
+        self.inner
+            .idle
+            .iter()
+            .map(|b| b.lock().map(|q| q.len()).unwrap_or(0))
+            .sum()
+    }
+}
+
+impl Drop for AsyncPool {
+    fn drop(&mut self) {
+        self.close();
+    }
+}
+
+impl AsyncPoolInner {
+    fn is_closed(&self) -> bool {
+        self.closed.load(Ordering::Acquire)
+    }
+
+    fn bucket_index(ip_version: IPVersion) -> usize {
+        match ip_version {
+            IPVersion::IPv6 => 1,
+            _ => 0,
+        }
+    }
+
+    /// 取出一条未过期的空闲连接，过期连接直接关闭
+    fn take_idle(&self) -> Option<AsyncConn> {
+        for bucket in &self.idle {
+            loop {
+                let conn = bucket.lock().unwrap_or_else(|e| e.into_inner()).pop_front();
+                let Some(conn) = conn else {
+                    break;
+                };
+                self.update_stats_on_idle_pop(&conn);
+                if conn.is_expired(&self.config) {
+                    self.discard(conn);
+                    continue;
+                }
+                return Some(conn);
+            }
+        }
+        None
+    }
+
+    /// 使用预留的名额异步建连
+    async fn dial(&self, slot: SlotGuard<'_>) -> Result<AsyncConn> {
+        let stream =
+            match tokio::time::timeout(self.config.connection_timeout, (self.dialer)()).await {
+                Ok(Ok(stream)) => stream,
+                Ok(Err(e)) => return Err(NetConnPoolError::dial_failed(Box::new(e))),
+                Err(_) => {
+                    return Err(NetConnPoolError::dial_failed(Box::new(io::Error::new(
+                        io::ErrorKind::TimedOut,
+                        "异步建连超时",
+                    ))))
+                }
+            };
+        if self.config.tcp_nodelay {
+            stream.set_nodelay(true)?;
+        }
+        let ip_version = match stream.peer_addr() {
+            Ok(addr) if addr.is_ipv6() => IPVersion::IPv6,
+            _ => IPVersion::IPv4,
+        };
+        slot.commit();
+
+        if let Some(stats) = &self.stats_collector {
+            stats.increment_total_connections_created();
+            stats.increment_current_tcp_connections(1);
+            match ip_version {
+                IPVersion::IPv6 => stats.increment_current_ipv6_connections(1),
+                _ => stats.increment_current_ipv4_connections(1),
+            }
+        }
+
+        let now = Instant::now();
+        Ok(AsyncConn {
+            id: self.next_id.fetch_add(1, Ordering::Relaxed),
+            stream,
+            ip_version,
+            created_at: now,
+            idle_since: now,
+            reuse_count: 0,
+        })
+    }
+
+    /// 归还连接：池已关闭、连接过期或空闲已满时直接关闭
+    fn put_back(&self, mut conn: AsyncConn) {
+        self.active.fetch_sub(1, Ordering::Relaxed);
+        if let Some(stats) = &self.stats_collector {
+            stats.increment_current_active_connections(-1);
+        }
+
+        if self.is_closed() || conn.is_expired(&self.config) {
+            self.discard(conn);
+        } else {
+            conn.idle_since = Instant::now();
+            let idx = Self::bucket_index(conn.ip_version);
+            let mut bucket = self.idle[idx].lock().unwrap_or_else(|e| e.into_inner());
+            if bucket.len() < self.config.max_idle_connections {
+                self.update_stats_on_idle_push(&conn);
+                bucket.push_back(conn);
+            } else {
+                drop(bucket);
+                self.discard(conn);
+            }
+        }
+        self.notify.notify_one();
+    }
+
+    /// 关闭连接并释放名额
+    fn discard(&self, conn: AsyncConn) {
+        self.total.fetch_sub(1, Ordering::Relaxed);
+        if let Some(stats) = &self.stats_collector {
+            stats.increment_total_connections_closed();
+            stats.increment_current_tcp_connections(-1);
+            match conn.ip_version {
+                IPVersion::IPv6 => stats.increment_current_ipv6_connections(-1),
+                _ => stats.increment_current_ipv4_connections(-1),
+            }
+        }
+        drop(conn);
+        self.notify.notify_one();
+    }
+
+    fn update_stats_on_idle_push(&self, conn: &AsyncConn) {
+        if let Some(stats) = &self.stats_collector {
+            stats.increment_current_idle_connections(1);
+            stats.increment_current_tcp_idle_connections(1);
+            match conn.ip_version {
+                IPVersion::IPv6 => stats.increment_current_ipv6_idle_connections(1),
+                _ => stats.increment_current_ipv4_idle_connections(1),
+            }
+        }
+    }
+
+    fn update_stats_on_idle_pop(&self, conn: &AsyncConn) {
+        if let Some(stats) = &self.stats_collector {
+            stats.increment_current_idle_connections(-1);
+            stats.increment_current_tcp_idle_connections(-1);
+            match conn.ip_version {
+                IPVersion::IPv6 => stats.increment_current_ipv6_idle_connections(-1),
+                _ => stats.increment_current_ipv4_idle_connections(-1),
+            }
+        }
+    }
+}
+
+/// 建连名额预留：建连失败或 Future 被取消时自动归还名额
+struct SlotGuard<'a> {
+    inner: &'a AsyncPoolInner,
+    committed: bool,
+}
+
+impl<'a> SlotGuard<'a> {
+    fn reserve(inner: &'a AsyncPoolInner) -> Option<Self> {
+        let max = inner.config.max_connections;
+        inner
+            .total
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |current| {
+                (max == 0 || current < max).then_some(current + 1)
+            })
+            .ok()
+            .map(|_| Self {
+                inner,
+                committed: false,
+            })
+    }
+
+    fn commit(mut self) {
+        self.committed = true;
+    }
+}
+
+impl Drop for SlotGuard<'_> {
+    fn drop(&mut self) {
+        if !self.committed {
+            self.inner.total.fetch_sub(1, Ordering::Relaxed);
+            self.inner.notify.notify_one();
+        }
+    }
+}
+
+/// AsyncPooledConnection 自动归还的异步连接包装器
+/// 解引用为 `tokio::net::TcpStream`，Drop 时自动归还到池中
+pub struct AsyncPooledConnection {
+    conn: Option<AsyncConn>,
+    pool: Weak<AsyncPoolInner>,
+}
+
+impl AsyncPooledConnection {
+    fn new(conn: AsyncConn, pool: Weak<AsyncPoolInner>) -> Self {
+        Self {
+            conn: Some(conn),
+            pool,
+        }
+    }
+
+    fn conn(&self) -> &AsyncConn {
+        self.conn.as_ref().expect("连接在 Drop 前始终存在")
+    }
+
+    /// 获取连接 ID
+    pub fn id(&self) -> u64 {
+        self.conn().id
+    }
+
+    /// 获取连接的 IP 版本
+    pub fn ip_version(&self) -> IPVersion {
+        self.conn().ip_version
+    }
+
+    /// 获取连接被复用的次数
+    pub fn reuse_count(&self) -> u64 {
+        self.conn().reuse_count
+    }
+}
+
+impl fmt::Debug for AsyncPooledConnection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AsyncPooledConnection")
+            .field("id", &self.id())
+            .field("ip_version", &self.ip_version())
+            .field("stream", &self.conn().stream)
+            .finish()
+    }
+}
+
+impl Deref for AsyncPooledConnection {
+    type Target = TcpStream;
+    fn deref(&self) -> &Self::Target {
+        &self.conn().stream
+    }
+}
+
+impl DerefMut for AsyncPooledConnection {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.conn.as_mut().expect("连接在 Drop 前始终存在").stream
+    }
+}
+
+impl Drop for AsyncPooledConnection {
+    fn drop(&mut self) {
+        if let (Some(conn), Some(pool)) = (self.conn.take(), self.pool.upgrade()) {
+            pool.put_back(conn);
+        }
+    }
+}
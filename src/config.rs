@@ -2,9 +2,13 @@
 // All rights reserved.
 
 use crate::errors::{NetConnPoolError, Result};
+use crate::events::PoolEvent;
 use crate::mode::PoolMode;
 use crate::protocol::Protocol;
+use crate::stats::Stats;
+use socket2::SockRef;
 use std::net::{TcpStream, UdpSocket};
+use std::sync::mpsc::SyncSender;
 use std::time::Duration;
 
 /// CloseConn 连接关闭回调类型
@@ -17,6 +21,12 @@ pub type OnCreatedCallback = dyn Fn(&ConnectionType) -> std::result::Result<(),
     + Send
     + Sync;
 
+/// OnAcquire 借出前初始化回调类型
+/// 返回错误表示该连接无法恢复到干净状态，连接池会丢弃它
+pub type OnAcquireCallback = dyn Fn(&ConnectionType) -> std::result::Result<(), Box<dyn std::error::Error + Send + Sync>>
+    + Send
+    + Sync;
+
 /// OnBorrow/OnReturn 借出/归还回调类型
 pub type BorrowReturnCallback = dyn Fn(&ConnectionType) + Send + Sync;
 
@@ -31,6 +41,40 @@ pub type Dialer = Box<
         + Sync,
 >;
 
+/// LabeledDialer 带标签的连接创建函数类型（客户端模式）
+/// 与 [`Dialer`] 相同，但可额外返回一个可读标签（如目标 host），存入连接供日志/诊断关联业务含义
+pub type LabeledDialer = Box<
+    dyn Fn(
+            Option<Protocol>,
+        ) -> std::result::Result<
+            (ConnectionType, Option<String>),
+            Box<dyn std::error::Error + Send + Sync>,
+        > + Send
+        + Sync,
+>;
+
+/// ConnectionRole 连接角色：建立后只用于单一方向的连接（如消息队列的消费/生产连接）
+///
+/// 带角色的连接只会被同角色的 `Pool::get_role` 借出，不会被普通 get 拿到。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ConnectionRole {
+    /// ReadOnly 只读连接（如消费者）
+    ReadOnly,
+    /// WriteOnly 只写连接（如生产者）
+    WriteOnly,
+}
+
+/// RoleDialer 按角色创建连接的函数类型（客户端模式）
+/// 参数为调用方请求的协议与角色，返回的连接标注为该角色
+pub type RoleDialer = Box<
+    dyn Fn(
+            Option<Protocol>,
+            ConnectionRole,
+        ) -> std::result::Result<ConnectionType, Box<dyn std::error::Error + Send + Sync>>
+        + Send
+        + Sync,
+>;
+
 /// Acceptor 连接接受函数类型（服务器端模式）
 /// 从Listener接受新连接，返回网络连接和错误
 pub type Acceptor = Box<
@@ -45,11 +89,134 @@ pub type Acceptor = Box<
 /// 返回连接是否健康
 pub type HealthChecker = Box<dyn Fn(&ConnectionType) -> bool + Send + Sync>;
 
+/// HealthCheckerWithReason 带失败原因的健康检查函数类型
+/// 返回 Ok(()) 表示健康，Err(reason) 表示不健康及其原因（如 "read timeout"、"ping failed"）
+pub type HealthCheckerWithReason =
+    Box<dyn Fn(&ConnectionType) -> std::result::Result<(), String> + Send + Sync>;
+
+/// OnHealthCheckFailure 健康检查失败回调类型
+/// 参数为失败的连接与失败原因
+pub type HealthCheckFailureCallback = dyn Fn(&ConnectionType, &str) + Send + Sync;
+
+/// StatsCallback 周期性统计回调，参数为本次采样的统计快照
+pub type StatsCallback = dyn Fn(&Stats) + Send + Sync;
+
+/// WatermarkCallback 容量水位告警回调，参数为越过阈值时的统计快照
+pub type WatermarkCallback = dyn Fn(&Stats) + Send + Sync;
+
+/// DegradationCallback 降级态切换回调，参数为是否进入降级态与当时的可用连接数
+pub type DegradationCallback = dyn Fn(bool, usize) + Send + Sync;
+
+/// SlowGetCallback 慢获取回调，参数为触发时该次 get 已等待的时间
+pub type SlowGetCallback = dyn Fn(Duration) + Send + Sync;
+
 /// ConnectionType 连接类型（TCP或UDP）
 #[derive(Debug)]
 pub enum ConnectionType {
     Tcp(TcpStream),
     Udp(UdpSocket),
+    /// Tls 已完成握手的 TLS 连接（`tls` feature），按 TCP 分桶管理
+    #[cfg(feature = "tls")]
+    Tls(crate::tls::TlsStream),
+}
+
+impl ConnectionType {
+    /// 对端地址
+    pub fn peer_addr(&self) -> std::io::Result<std::net::SocketAddr> {
+        match self {
+            ConnectionType::Tcp(stream) => stream.peer_addr(),
+            ConnectionType::Udp(socket) => socket.peer_addr(),
+            #[cfg(feature = "tls")]
+            ConnectionType::Tls(tls) => tls.peer_addr(),
+        }
+    }
+
+    /// 本地地址
+    pub fn local_addr(&self) -> std::io::Result<std::net::SocketAddr> {
+        match self {
+            ConnectionType::Tcp(stream) => stream.local_addr(),
+            ConnectionType::Udp(socket) => socket.local_addr(),
+            #[cfg(feature = "tls")]
+            ConnectionType::Tls(tls) => tls.local_addr(),
+        }
+    }
+}
+
+/// SocketOptions 建连后统一应用的 socket 选项
+///
+/// 各字段为 None 时保持系统默认值。选项在 Dialer 返回连接、纳入连接池前设置，
+/// 因此只影响连接建立之后的行为（例如 reuse_address 对已连接的 socket 仅在重新绑定时有意义）。
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SocketOptions {
+    /// ReuseAddress SO_REUSEADDR
+    pub reuse_address: Option<bool>,
+    /// RecvBufferSize SO_RCVBUF（字节）
+    pub recv_buffer_size: Option<usize>,
+    /// SendBufferSize SO_SNDBUF（字节）
+    pub send_buffer_size: Option<usize>,
+    /// Linger SO_LINGER（仅 TCP），Some(Duration::ZERO) 表示关闭时直接 RST
+    pub linger: Option<Duration>,
+    /// Tos IP_TOS（仅 IPv4 连接）
+    pub tos: Option<u32>,
+}
+
+impl SocketOptions {
+    /// 将选项应用到 socket 上
+    pub(crate) fn apply(&self, socket: SockRef<'_>, is_tcp: bool) -> std::io::Result<()> {
+        if let Some(reuse) = self.reuse_address {
+            socket.set_reuse_address(reuse)?;
+        }
+        if let Some(size) = self.recv_buffer_size {
+            socket.set_recv_buffer_size(size)?;
+        }
+        if let Some(size) = self.send_buffer_size {
+            socket.set_send_buffer_size(size)?;
+        }
+        if is_tcp {
+            if let Some(linger) = self.linger {
+                socket.set_linger(Some(linger))?;
+            }
+        }
+        #[cfg(not(any(
+            target_os = "fuchsia",
+            target_os = "redox",
+            target_os = "solaris",
+            target_os = "illumos",
+            target_os = "haiku",
+        )))]
+        if let Some(tos) = self.tos {
+            if socket.local_addr()?.is_ipv4() {
+                socket.set_tos(tos)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// AdaptiveTimeout 自适应获取超时参数
+///
+/// 启用后，使用默认超时的 get（`get`、`get_tcp` 等）以最近 `window` 次成功建连耗时的 P99
+/// 乘以 `multiplier` 作为等待建连许可的上限，并限制在 [`min_timeout`, `get_connection_timeout`] 之间；
+/// 尚无建连样本时仍使用 get_connection_timeout。池满时等待连接归还始终以 get_connection_timeout
+/// 为上限，避免负载高时因建连很快而过早超时。显式传入 timeout 的 get 不受影响。
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AdaptiveTimeout {
+    /// Window 参与计算的最近建连样本数
+    pub window: usize,
+    /// Multiplier 动态超时相对建连耗时 P99 的倍数
+    pub multiplier: f64,
+    /// MinTimeout 动态超时下限
+    pub min_timeout: Duration,
+}
+
+impl Default for AdaptiveTimeout {
+    fn default() -> Self {
+        Self {
+            window: 100,
+            multiplier: 2.0,
+            min_timeout: Duration::from_millis(10),
+        }
+    }
 }
 
 /// Config 连接池配置
@@ -59,11 +226,54 @@ pub struct Config {
     pub mode: PoolMode,
 
     /// MaxConnections 最大连接数，0表示无限制
+    /// 设置 soft_max_connections 时作为突发时允许达到的硬上限
     pub max_connections: usize,
 
+    /// SoftMaxConnections 连接数软上限，0表示不启用（平时即以 max_connections 为上限）
+    /// 平时建连受软上限约束；等待获取连接的线程数达到 burst_waiter_threshold 时允许临时突破到
+    /// max_connections。超出软上限的连接在归还且无人等待时直接关闭，不放回空闲池。
+    /// 启用时 max_connections 必须大于0且不小于该值
+    pub soft_max_connections: usize,
+
+    /// BurstWaiterThreshold 允许突破软上限所需的最少等待线程数
+    /// 默认值为1，即一旦有请求因软上限而等待就允许突发；设置 soft_max_connections 时必须大于0
+    pub burst_waiter_threshold: usize,
+
+    /// MaxTCPConnections TCP 连接数上限，0表示继承 max_connections
+    /// 与 max_connections 同时生效，用于避免混合池中某一协议占满全部额度
+    /// 指定协议的获取在建连前检查；未指定协议的 get() 只能在建连后按实际协议检查，
+    /// 超限的新连接会被立即关闭
+    pub max_tcp_connections: usize,
+
+    /// MaxUDPConnections UDP 连接数上限，0表示继承 max_connections
+    pub max_udp_connections: usize,
+
+    /// MaxNewConnectionsPerSec 每秒最多新建的连接数（令牌桶限速），0表示不限制
+    /// 超过速率时 get 不再调用 Dialer，而是在超时时间内等待空闲连接归还；
+    /// 预热与后台补建同样受此限制，等待令牌补充后再建连
+    pub max_new_connections_per_sec: usize,
+
+    /// MaxConcurrentDials 同时进行的建连数上限（信号量），0表示不限制
+    /// 已有这么多建连在进行时 get 不再调用 Dialer，而是在超时时间内等待其它建连完成或空闲连接归还
+    pub max_concurrent_dials: usize,
+
+    /// FdBudget 进程文件描述符预算：进程已打开的描述符数达到预算时拒绝新建连接，
+    /// 返回 FdBudgetExceeded 而不是让 connect 报 EMFILE。默认值为0，表示不限制
+    pub fd_budget: usize,
+
+    /// FdBudgetRatio 按 RLIMIT_NOFILE 软限制的比例设置描述符预算，取值范围 (0.0, 1.0]
+    /// 在 Pool::new 时读取一次软限制；与 fd_budget 同时设置时取较小者。
+    /// 读取不到软限制（不限制或平台不支持）时不生效。默认值为None
+    pub fd_budget_ratio: Option<f64>,
+
     /// MinConnections 最小连接数（预热连接数）
     pub min_connections: usize,
 
+    /// WarmRatio 按 max_connections 比例预热，取值范围 [0.0, 1.0]
+    /// 预热目标为 (max_connections * warm_ratio).round() 与 min_connections 中的较大者；
+    /// max_connections 为 0（不限制）时比例不生效。默认值为None，表示只按 min_connections 预热
+    pub warm_ratio: Option<f64>,
+
     /// MaxIdleConnections 最大空闲连接数
     pub max_idle_connections: usize,
 
@@ -71,17 +281,86 @@ pub struct Config {
     pub connection_timeout: Duration,
 
     /// IdleTimeout 空闲连接超时时间，超过此时间的空闲连接将被关闭
+    /// 0 表示不限制（空闲连接永不因空闲而过期）
     pub idle_timeout: Duration,
 
     /// MaxLifetime 连接最大生命周期，超过此时间的连接将被关闭
+    /// 0 表示不限制（连接永不因存活时间而过期）
     pub max_lifetime: Duration,
 
+    /// LifetimeJitter 连接生命周期抖动比例，取值范围 [0.0, 1.0)
+    /// 每条连接的有效 max_lifetime 在 max_lifetime * (1 ± lifetime_jitter) 之间随机分布，
+    /// 避免同时建立的连接同时过期。默认值为0（不抖动）
+    pub lifetime_jitter: f64,
+
+    /// IdleProbeAfter 空闲超过该时长的 TCP（含 TLS）连接由 reaper 做半关闭探测
+    /// 以非阻塞 peek 检查对端是否已关闭（读到 EOF 或出错），是则在下次借出前回收，
+    /// 避免借到对端早已 FIN 的连接、直到写入才报 BrokenPipe。默认值为0，表示不探测
+    pub idle_probe_after: Duration,
+
+    /// PeekOnBorrow 借出空闲的 TCP（含 TLS）连接前以非阻塞 peek 探测对端是否已关闭
+    /// test_on_borrow 的轻量替代：不发送验证查询，只跳过已收到 FIN/RST 的连接。默认值为false
+    pub peek_on_borrow: bool,
+
+    /// MaxBytesPerConn 单连接生命周期内累计读写字节上限，达到上限的连接在下次借出前被淘汰
+    /// 仅统计经 PooledConnection 的 Read/Write（含 split 拆分出的读写半）透传的字节，
+    /// 直接操作底层 socket 的读写不计入。默认值为0，表示不限制
+    pub max_bytes_per_conn: u64,
+
+    /// UDPRebindAfterReuses UDP 连接复用次数达到该值后，归还时关闭并由后台线程新建一条（换新本地端口）代替放回
+    /// 用于规避 NAT/防火墙会话老化导致长期复用同一本地端口的回包被丢。默认值为0，表示不按次数重建
+    pub udp_rebind_after_reuses: usize,
+
+    /// UDPRebindAfter UDP 连接存活超过该时长后，归还时关闭并由后台线程新建一条代替放回
+    /// 应小于 NAT 会话老化时间；默认值为0，表示不按时长重建
+    pub udp_rebind_after: Duration,
+
     /// GetConnectionTimeout 获取连接的超时时间
     pub get_connection_timeout: Duration,
 
+    /// AdaptiveTimeout 自适应获取超时（可选）
+    /// 设置后默认 get 等待建连许可（并发建连已满、限速、暂停建连）的上限跟随近期建连耗时的 P99
+    /// 动态调整，get_connection_timeout 作为上限；等待连接归还不受影响。
+    /// 默认值为None，表示始终使用 get_connection_timeout
+    pub adaptive_timeout: Option<AdaptiveTimeout>,
+
+    /// MaxWaitQueue 同时等待获取连接的最大线程数（背压）
+    /// 池满需要等待时，等待者已达上限的 get 立即返回 QueueFull 而不排队；
+    /// 默认值为0，表示不限制
+    pub max_wait_queue: usize,
+
+    /// WaitPollInterval get 在池内等待时的最长单次等待片
+    /// 长等待被切成若干片，每片醒来重试一次空闲连接/建连，作为归还通知丢失时的兜底。
+    /// 默认值为None，表示一次等待到剩余超时（完全依赖归还通知唤醒）
+    pub wait_poll_interval: Option<Duration>,
+
+    /// SlowGetThreshold 慢获取软阈值：get 等待超过该时间（尚未到超时）时调用 on_slow_get
+    /// 默认值为0；设置 on_slow_get 时必须大于0
+    pub slow_get_threshold: Duration,
+
+    /// OnSlowGet 慢获取回调，每次 get 至多触发一次，在等待中的 get 线程上同步执行，
+    /// 可用于打点或触发降级；回调耗时计入该次 get 的等待时间。默认值为None
+    pub on_slow_get: Option<Box<SlowGetCallback>>,
+
+    /// EventSink 连接池事件流（Created/Borrowed/Returned/Closed/Leaked/HealthFailed 等）
+    /// 使用 `mpsc::sync_channel` 创建的有界 channel；channel 已满时丢弃事件而不阻塞，
+    /// 丢弃数见 `Pool::dropped_events`。默认值为None
+    pub event_sink: Option<SyncSender<PoolEvent>>,
+
+    /// PriorityAging get_with_priority 的优先级老化周期
+    /// 等待者每等待一个周期有效优先级提升一级，防止低优先级请求饿死；
+    /// 为0表示不老化。默认值为1秒
+    pub priority_aging: Duration,
+
     /// HealthCheckInterval 健康检查间隔
     pub health_check_interval: Duration,
 
+    /// ReaperInterval 后台清理线程（idle/lifetime/泄漏回收）的运行间隔
+    /// 与健康检查频率解耦：每轮只对距上次检查超过 HealthCheckInterval 的连接做健康检查，
+    /// 每条连接的实际检查间隔落在 [HealthCheckInterval, HealthCheckInterval + ReaperInterval) 内。
+    /// 默认值为None，取 HealthCheckInterval 的 1/4 且不超过1秒（HealthCheckInterval 为0时取1秒）
+    pub reaper_interval: Option<Duration>,
+
     /// HealthCheckTimeout 健康检查超时时间
     pub health_check_timeout: Duration,
 
@@ -89,10 +368,47 @@ pub struct Config {
     /// 如果连接在此时间内未归还，将触发泄漏警告
     pub connection_leak_timeout: Duration,
 
+    /// ForceReclaimOnLeak 严格泄漏回收模式
+    /// 默认情况下，连接泄漏超过 ConnectionLeakTimeout 的 2 倍才会被强制驱逐；
+    /// 启用后，超过 ConnectionLeakTimeout 即由 reaper 移除连接并关闭底层 socket，
+    /// 持有者后续的 IO 会报错，容量立即归还给连接池。默认值为false
+    pub force_reclaim_on_leak: bool,
+
+    /// ShutdownTimeout close() 等待在用连接归还的最长时间
+    /// 超时后强制关闭仍在使用的连接；为0表示不等待。
+    /// 默认值为None，回退使用 ConnectionLeakTimeout（兼容旧行为）
+    pub shutdown_timeout: Option<Duration>,
+
+    /// VerifyOnClose close() 结束时校验资源是否全部释放（调试用）
+    /// 要求连接表为空、空闲计数全零、活跃计数为零，不满足时记录警告并返回 CloseVerificationFailed。
+    /// 默认值为false
+    pub verify_on_close: bool,
+
     /// Dialer 连接创建函数（客户端模式必需）
     /// 在客户端模式下，用于主动创建连接到服务器
     pub dialer: Option<Dialer>,
 
+    /// LabeledDialer 带标签的连接创建函数（可选）
+    /// 设置后优先于 Dialer 使用，返回的标签通过 `Connection::label` 读取，复用时保留
+    pub labeled_dialer: Option<LabeledDialer>,
+
+    /// RoleDialer 按角色创建连接的函数（可选）
+    /// `Pool::get_role` 需要新建连接时调用，创建的连接标注为请求的角色；
+    /// 普通 get 与预热仍使用 Dialer / LabeledDialer
+    pub role_dialer: Option<RoleDialer>,
+
+    /// ShadowDialer 影子连接的创建函数（客户端模式可选）
+    /// 设置后连接池内部维护一个独立的影子连接池，`Pool::get_with_shadow` 在借出主连接的同时
+    /// 附带一条影子连接，用于灰度/对拍时把请求复制到另一个后端。影子池沿用本配置的容量与超时，
+    /// 但不预热、不启用回调与事件。默认值为None
+    pub shadow_dialer: Option<Dialer>,
+
+    /// FallbackDialer 降级连接的创建函数（客户端模式可选）
+    /// 主 Dialer（含 LabeledDialer / RoleDialer）建连失败时改用它建连（如只读副本、备用区域），
+    /// 建立的连接通过 `Connection::is_fallback` 标记为降级连接，归还后照常复用。
+    /// 降级建连也失败时返回主 Dialer 的错误。默认值为None
+    pub fallback_dialer: Option<Dialer>,
+
     /// Listener 网络监听器（服务器端模式必需）
     /// 在服务器端模式下，用于接受客户端连接
     pub listener: Option<std::net::TcpListener>,
@@ -106,19 +422,97 @@ pub struct Config {
     /// 如果为None，将使用默认的ping检查
     pub health_checker: Option<HealthChecker>,
 
+    /// HealthCheckerWithReason 带失败原因的健康检查函数（可选）
+    /// 设置后优先于 health_checker 使用，失败原因会计入统计并传给 on_health_check_failure
+    pub health_checker_with_reason: Option<HealthCheckerWithReason>,
+
+    /// OnHealthCheckFailure 健康检查失败时调用，可用于记录失败原因
+    pub on_health_check_failure: Option<Box<HealthCheckFailureCallback>>,
+
+    /// HealthCheckConcurrency 单轮健康检查的最大并行度
+    /// 默认值为1（在 reaper 线程中串行检查）；大于1时使用有界工作线程并行检查，
+    /// 适用于检查本身涉及网络往返的场景，同时限制对后端的瞬时压力
+    pub health_check_concurrency: usize,
+
+    /// ValidationQuery 内置验证往返（请求, 期望响应）
+    /// 发送请求后读取与期望响应等长的数据并比对，不一致或超时即判定连接失效，
+    /// 超时使用 health_check_timeout。需配合 test_on_borrow / test_while_idle 使用
+    pub validation_query: Option<(Vec<u8>, Vec<u8>)>,
+
+    /// TestOnBorrow 借出空闲连接前执行 validation_query，失败则丢弃该连接
+    /// 默认值为false
+    pub test_on_borrow: bool,
+
+    /// TestWhileIdle 后台健康检查时对空闲连接执行 validation_query
+    /// 与 health_checker 同时配置时两者都需通过；默认值为false
+    pub test_while_idle: bool,
+
+    /// ReplaceEvictedConnections 是否对被清理的空闲连接进行热替换
+    /// 启用后，reaper 因健康检查失败或过期移除空闲连接时，若连接总数低于 min_connections
+    /// 或该连接所在的桶已无空闲连接，会用 Dialer 补建一条同类型连接放回空闲队列。
+    /// 补建在后台建连线程中进行，不会阻塞 reaper。仅客户端模式生效，默认值为false
+    pub replace_evicted_connections: bool,
+
+    /// FailFastOnNew Pool::new 时是否立即用 Dialer 预检一条连接
+    /// 启用后，Pool::new 会同步创建一条连接并放入空闲池，创建失败则直接返回错误，
+    /// 避免把错误的 Dialer 配置带到运行时。仅客户端模式生效，默认值为false
+    pub fail_fast_on_new: bool,
+
+    /// LazyInit 是否延迟启动后台线程
+    /// 启用后 Pool::new 不启动 reaper、预热、异步归还与统计上报线程，直到第一次 get
+    /// 时才启动（只启动一次），适合创建后可能根本不用的连接池。默认值为false
+    pub lazy_init: bool,
+
     /// CloseConn 连接关闭函数（可选）
-    /// 如果为None，将尝试关闭连接
+    /// 在默认关闭逻辑之前调用；即使回调返回错误，仍会执行默认关闭（TCP shutdown），
+    /// 确保底层 socket 真正关闭；回调失败计入 close_callback_failures 统计
     pub close_conn: Option<Box<CloseConnCallback>>,
 
     /// OnCreated 连接创建后调用
     pub on_created: Option<Box<OnCreatedCallback>>,
 
+    /// OnAcquire 每次借出前调用，用于把连接重置到干净状态（如发送 RESET）
+    /// 返回错误时丢弃该连接，继续尝试下一条空闲连接或新建连接
+    pub on_acquire: Option<Box<OnAcquireCallback>>,
+
     /// OnBorrow 连接从池中取出前调用
     pub on_borrow: Option<Box<BorrowReturnCallback>>,
 
     /// OnReturn 连接归还池中前调用
     pub on_return: Option<Box<BorrowReturnCallback>>,
 
+    /// OnStats 周期性统计回调（推送式指标上报）
+    /// 设置后由独立的后台线程每隔 stats_interval 采样一次 Stats 并调用；
+    /// 要求 enable_stats 为 true，否则配置校验失败
+    pub on_stats: Option<Box<StatsCallback>>,
+
+    /// StatsInterval on_stats 的采样间隔
+    /// 默认值为10秒；设置 on_stats 时不能为0
+    pub stats_interval: Duration,
+
+    /// StatsEwmaWindow Stats::smoothed 中 EWMA 平滑指标的时间窗口（时间常数）
+    /// 窗口越大越平稳、对变化的响应越慢；默认值为10秒，不能为0
+    pub stats_ewma_window: Duration,
+
+    /// HealthWindow `Pool::health` 统计失败率与耗尽次数的窗口周期
+    /// 由 reaper 每个周期推进一次窗口，health() 只读取、不改变窗口，
+    /// 统计范围为最近 1～2 个周期。默认值为30秒，不能为0
+    pub health_window: Duration,
+
+    /// OnHighWatermark 容量水位告警（阈值比例, 回调）
+    /// current_connections / max_connections 由低于阈值升到阈值及以上时调用一次，
+    /// 回落到阈值以下后才会再次触发（边沿触发去抖）。阈值范围 (0, 1]；
+    /// 要求 enable_stats 为 true 且 max_connections 大于0
+    pub on_high_watermark: Option<(f64, Box<WatermarkCallback>)>,
+
+    /// MinAvailability 最小可用度：可用连接数（空闲连接 + 剩余可新建额度）低于该值时进入降级态
+    /// 降级态下 get_sheddable 快速失败、健康检查频率加倍，可用连接数回到该值及以上时退出。
+    /// 默认值为0，表示不启用；启用时 max_connections 必须大于0且不小于该值
+    pub min_availability: usize,
+
+    /// OnDegradation 进入/退出降级态时调用（边沿触发），需要同时设置 min_availability。默认值为None
+    pub on_degradation: Option<Box<DegradationCallback>>,
+
     /// EnableStats 是否启用统计信息
     pub enable_stats: bool,
 
@@ -137,6 +531,31 @@ pub struct Config {
     /// MaxBufferClearPackets UDP缓冲区清理最大包数
     /// 默认值: 100
     pub max_buffer_clear_packets: usize,
+
+    /// AsyncReturn 是否异步归还连接
+    /// 启用后持有者 Drop 时只切换状态并入队，有效性校验、UDP 缓冲区清理、on_return
+    /// 与关闭失效连接由后台线程完成，不阻塞业务线程。默认值为false
+    pub async_return: bool,
+
+    /// TcpNodelay 是否为 TCP 连接设置 TCP_NODELAY（禁用 Nagle 算法）
+    /// 默认值为false，保持系统默认行为
+    pub tcp_nodelay: bool,
+
+    /// TcpKeepalive TCP keepalive 空闲探测时间
+    /// 为 None 时不启用 keepalive；启用后可让长时间空闲的连接及时发现被中间设备断开
+    pub tcp_keepalive: Option<Duration>,
+
+    /// DefaultReadTimeout 连接默认读超时
+    /// 建连及每次从空闲池借出时应用到底层 socket，避免忘记设置超时导致读操作永久阻塞；
+    /// 借用期间调用方修改的超时会在下次借出时恢复为该默认值。None 表示不设置（默认）
+    pub default_read_timeout: Option<Duration>,
+
+    /// DefaultWriteTimeout 连接默认写超时，语义同 DefaultReadTimeout。None 表示不设置（默认）
+    pub default_write_timeout: Option<Duration>,
+
+    /// SocketOptions 建连后统一应用的 socket 选项（SO_RCVBUF/SO_SNDBUF/SO_LINGER/IP_TOS 等）
+    /// 默认全部保持系统默认值
+    pub socket_options: SocketOptions,
 }
 
 impl Default for Config {
@@ -150,26 +569,97 @@ impl std::fmt::Debug for Config {
         f.debug_struct("Config")
             .field("mode", &self.mode)
             .field("max_connections", &self.max_connections)
+            .field("soft_max_connections", &self.soft_max_connections)
+            .field("burst_waiter_threshold", &self.burst_waiter_threshold)
+            .field("max_tcp_connections", &self.max_tcp_connections)
+            .field("max_udp_connections", &self.max_udp_connections)
+            .field(
+                "max_new_connections_per_sec",
+                &self.max_new_connections_per_sec,
+            )
+            .field("max_concurrent_dials", &self.max_concurrent_dials)
+            .field("fd_budget", &self.fd_budget)
+            .field("fd_budget_ratio", &self.fd_budget_ratio)
             .field("min_connections", &self.min_connections)
+            .field("warm_ratio", &self.warm_ratio)
             .field("max_idle_connections", &self.max_idle_connections)
             .field("connection_timeout", &self.connection_timeout)
             .field("idle_timeout", &self.idle_timeout)
             .field("max_lifetime", &self.max_lifetime)
+            .field("lifetime_jitter", &self.lifetime_jitter)
+            .field("idle_probe_after", &self.idle_probe_after)
+            .field("peek_on_borrow", &self.peek_on_borrow)
+            .field("max_bytes_per_conn", &self.max_bytes_per_conn)
+            .field("udp_rebind_after_reuses", &self.udp_rebind_after_reuses)
+            .field("udp_rebind_after", &self.udp_rebind_after)
             .field("get_connection_timeout", &self.get_connection_timeout)
+            .field("adaptive_timeout", &self.adaptive_timeout)
+            .field("max_wait_queue", &self.max_wait_queue)
+            .field("wait_poll_interval", &self.wait_poll_interval)
+            .field("slow_get_threshold", &self.slow_get_threshold)
+            .field("on_slow_get", &self.on_slow_get.as_ref().map(|_| "..."))
+            .field("event_sink", &self.event_sink.as_ref().map(|_| "..."))
+            .field("priority_aging", &self.priority_aging)
             .field("health_check_interval", &self.health_check_interval)
+            .field("reaper_interval", &self.reaper_interval)
             .field("health_check_timeout", &self.health_check_timeout)
             .field("connection_leak_timeout", &self.connection_leak_timeout)
+            .field("force_reclaim_on_leak", &self.force_reclaim_on_leak)
+            .field("shutdown_timeout", &self.shutdown_timeout)
+            .field("verify_on_close", &self.verify_on_close)
             .field("dialer", &self.dialer.as_ref().map(|_| "..."))
+            .field(
+                "labeled_dialer",
+                &self.labeled_dialer.as_ref().map(|_| "..."),
+            )
+            .field("role_dialer", &self.role_dialer.as_ref().map(|_| "..."))
+            .field("shadow_dialer", &self.shadow_dialer.as_ref().map(|_| "..."))
+            .field(
+                "fallback_dialer",
+                &self.fallback_dialer.as_ref().map(|_| "..."),
+            )
             .field("listener", &self.listener)
             .field("acceptor", &self.acceptor.as_ref().map(|_| "..."))
             .field(
                 "health_checker",
                 &self.health_checker.as_ref().map(|_| "..."),
             )
+            .field(
+                "health_checker_with_reason",
+                &self.health_checker_with_reason.as_ref().map(|_| "..."),
+            )
+            .field(
+                "on_health_check_failure",
+                &self.on_health_check_failure.as_ref().map(|_| "..."),
+            )
+            .field("health_check_concurrency", &self.health_check_concurrency)
+            .field("validation_query", &self.validation_query)
+            .field("test_on_borrow", &self.test_on_borrow)
+            .field("test_while_idle", &self.test_while_idle)
+            .field(
+                "replace_evicted_connections",
+                &self.replace_evicted_connections,
+            )
+            .field("fail_fast_on_new", &self.fail_fast_on_new)
+            .field("lazy_init", &self.lazy_init)
             .field("close_conn", &self.close_conn.as_ref().map(|_| "..."))
             .field("on_created", &self.on_created.as_ref().map(|_| "..."))
+            .field("on_acquire", &self.on_acquire.as_ref().map(|_| "..."))
             .field("on_borrow", &self.on_borrow.as_ref().map(|_| "..."))
             .field("on_return", &self.on_return.as_ref().map(|_| "..."))
+            .field("on_stats", &self.on_stats.as_ref().map(|_| "..."))
+            .field(
+                "on_high_watermark",
+                &self.on_high_watermark.as_ref().map(|(ratio, _)| ratio),
+            )
+            .field("min_availability", &self.min_availability)
+            .field(
+                "on_degradation",
+                &self.on_degradation.as_ref().map(|_| "..."),
+            )
+            .field("stats_interval", &self.stats_interval)
+            .field("stats_ewma_window", &self.stats_ewma_window)
+            .field("health_window", &self.health_window)
             .field("enable_stats", &self.enable_stats)
             .field("enable_health_check", &self.enable_health_check)
             .field(
@@ -178,6 +668,12 @@ impl std::fmt::Debug for Config {
             )
             .field("udp_buffer_clear_timeout", &self.udp_buffer_clear_timeout)
             .field("max_buffer_clear_packets", &self.max_buffer_clear_packets)
+            .field("async_return", &self.async_return)
+            .field("tcp_nodelay", &self.tcp_nodelay)
+            .field("tcp_keepalive", &self.tcp_keepalive)
+            .field("default_read_timeout", &self.default_read_timeout)
+            .field("default_write_timeout", &self.default_write_timeout)
+            .field("socket_options", &self.socket_options)
             .finish()
     }
 }
@@ -198,28 +694,81 @@ impl Config {
         Self {
             mode: PoolMode::Client,
             max_connections: 10,
+            soft_max_connections: 0,
+            burst_waiter_threshold: 1,
+            max_tcp_connections: 0,
+            max_udp_connections: 0,
+            max_new_connections_per_sec: 0,
+            max_concurrent_dials: 0,
+            fd_budget: 0,
+            fd_budget_ratio: None,
             min_connections: 2,
+            warm_ratio: None,
             max_idle_connections: 10,
             connection_timeout: Duration::from_secs(10),
             idle_timeout: Duration::from_secs(5 * 60),
             max_lifetime: Duration::from_secs(30 * 60),
+            lifetime_jitter: 0.0,
+            idle_probe_after: Duration::ZERO,
+            peek_on_borrow: false,
+            max_bytes_per_conn: 0,
+            udp_rebind_after_reuses: 0,
+            udp_rebind_after: Duration::ZERO,
             get_connection_timeout: Duration::from_secs(5),
+            adaptive_timeout: None,
+            max_wait_queue: 0,
+            wait_poll_interval: None,
+            slow_get_threshold: Duration::ZERO,
+            on_slow_get: None,
+            event_sink: None,
+            priority_aging: Duration::from_secs(1),
             health_check_interval: Duration::from_secs(30),
+            reaper_interval: None,
             health_check_timeout: Duration::from_secs(3),
             connection_leak_timeout: Duration::from_secs(5 * 60),
+            force_reclaim_on_leak: false,
+            shutdown_timeout: None,
+            verify_on_close: false,
             dialer: None,
+            labeled_dialer: None,
+            role_dialer: None,
+            shadow_dialer: None,
+            fallback_dialer: None,
             listener: None,
             acceptor: None,
             health_checker: None,
+            health_checker_with_reason: None,
+            on_health_check_failure: None,
+            health_check_concurrency: 1,
+            validation_query: None,
+            test_on_borrow: false,
+            test_while_idle: false,
+            replace_evicted_connections: false,
+            fail_fast_on_new: false,
+            lazy_init: false,
             close_conn: None,
             on_created: None,
+            on_acquire: None,
             on_borrow: None,
             on_return: None,
+            on_stats: None,
+            on_high_watermark: None,
+            min_availability: 0,
+            on_degradation: None,
+            stats_interval: Duration::from_secs(10),
+            stats_ewma_window: Duration::from_secs(10),
+            health_window: Duration::from_secs(30),
             enable_stats: true,
             enable_health_check: true,
             clear_udp_buffer_on_return: true,
             udp_buffer_clear_timeout: Duration::from_millis(100),
             max_buffer_clear_packets: 100,
+            async_return: false,
+            tcp_nodelay: false,
+            tcp_keepalive: None,
+            default_read_timeout: None,
+            default_write_timeout: None,
+            socket_options: SocketOptions::default(),
         }
     }
 
@@ -230,26 +779,7 @@ impl Config {
             max_connections: 100, // 服务器端通常需要更多连接
             min_connections: 0,   // 服务器端通常不需要预热
             max_idle_connections: 50,
-            connection_timeout: Duration::from_secs(10),
-            idle_timeout: Duration::from_secs(5 * 60),
-            max_lifetime: Duration::from_secs(30 * 60),
-            get_connection_timeout: Duration::from_secs(5),
-            health_check_interval: Duration::from_secs(30),
-            health_check_timeout: Duration::from_secs(3),
-            connection_leak_timeout: Duration::from_secs(5 * 60),
-            dialer: None,
-            listener: None,
-            acceptor: None,
-            health_checker: None,
-            close_conn: None,
-            on_created: None,
-            on_borrow: None,
-            on_return: None,
-            enable_stats: true,
-            enable_health_check: true,
-            clear_udp_buffer_on_return: true,
-            udp_buffer_clear_timeout: Duration::from_millis(100),
-            max_buffer_clear_packets: 100,
+            ..Self::default_config()
         }
     }
 
@@ -258,8 +788,11 @@ impl Config {
         // 根据模式验证必需的配置
         match self.mode {
             PoolMode::Client => {
-                // 客户端模式需要Dialer
-                if self.dialer.is_none() {
+                // 客户端模式需要Dialer（或带标签、按角色的 Dialer）
+                if self.dialer.is_none()
+                    && self.labeled_dialer.is_none()
+                    && self.role_dialer.is_none()
+                {
                     return Err(NetConnPoolError::InvalidConfig {
                         reason: "客户端模式需要 Dialer".to_string(),
                     });
@@ -272,9 +805,44 @@ impl Config {
                         reason: "服务器端模式需要 Listener".to_string(),
                     });
                 }
+                if self.shadow_dialer.is_some() {
+                    return Err(NetConnPoolError::InvalidConfig {
+                        reason: "shadow_dialer 仅支持客户端模式".to_string(),
+                    });
+                }
+                if self.fallback_dialer.is_some() {
+                    return Err(NetConnPoolError::InvalidConfig {
+                        reason: "fallback_dialer 仅支持客户端模式".to_string(),
+                    });
+                }
             }
         }
 
+        self.validate_limits()
+    }
+
+    /// 由 shadow_dialer 构造影子连接池的配置：沿用容量与超时，不预热、不带回调
+    pub(crate) fn take_shadow_config(&mut self) -> Option<Config> {
+        let dialer = self.shadow_dialer.take()?;
+        Some(Config {
+            dialer: Some(dialer),
+            max_connections: self.max_connections,
+            max_idle_connections: self.max_idle_connections,
+            min_connections: 0,
+            connection_timeout: self.connection_timeout,
+            idle_timeout: self.idle_timeout,
+            max_lifetime: self.max_lifetime,
+            get_connection_timeout: self.get_connection_timeout,
+            default_read_timeout: self.default_read_timeout,
+            default_write_timeout: self.default_write_timeout,
+            socket_options: self.socket_options.clone(),
+            enable_stats: self.enable_stats,
+            ..Self::default_config()
+        })
+    }
+
+    /// 校验与模式无关的容量、超时等配置（同步与异步连接池共用）
+    pub(crate) fn validate_limits(&self) -> Result<()> {
         if self.min_connections > 0
             && self.max_connections > 0
             && self.min_connections > self.max_connections
@@ -286,6 +854,19 @@ impl Config {
                 ),
             });
         }
+        for (name, limit) in [
+            ("max_tcp_connections", self.max_tcp_connections),
+            ("max_udp_connections", self.max_udp_connections),
+        ] {
+            if limit > 0 && self.max_connections > 0 && limit > self.max_connections {
+                return Err(NetConnPoolError::InvalidConfig {
+                    reason: format!(
+                        "{} ({}) 不能大于 max_connections ({})",
+                        name, limit, self.max_connections
+                    ),
+                });
+            }
+        }
         if self.max_idle_connections == 0 {
             return Err(NetConnPoolError::InvalidConfig {
                 reason: "max_idle_connections 必须大于 0".to_string(),
@@ -296,6 +877,16 @@ impl Config {
                 reason: "connection_timeout 必须大于 0".to_string(),
             });
         }
+        for (name, timeout) in [
+            ("default_read_timeout", self.default_read_timeout),
+            ("default_write_timeout", self.default_write_timeout),
+        ] {
+            if timeout.is_some_and(|t| t.is_zero()) {
+                return Err(NetConnPoolError::InvalidConfig {
+                    reason: format!("{} 必须大于 0（不设置请使用 None）", name),
+                });
+            }
+        }
 
         // 添加更多验证
         if self.max_idle_connections > 0
@@ -310,7 +901,8 @@ impl Config {
             });
         }
 
-        if self.idle_timeout > self.max_lifetime {
+        // max_lifetime 为 0 表示不限制生命周期，此时任意 idle_timeout 都合法
+        if !self.max_lifetime.is_zero() && self.idle_timeout > self.max_lifetime {
             return Err(NetConnPoolError::InvalidConfig {
                 reason: format!(
                     "idle_timeout ({:?}) 不能大于 max_lifetime ({:?})",
@@ -319,6 +911,56 @@ impl Config {
             });
         }
 
+        if let Some(adaptive) = &self.adaptive_timeout {
+            if adaptive.window == 0 {
+                return Err(NetConnPoolError::InvalidConfig {
+                    reason: "adaptive_timeout.window 必须大于 0".to_string(),
+                });
+            }
+            if !(adaptive.multiplier.is_finite() && adaptive.multiplier > 0.0) {
+                return Err(NetConnPoolError::InvalidConfig {
+                    reason: format!(
+                        "adaptive_timeout.multiplier ({}) 必须为正数",
+                        adaptive.multiplier
+                    ),
+                });
+            }
+            if adaptive.min_timeout.is_zero() || adaptive.min_timeout > self.get_connection_timeout
+            {
+                return Err(NetConnPoolError::InvalidConfig {
+                    reason: format!(
+                        "adaptive_timeout.min_timeout ({:?}) 必须大于 0 且不大于 get_connection_timeout ({:?})",
+                        adaptive.min_timeout, self.get_connection_timeout
+                    ),
+                });
+            }
+        }
+
+        if let Some(ratio) = self.fd_budget_ratio {
+            if !(ratio > 0.0 && ratio <= 1.0) {
+                return Err(NetConnPoolError::InvalidConfig {
+                    reason: format!("fd_budget_ratio ({}) 必须在 (0.0, 1.0] 范围内", ratio),
+                });
+            }
+        }
+
+        if let Some(ratio) = self.warm_ratio {
+            if !(0.0..=1.0).contains(&ratio) {
+                return Err(NetConnPoolError::InvalidConfig {
+                    reason: format!("warm_ratio ({}) 必须在 [0.0, 1.0] 范围内", ratio),
+                });
+            }
+        }
+
+        if !(0.0..1.0).contains(&self.lifetime_jitter) {
+            return Err(NetConnPoolError::InvalidConfig {
+                reason: format!(
+                    "lifetime_jitter ({}) 必须在 [0.0, 1.0) 范围内",
+                    self.lifetime_jitter
+                ),
+            });
+        }
+
         if self.health_check_timeout > self.health_check_interval {
             return Err(NetConnPoolError::InvalidConfig {
                 reason: format!(
@@ -327,6 +969,116 @@ impl Config {
                 ),
             });
         }
+
+        if self
+            .reaper_interval
+            .is_some_and(|interval| interval.is_zero())
+        {
+            return Err(NetConnPoolError::InvalidConfig {
+                reason: "reaper_interval 必须大于0".to_string(),
+            });
+        }
+
+        if (self.test_on_borrow || self.test_while_idle) && self.validation_query.is_none() {
+            return Err(NetConnPoolError::InvalidConfig {
+                reason: "启用 test_on_borrow / test_while_idle 时必须设置 validation_query"
+                    .to_string(),
+            });
+        }
+
+        if self.on_stats.is_some() {
+            if !self.enable_stats {
+                return Err(NetConnPoolError::InvalidConfig {
+                    reason: "设置 on_stats 时必须启用 enable_stats".to_string(),
+                });
+            }
+            if self.stats_interval.is_zero() {
+                return Err(NetConnPoolError::InvalidConfig {
+                    reason: "设置 on_stats 时 stats_interval 必须大于0".to_string(),
+                });
+            }
+        }
+
+        if self.stats_ewma_window.is_zero() {
+            return Err(NetConnPoolError::InvalidConfig {
+                reason: "stats_ewma_window 必须大于0".to_string(),
+            });
+        }
+
+        if self.health_window.is_zero() {
+            return Err(NetConnPoolError::InvalidConfig {
+                reason: "health_window 必须大于0".to_string(),
+            });
+        }
+
+        if self.on_slow_get.is_some() && self.slow_get_threshold.is_zero() {
+            return Err(NetConnPoolError::InvalidConfig {
+                reason: "设置 on_slow_get 时 slow_get_threshold 必须大于0".to_string(),
+            });
+        }
+
+        if self.min_availability > 0 {
+            if self.max_connections == 0 {
+                return Err(NetConnPoolError::InvalidConfig {
+                    reason: "设置 min_availability 时 max_connections 必须大于0".to_string(),
+                });
+            }
+            if self.min_availability > self.max_connections {
+                return Err(NetConnPoolError::InvalidConfig {
+                    reason: format!(
+                        "min_availability ({}) 不能大于 max_connections ({})",
+                        self.min_availability, self.max_connections
+                    ),
+                });
+            }
+        } else if self.on_degradation.is_some() {
+            return Err(NetConnPoolError::InvalidConfig {
+                reason: "设置 on_degradation 时必须设置 min_availability".to_string(),
+            });
+        }
+
+        if self.soft_max_connections > 0 {
+            if self.max_connections == 0 || self.soft_max_connections > self.max_connections {
+                return Err(NetConnPoolError::InvalidConfig {
+                    reason: format!(
+                        "soft_max_connections ({}) 要求 max_connections ({}) 大于0且不小于它",
+                        self.soft_max_connections, self.max_connections
+                    ),
+                });
+            }
+            if self.min_connections > self.soft_max_connections {
+                return Err(NetConnPoolError::InvalidConfig {
+                    reason: format!(
+                        "min_connections ({}) 不能大于 soft_max_connections ({})",
+                        self.min_connections, self.soft_max_connections
+                    ),
+                });
+            }
+            if self.burst_waiter_threshold == 0 {
+                return Err(NetConnPoolError::InvalidConfig {
+                    reason: "设置 soft_max_connections 时 burst_waiter_threshold 必须大于0"
+                        .to_string(),
+                });
+            }
+        }
+
+        if let Some((ratio, _)) = &self.on_high_watermark {
+            if !(*ratio > 0.0 && *ratio <= 1.0) {
+                return Err(NetConnPoolError::InvalidConfig {
+                    reason: format!("on_high_watermark 阈值 ({}) 必须在 (0, 1] 范围内", ratio),
+                });
+            }
+            if !self.enable_stats {
+                return Err(NetConnPoolError::InvalidConfig {
+                    reason: "设置 on_high_watermark 时必须启用 enable_stats".to_string(),
+                });
+            }
+            if self.max_connections == 0 {
+                return Err(NetConnPoolError::InvalidConfig {
+                    reason: "设置 on_high_watermark 时 max_connections 必须大于0".to_string(),
+                });
+            }
+        }
         Ok(())
     }
 
@@ -349,6 +1101,31 @@ impl Config {
         if self.max_buffer_clear_packets == 0 {
             self.max_buffer_clear_packets = 100;
         }
+        if self.health_check_concurrency == 0 {
+            self.health_check_concurrency = 1;
+        }
+    }
+    /// 生效的文件描述符预算：fd_budget 与 RLIMIT_NOFILE * fd_budget_ratio 中的较小者，均未生效时为 None
+    pub fn effective_fd_budget(&self) -> Option<usize> {
+        let fixed = (self.fd_budget > 0).then_some(self.fd_budget);
+        let by_ratio = self
+            .fd_budget_ratio
+            .and_then(|ratio| crate::fd::fd_limit().map(|limit| (limit as f64 * ratio) as usize));
+        match (fixed, by_ratio) {
+            (Some(a), Some(b)) => Some(a.min(b)),
+            (a, b) => a.or(b),
+        }
+    }
+
+    /// 预热目标连接数：min_connections 与 (max_connections * warm_ratio).round() 中的较大者
+    pub fn warm_target(&self) -> usize {
+        let by_ratio = match self.warm_ratio {
+            Some(ratio) if self.max_connections > 0 => {
+                (self.max_connections as f64 * ratio).round() as usize
+            }
+            _ => 0,
+        };
+        self.min_connections.max(by_ratio)
     }
 }
 
@@ -420,12 +1197,66 @@ impl ConfigBuilder {
         self
     }
 
+    /// 设置连接数软上限（0 表示不启用），max_connections 作为突发时的硬上限
+    pub fn soft_max_connections(mut self, soft_max_connections: usize) -> Self {
+        self.config.soft_max_connections = soft_max_connections;
+        self
+    }
+
+    /// 设置允许突破软上限所需的最少等待线程数
+    pub fn burst_waiter_threshold(mut self, burst_waiter_threshold: usize) -> Self {
+        self.config.burst_waiter_threshold = burst_waiter_threshold;
+        self
+    }
+
+    /// 设置 TCP 连接数上限（0 表示继承 max_connections）
+    pub fn max_tcp_connections(mut self, max_tcp_connections: usize) -> Self {
+        self.config.max_tcp_connections = max_tcp_connections;
+        self
+    }
+
+    /// 设置 UDP 连接数上限（0 表示继承 max_connections）
+    pub fn max_udp_connections(mut self, max_udp_connections: usize) -> Self {
+        self.config.max_udp_connections = max_udp_connections;
+        self
+    }
+
+    /// 设置每秒最多新建的连接数（0 表示不限制）
+    pub fn max_new_connections_per_sec(mut self, max_new_connections_per_sec: usize) -> Self {
+        self.config.max_new_connections_per_sec = max_new_connections_per_sec;
+        self
+    }
+
+    /// 设置同时进行的建连数上限（0 表示不限制）
+    pub fn max_concurrent_dials(mut self, max_concurrent_dials: usize) -> Self {
+        self.config.max_concurrent_dials = max_concurrent_dials;
+        self
+    }
+
+    /// 设置进程文件描述符预算（0 表示不限制）
+    pub fn fd_budget(mut self, fd_budget: usize) -> Self {
+        self.config.fd_budget = fd_budget;
+        self
+    }
+
+    /// 设置按 RLIMIT_NOFILE 软限制比例计算的文件描述符预算
+    pub fn fd_budget_ratio(mut self, fd_budget_ratio: f64) -> Self {
+        self.config.fd_budget_ratio = Some(fd_budget_ratio);
+        self
+    }
+
     /// 设置最小连接数（预热连接数）
     pub fn min_connections(mut self, min_connections: usize) -> Self {
         self.config.min_connections = min_connections;
         self
     }
 
+    /// 设置按 max_connections 比例预热的比例
+    pub fn warm_ratio(mut self, warm_ratio: f64) -> Self {
+        self.config.warm_ratio = Some(warm_ratio);
+        self
+    }
+
     /// 设置最大空闲连接数
     pub fn max_idle_connections(mut self, max_idle_connections: usize) -> Self {
         self.config.max_idle_connections = max_idle_connections;
@@ -450,18 +1281,102 @@ impl ConfigBuilder {
         self
     }
 
+    /// 设置连接生命周期抖动比例
+    pub fn lifetime_jitter(mut self, lifetime_jitter: f64) -> Self {
+        self.config.lifetime_jitter = lifetime_jitter;
+        self
+    }
+
+    /// 设置空闲连接半关闭探测的空闲时长阈值（0 表示不探测）
+    pub fn idle_probe_after(mut self, idle_probe_after: Duration) -> Self {
+        self.config.idle_probe_after = idle_probe_after;
+        self
+    }
+
+    /// 设置借出空闲连接前是否 peek 探测对端已关闭
+    pub fn peek_on_borrow(mut self, peek_on_borrow: bool) -> Self {
+        self.config.peek_on_borrow = peek_on_borrow;
+        self
+    }
+
+    /// 设置单连接累计读写字节上限（0 表示不限制）
+    pub fn max_bytes_per_conn(mut self, max_bytes_per_conn: u64) -> Self {
+        self.config.max_bytes_per_conn = max_bytes_per_conn;
+        self
+    }
+
+    /// 设置 UDP 连接复用多少次后在归还时重建（0 表示不按次数重建）
+    pub fn udp_rebind_after_reuses(mut self, udp_rebind_after_reuses: usize) -> Self {
+        self.config.udp_rebind_after_reuses = udp_rebind_after_reuses;
+        self
+    }
+
+    /// 设置 UDP 连接存活多久后在归还时重建（0 表示不按时长重建）
+    pub fn udp_rebind_after(mut self, udp_rebind_after: Duration) -> Self {
+        self.config.udp_rebind_after = udp_rebind_after;
+        self
+    }
+
     /// 设置获取连接的超时时间
     pub fn get_connection_timeout(mut self, get_connection_timeout: Duration) -> Self {
         self.config.get_connection_timeout = get_connection_timeout;
         self
     }
 
+    /// 设置自适应获取超时参数
+    pub fn adaptive_timeout(mut self, adaptive_timeout: AdaptiveTimeout) -> Self {
+        self.config.adaptive_timeout = Some(adaptive_timeout);
+        self
+    }
+
+    /// 设置同时等待获取连接的最大线程数（0 表示不限制）
+    pub fn max_wait_queue(mut self, max_wait_queue: usize) -> Self {
+        self.config.max_wait_queue = max_wait_queue;
+        self
+    }
+
+    /// 设置 get 等待时的最长单次等待片（归还通知丢失时的兜底轮询）
+    pub fn wait_poll_interval(mut self, wait_poll_interval: Duration) -> Self {
+        self.config.wait_poll_interval = Some(wait_poll_interval);
+        self
+    }
+
+    /// 设置慢获取软阈值
+    pub fn slow_get_threshold(mut self, slow_get_threshold: Duration) -> Self {
+        self.config.slow_get_threshold = slow_get_threshold;
+        self
+    }
+
+    /// 设置慢获取回调（get 等待超过 slow_get_threshold 时调用，每次 get 至多一次）
+    pub fn on_slow_get(mut self, on_slow_get: Box<SlowGetCallback>) -> Self {
+        self.config.on_slow_get = Some(on_slow_get);
+        self
+    }
+
+    /// 设置连接池事件流的发送端（使用 `mpsc::sync_channel` 创建）
+    pub fn event_sink(mut self, event_sink: SyncSender<PoolEvent>) -> Self {
+        self.config.event_sink = Some(event_sink);
+        self
+    }
+
+    /// 设置优先级老化周期（0 表示不老化）
+    pub fn priority_aging(mut self, priority_aging: Duration) -> Self {
+        self.config.priority_aging = priority_aging;
+        self
+    }
+
     /// 设置健康检查间隔
     pub fn health_check_interval(mut self, health_check_interval: Duration) -> Self {
         self.config.health_check_interval = health_check_interval;
         self
     }
 
+    /// 设置后台清理线程的运行间隔（与健康检查间隔相互独立）
+    pub fn reaper_interval(mut self, reaper_interval: Duration) -> Self {
+        self.config.reaper_interval = Some(reaper_interval);
+        self
+    }
+
     /// 设置健康检查超时时间
     pub fn health_check_timeout(mut self, health_check_timeout: Duration) -> Self {
         self.config.health_check_timeout = health_check_timeout;
@@ -474,12 +1389,54 @@ impl ConfigBuilder {
         self
     }
 
+    /// 设置是否在连接泄漏超时后立即强制回收（严格模式）
+    pub fn force_reclaim_on_leak(mut self, force_reclaim_on_leak: bool) -> Self {
+        self.config.force_reclaim_on_leak = force_reclaim_on_leak;
+        self
+    }
+
+    /// 设置关闭连接池时等待在用连接归还的最长时间
+    pub fn shutdown_timeout(mut self, shutdown_timeout: Duration) -> Self {
+        self.config.shutdown_timeout = Some(shutdown_timeout);
+        self
+    }
+
+    /// 设置是否在关闭连接池后校验资源全部释放（调试用）
+    pub fn verify_on_close(mut self, verify_on_close: bool) -> Self {
+        self.config.verify_on_close = verify_on_close;
+        self
+    }
+
     /// 设置连接创建函数（客户端模式）
     pub fn dialer(mut self, dialer: Dialer) -> Self {
         self.config.dialer = Some(dialer);
         self
     }
 
+    /// 设置带标签的连接创建函数（客户端模式，优先于 dialer）
+    pub fn labeled_dialer(mut self, labeled_dialer: LabeledDialer) -> Self {
+        self.config.labeled_dialer = Some(labeled_dialer);
+        self
+    }
+
+    /// 设置按角色创建连接的函数（客户端模式，供 `Pool::get_role` 使用）
+    pub fn role_dialer(mut self, role_dialer: RoleDialer) -> Self {
+        self.config.role_dialer = Some(role_dialer);
+        self
+    }
+
+    /// 设置影子连接的创建函数（客户端模式）
+    pub fn shadow_dialer(mut self, shadow_dialer: Dialer) -> Self {
+        self.config.shadow_dialer = Some(shadow_dialer);
+        self
+    }
+
+    /// 设置主 Dialer 失败时使用的降级连接创建函数（客户端模式）
+    pub fn fallback_dialer(mut self, fallback_dialer: Dialer) -> Self {
+        self.config.fallback_dialer = Some(fallback_dialer);
+        self
+    }
+
     /// 设置网络监听器（服务器端模式）
     pub fn listener(mut self, listener: std::net::TcpListener) -> Self {
         self.config.listener = Some(listener);
@@ -498,6 +1455,66 @@ impl ConfigBuilder {
         self
     }
 
+    /// 设置带失败原因的健康检查函数
+    pub fn health_checker_with_reason(
+        mut self,
+        health_checker_with_reason: HealthCheckerWithReason,
+    ) -> Self {
+        self.config.health_checker_with_reason = Some(health_checker_with_reason);
+        self
+    }
+
+    /// 设置健康检查失败回调
+    pub fn on_health_check_failure(
+        mut self,
+        on_health_check_failure: Box<HealthCheckFailureCallback>,
+    ) -> Self {
+        self.config.on_health_check_failure = Some(on_health_check_failure);
+        self
+    }
+
+    /// 设置单轮健康检查的最大并行度
+    pub fn health_check_concurrency(mut self, health_check_concurrency: usize) -> Self {
+        self.config.health_check_concurrency = health_check_concurrency;
+        self
+    }
+
+    /// 设置验证往返的请求与期望响应
+    pub fn validation_query(mut self, request: Vec<u8>, expected: Vec<u8>) -> Self {
+        self.config.validation_query = Some((request, expected));
+        self
+    }
+
+    /// 设置借出前是否执行验证往返
+    pub fn test_on_borrow(mut self, test_on_borrow: bool) -> Self {
+        self.config.test_on_borrow = test_on_borrow;
+        self
+    }
+
+    /// 设置后台健康检查时是否对空闲连接执行验证往返
+    pub fn test_while_idle(mut self, test_while_idle: bool) -> Self {
+        self.config.test_while_idle = test_while_idle;
+        self
+    }
+
+    /// 设置是否对被清理的空闲连接进行热替换
+    pub fn replace_evicted_connections(mut self, replace_evicted_connections: bool) -> Self {
+        self.config.replace_evicted_connections = replace_evicted_connections;
+        self
+    }
+
+    /// 设置 Pool::new 时是否预检 Dialer（失败则快速返回错误）
+    pub fn fail_fast_on_new(mut self, fail_fast_on_new: bool) -> Self {
+        self.config.fail_fast_on_new = fail_fast_on_new;
+        self
+    }
+
+    /// 设置是否延迟到第一次 get 时才启动后台线程
+    pub fn lazy_init(mut self, lazy_init: bool) -> Self {
+        self.config.lazy_init = lazy_init;
+        self
+    }
+
     /// 设置连接关闭函数
     pub fn close_conn(mut self, close_conn: Box<CloseConnCallback>) -> Self {
         self.config.close_conn = Some(close_conn);
@@ -510,6 +1527,12 @@ impl ConfigBuilder {
         self
     }
 
+    /// 设置借出前初始化回调（失败时丢弃连接）
+    pub fn on_acquire(mut self, on_acquire: Box<OnAcquireCallback>) -> Self {
+        self.config.on_acquire = Some(on_acquire);
+        self
+    }
+
     /// 设置连接借出前回调
     pub fn on_borrow(mut self, on_borrow: Box<BorrowReturnCallback>) -> Self {
         self.config.on_borrow = Some(on_borrow);
@@ -522,6 +1545,48 @@ impl ConfigBuilder {
         self
     }
 
+    /// 设置周期性统计回调
+    pub fn on_stats(mut self, on_stats: Box<StatsCallback>) -> Self {
+        self.config.on_stats = Some(on_stats);
+        self
+    }
+
+    /// 设置最小可用度（0 表示不启用降级）
+    pub fn min_availability(mut self, min_availability: usize) -> Self {
+        self.config.min_availability = min_availability;
+        self
+    }
+
+    /// 设置降级态切换回调
+    pub fn on_degradation(mut self, on_degradation: Box<DegradationCallback>) -> Self {
+        self.config.on_degradation = Some(on_degradation);
+        self
+    }
+
+    /// 设置容量水位告警：连接数占 max_connections 的比例升到 `ratio` 时调用 `callback`
+    pub fn on_high_watermark(mut self, ratio: f64, callback: Box<WatermarkCallback>) -> Self {
+        self.config.on_high_watermark = Some((ratio, callback));
+        self
+    }
+
+    /// 设置周期性统计回调的采样间隔
+    pub fn stats_interval(mut self, stats_interval: Duration) -> Self {
+        self.config.stats_interval = stats_interval;
+        self
+    }
+
+    /// 设置 EWMA 平滑指标的时间窗口
+    pub fn stats_ewma_window(mut self, stats_ewma_window: Duration) -> Self {
+        self.config.stats_ewma_window = stats_ewma_window;
+        self
+    }
+
+    /// 设置 `Pool::health` 的统计窗口周期
+    pub fn health_window(mut self, health_window: Duration) -> Self {
+        self.config.health_window = health_window;
+        self
+    }
+
     /// 设置是否启用统计信息
     pub fn enable_stats(mut self, enable_stats: bool) -> Self {
         self.config.enable_stats = enable_stats;
@@ -552,6 +1617,42 @@ impl ConfigBuilder {
         self
     }
 
+    /// 设置是否由后台线程异步完成连接归还
+    pub fn async_return(mut self, async_return: bool) -> Self {
+        self.config.async_return = async_return;
+        self
+    }
+
+    /// 设置是否为 TCP 连接启用 TCP_NODELAY
+    pub fn tcp_nodelay(mut self, tcp_nodelay: bool) -> Self {
+        self.config.tcp_nodelay = tcp_nodelay;
+        self
+    }
+
+    /// 设置 TCP keepalive 空闲探测时间（None 表示不启用）
+    pub fn tcp_keepalive(mut self, tcp_keepalive: Option<Duration>) -> Self {
+        self.config.tcp_keepalive = tcp_keepalive;
+        self
+    }
+
+    /// 设置连接默认读超时（None 表示不设置）
+    pub fn default_read_timeout(mut self, default_read_timeout: Option<Duration>) -> Self {
+        self.config.default_read_timeout = default_read_timeout;
+        self
+    }
+
+    /// 设置连接默认写超时（None 表示不设置）
+    pub fn default_write_timeout(mut self, default_write_timeout: Option<Duration>) -> Self {
+        self.config.default_write_timeout = default_write_timeout;
+        self
+    }
+
+    /// 设置建连后统一应用的 socket 选项
+    pub fn socket_options(mut self, socket_options: SocketOptions) -> Self {
+        self.config.socket_options = socket_options;
+        self
+    }
+
     /// 构建并验证配置
     ///
     /// # 返回值
@@ -1,15 +1,28 @@
 // Copyright (c) 2025, vistone
 // All rights reserved.
 
-use crate::config::ConnectionType;
+use crate::config::{ConnectionRole, ConnectionType};
 use crate::ipversion::{detect_ip_version, IPVersion};
-use crate::protocol::Protocol;
+use crate::protocol::{detect_protocol, Protocol};
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
 use std::net::{TcpStream, UdpSocket};
-use std::sync::atomic::{AtomicBool, AtomicI64, AtomicU64, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicI64, AtomicU64, AtomicU8, Ordering};
 use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 
 static CONNECTION_ID_GENERATOR: AtomicU64 = AtomicU64::new(1);
 
+/// lease_nanos 未设置租约时的取值
+const NO_LEASE: u64 = u64::MAX;
+
+// 连接状态位：借出、不健康、已关闭。三者放在同一个原子字里，
+// 借出与标记不健康都通过 CAS 完成，两者不会交错出"不健康连接被借出"的结果
+const STATE_IN_USE: u8 = 1;
+const STATE_UNHEALTHY: u8 = 1 << 1;
+const STATE_CLOSED: u8 = 1 << 2;
+/// 空闲连接正被 reaper 健康检查/验证往返独占，期间不可借出
+const STATE_CHECKING: u8 = 1 << 3;
+
 /// on_close 关闭回调类型
 pub type OnCloseCallback =
     dyn Fn() -> std::result::Result<(), Box<dyn std::error::Error + Send + Sync>> + Send + Sync;
@@ -37,21 +50,39 @@ pub struct Connection {
     /// LastHealthCheckAt 最后健康检查时间（使用 AtomicU64 存储 UNIX 时间戳纳秒）
     last_health_check_at: AtomicU64,
 
-    /// IsHealthy 是否健康
-    is_healthy: AtomicBool,
-
-    /// Closed 是否已关闭（用于 close 幂等）
-    closed: AtomicBool,
-
-    /// InUse 是否正在使用中
-    in_use: AtomicBool,
+    /// State 借出/不健康/已关闭状态位（单一原子状态机，不健康与已关闭为终态）
+    state: AtomicU8,
 
     /// ReuseCount 连接复用次数
     reuse_count: AtomicI64,
 
+    /// BytesTransferred 经 PooledConnection 透传读写的累计字节数
+    bytes_transferred: AtomicU64,
+
+    /// borrow_epoch 借用代际：每次借出和归还各递增一次，奇数表示借出中
+    borrow_epoch: AtomicU64,
+
     /// leak_reported 是否已上报过泄漏（避免重复计数）
     leak_reported: AtomicBool,
 
+    /// lease_nanos 本次借用的租约时长（纳秒），NO_LEASE 表示沿用全局 connection_leak_timeout
+    lease_nanos: AtomicU64,
+
+    /// lifetime_scale 生命周期缩放系数（用于 max_lifetime 抖动，默认 1.0）
+    lifetime_scale: f64,
+
+    /// Label 用户自定义的可读标签（如目标 host），由 LabeledDialer 提供
+    label: Option<String>,
+
+    /// Role 连接角色，由 RoleDialer 创建时标注
+    role: Option<ConnectionRole>,
+
+    /// oneshot 一次性连接（get_oneshot 建立），归还时直接关闭而不放回空闲池
+    oneshot: AtomicBool,
+
+    /// fallback 降级连接：主 Dialer 失败后由 fallback_dialer 建立
+    fallback: AtomicBool,
+
     /// on_close 关闭回调
     ///
     /// 如果提供了此回调，连接池在关闭连接时将调用此函数，并**跳过默认的关闭逻辑**。
@@ -68,12 +99,18 @@ impl fmt::Debug for Connection {
             .field("conn", &self.conn)
             .field("protocol", &self.protocol)
             .field("ip_version", &self.ip_version)
+            .field("label", &self.label)
+            .field("role", &self.role)
+            .field("oneshot", &self.is_oneshot())
             .field("created_at", &self.created_at)
             .field("last_used_at", &self.last_used_at)
             .field("last_health_check_at", &self.last_health_check_at)
-            .field("is_healthy", &self.is_healthy)
-            .field("in_use", &self.in_use)
+            .field("is_healthy", &self.health_status())
+            .field("in_use", &self.is_in_use())
+            .field("closed", &self.is_closed())
             .field("reuse_count", &self.reuse_count)
+            .field("bytes_transferred", &self.bytes_transferred)
+            .field("borrow_epoch", &self.borrow_epoch)
             .finish()
     }
 }
@@ -95,17 +132,13 @@ impl Connection {
     /// NewConnection 创建新连接
     pub fn new(conn: ConnectionType, on_close: Option<Box<OnCloseCallback>>) -> Self {
         let now = Instant::now();
-        let protocol = match &conn {
-            ConnectionType::Tcp(_) => Protocol::TCP,
-            ConnectionType::Udp(_) => Protocol::UDP,
-        };
+        let protocol = detect_protocol(&conn);
 
-        let ip_version = match &conn {
-            ConnectionType::Tcp(s) => s.peer_addr().or_else(|_| s.local_addr()),
-            ConnectionType::Udp(s) => s.peer_addr().or_else(|_| s.local_addr()),
-        }
-        .map(|addr| detect_ip_version(&addr))
-        .unwrap_or(IPVersion::Unknown);
+        let ip_version = conn
+            .peer_addr()
+            .or_else(|_| conn.local_addr())
+            .map(|addr| detect_ip_version(&addr))
+            .unwrap_or(IPVersion::Unknown);
 
         // 安全地生成连接 ID，检测溢出
         let id = loop {
@@ -167,15 +200,51 @@ impl Connection {
             created_at: now,
             last_used_at: AtomicU64::new(system_now),
             last_health_check_at: AtomicU64::new(system_now),
-            is_healthy: AtomicBool::new(true),
-            closed: AtomicBool::new(false),
-            in_use: AtomicBool::new(false),
+            state: AtomicU8::new(0),
             reuse_count: AtomicI64::new(0),
+            bytes_transferred: AtomicU64::new(0),
+            borrow_epoch: AtomicU64::new(0),
             leak_reported: AtomicBool::new(false),
+            lease_nanos: AtomicU64::new(NO_LEASE),
+            lifetime_scale: 1.0,
+            label: None,
+            role: None,
+            oneshot: AtomicBool::new(false),
+            fallback: AtomicBool::new(false),
             on_close,
         }
     }
 
+    /// with_lifetime_jitter 为连接的有效生命周期叠加 ±jitter 比例的随机抖动
+    ///
+    /// 例如 jitter 为 0.1 时，有效生命周期在 max_lifetime 的 [0.9, 1.1] 倍之间随机分布，
+    /// 避免同一时刻建立的大量连接同时过期。jitter 会被限制在 [0.0, 1.0) 范围内。
+    pub fn with_lifetime_jitter(mut self, jitter: f64) -> Self {
+        let jitter = if jitter.is_finite() {
+            jitter.clamp(0.0, 0.99)
+        } else {
+            0.0
+        };
+        if jitter > 0.0 {
+            // 取 [0, 1) 的随机数映射到 [1 - jitter, 1 + jitter)
+            let mut hasher = RandomState::new().build_hasher();
+            hasher.write_u64(self.id());
+            hasher.write_u64(Self::now_nanos());
+            let unit = (hasher.finish() >> 11) as f64 / (1u64 << 53) as f64;
+            self.lifetime_scale = 1.0 + jitter * (unit * 2.0 - 1.0);
+        }
+        self
+    }
+
+    /// effective_max_lifetime 返回叠加抖动后的该连接实际生命周期上限
+    pub fn effective_max_lifetime(&self, max_lifetime: Duration) -> Duration {
+        if self.lifetime_scale == 1.0 {
+            max_lifetime
+        } else {
+            max_lifetime.mul_f64(self.lifetime_scale)
+        }
+    }
+
     /// NewConnectionFromTcp 从TCP流创建连接
     pub fn new_from_tcp(stream: TcpStream, on_close: Option<Box<OnCloseCallback>>) -> Self {
         Self::new(ConnectionType::Tcp(stream), on_close)
@@ -186,6 +255,48 @@ impl Connection {
         Self::new(ConnectionType::Udp(socket), on_close)
     }
 
+    /// WithLabel 为连接设置可读标签（如目标 host），便于日志与诊断关联业务含义
+    pub fn with_label(mut self, label: Option<String>) -> Self {
+        self.label = label;
+        self
+    }
+
+    /// Label 获取连接的可读标签
+    pub fn label(&self) -> Option<&str> {
+        self.label.as_deref()
+    }
+
+    /// WithRole 为连接标注角色（只读/只写）
+    pub fn with_role(mut self, role: Option<ConnectionRole>) -> Self {
+        self.role = role;
+        self
+    }
+
+    /// Role 获取连接角色，未标注时为 None
+    pub fn role(&self) -> Option<ConnectionRole> {
+        self.role
+    }
+
+    /// mark_oneshot 标记为一次性连接，归还时关闭而不复用
+    pub(crate) fn mark_oneshot(&self) {
+        self.oneshot.store(true, Ordering::Release);
+    }
+
+    /// IsOneshot 是否为一次性连接
+    pub fn is_oneshot(&self) -> bool {
+        self.oneshot.load(Ordering::Acquire)
+    }
+
+    /// mark_fallback 标记为降级连接（由 fallback_dialer 建立）
+    pub(crate) fn mark_fallback(&self) {
+        self.fallback.store(true, Ordering::Release);
+    }
+
+    /// IsFallback 是否为降级连接（主 Dialer 失败后由 fallback_dialer 建立）
+    pub fn is_fallback(&self) -> bool {
+        self.fallback.load(Ordering::Acquire)
+    }
+
     /// connection_type 获取连接类型引用
     pub fn connection_type(&self) -> &ConnectionType {
         &self.conn
@@ -231,6 +342,15 @@ impl Connection {
         }
     }
 
+    /// GetTlsConn 获取底层连接对象（TLS流，`tls` feature）
+    #[cfg(feature = "tls")]
+    pub fn tls_conn(&self) -> Option<&crate::tls::TlsStream> {
+        match &self.conn {
+            ConnectionType::Tls(stream) => Some(stream),
+            _ => None,
+        }
+    }
+
     /// GetUdpConn 获取底层连接对象（UDP套接字）
     pub fn udp_conn(&self) -> Option<&UdpSocket> {
         match &self.conn {
@@ -241,21 +361,26 @@ impl Connection {
 
     /// MarkInUse 标记为使用中
     pub fn mark_in_use(&self) {
-        self.in_use.store(true, Ordering::Release);
+        self.state.fetch_or(STATE_IN_USE, Ordering::AcqRel);
         self.last_used_at
             .store(Self::now_nanos(), Ordering::Release);
     }
 
     /// MarkIdle 标记为空闲
     pub fn mark_idle(&self) {
-        self.in_use.store(false, Ordering::Release);
+        self.state.fetch_and(!STATE_IN_USE, Ordering::AcqRel);
         self.last_used_at
             .store(Self::now_nanos(), Ordering::Release);
     }
 
+    /// LastUsedNanos 最近一次借出或归还的时间戳（纳秒），用于按最近使用排序
+    pub(crate) fn last_used_nanos(&self) -> u64 {
+        self.last_used_at.load(Ordering::Acquire)
+    }
+
     /// TryMarkIdle 尝试标记为空闲，并返回之前是否为使用中状态（原子操作）
     pub fn try_mark_idle(&self) -> bool {
-        let was_in_use = self.in_use.swap(false, Ordering::Acquire);
+        let was_in_use = self.state.fetch_and(!STATE_IN_USE, Ordering::AcqRel) & STATE_IN_USE != 0;
         if was_in_use {
             self.last_used_at
                 .store(Self::now_nanos(), Ordering::Release);
@@ -263,18 +388,65 @@ impl Connection {
         was_in_use
     }
 
+    /// TryAcquire 原子地由"空闲、健康、未关闭"转为使用中，任一条件不满足时返回 false
+    ///
+    /// 成功后才写入本次借用的租约，争抢失败的调用方不会改动别人持有的连接。
+    pub(crate) fn try_acquire(&self, lease: Option<Duration>) -> bool {
+        let acquired = self
+            .state
+            .compare_exchange(0, STATE_IN_USE, Ordering::AcqRel, Ordering::Acquire)
+            .is_ok();
+        if acquired {
+            self.set_lease(lease);
+            self.last_used_at
+                .store(Self::now_nanos(), Ordering::Release);
+        }
+        acquired
+    }
+
+    /// try_begin_check 原子地由"空闲、健康、未关闭"转为检查中，成功后直到 end_check 前不会被借出
+    pub(crate) fn try_begin_check(&self) -> bool {
+        self.state
+            .compare_exchange(0, STATE_CHECKING, Ordering::AcqRel, Ordering::Acquire)
+            .is_ok()
+    }
+
+    /// end_check 结束检查，释放对连接的独占
+    pub(crate) fn end_check(&self) {
+        self.state.fetch_and(!STATE_CHECKING, Ordering::AcqRel);
+    }
+
+    /// is_checking 连接是否正被健康检查独占
+    pub(crate) fn is_checking(&self) -> bool {
+        self.state.load(Ordering::Acquire) & STATE_CHECKING != 0
+    }
+
     /// UpdateHealth 更新健康状态
+    ///
+    /// 不健康是终态：`update_health(true)` 只刷新检查时间，不会让已判定不健康的连接恢复可借。
     pub fn update_health(&self, healthy: bool) {
-        self.is_healthy.store(healthy, Ordering::Release);
         if healthy {
             self.last_health_check_at
                 .store(Self::now_nanos(), Ordering::Release);
+        } else {
+            self.mark_unhealthy();
         }
     }
 
     /// mark_unhealthy 仅标记为不健康（不主动关闭）
     pub fn mark_unhealthy(&self) {
-        self.is_healthy.store(false, Ordering::Release);
+        self.state.fetch_or(STATE_UNHEALTHY, Ordering::AcqRel);
+    }
+
+    /// mark_unhealthy_if_idle 仅在连接未被借出时标记为不健康，返回是否标记成功
+    ///
+    /// 健康检查期间连接可能已被借出，此时检查结果作废，避免把刚借出的连接判为不健康后移除。
+    pub(crate) fn mark_unhealthy_if_idle(&self) -> bool {
+        self.state
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |state| {
+                (state & STATE_IN_USE == 0).then_some(state | STATE_UNHEALTHY)
+            })
+            .is_ok()
     }
 
     /// should_health_check 判断是否需要执行健康检查
@@ -296,20 +468,55 @@ impl Connection {
         !self.leak_reported.swap(true, Ordering::AcqRel)
     }
 
-    /// IsExpired 检查连接是否过期（超过MaxLifetime）
+    /// set_lease 设置本次借用的租约时长（get_with_lease），None 表示沿用全局泄漏判定时长
+    pub(crate) fn set_lease(&self, lease: Option<Duration>) {
+        let nanos = lease.map_or(NO_LEASE, |d| {
+            u64::try_from(d.as_nanos()).unwrap_or(NO_LEASE - 1)
+        });
+        self.lease_nanos.store(nanos, Ordering::Release);
+    }
+
+    /// Lease 本次借用的租约时长，未通过 get_with_lease 借出时为 None
+    pub fn lease(&self) -> Option<Duration> {
+        match self.lease_nanos.load(Ordering::Acquire) {
+            NO_LEASE => None,
+            nanos => Some(Duration::from_nanos(nanos)),
+        }
+    }
+
+    /// clear_leak_report 清除泄漏标记，返回此前是否已上报过泄漏
+    pub fn clear_leak_report(&self) -> bool {
+        self.leak_reported.swap(false, Ordering::AcqRel)
+    }
+
+    /// IsExpired 检查连接是否过期（超过MaxLifetime，已计入该连接的生命周期抖动）
     pub fn is_expired(&self, max_lifetime: Duration) -> bool {
+        // 0 表示不限制生命周期
         if max_lifetime.is_zero() {
             return false;
         }
-        Instant::now().duration_since(self.created_at) > max_lifetime
+        Instant::now().duration_since(self.created_at) > self.effective_max_lifetime(max_lifetime)
+    }
+
+    /// IsBytesExhausted 检查累计读写字节是否已达到 max_bytes 上限
+    pub fn is_bytes_exhausted(&self, max_bytes: u64) -> bool {
+        // 0 表示不限制累计字节
+        max_bytes != 0 && self.bytes_transferred() >= max_bytes
+    }
+
+    /// IsRebindDue 检查复用次数或连接年龄是否已达到重绑定阈值（0 表示不按该维度判断）
+    pub fn is_rebind_due(&self, max_reuses: usize, max_age: Duration) -> bool {
+        (max_reuses != 0 && self.reuse_count() >= max_reuses as i64)
+            || (!max_age.is_zero() && self.age() >= max_age)
     }
 
     /// IsIdleExpired 检查连接是否空闲太久（超过IdleTimeout）
     pub fn is_idle_expired(&self, idle_timeout: Duration) -> bool {
+        // 0 表示不限制空闲时间
         if idle_timeout.is_zero() {
             return false;
         }
-        if self.in_use.load(Ordering::Acquire) {
+        if self.is_in_use() {
             return false;
         }
         let last_nanos = self.last_used_at.load(Ordering::Acquire);
@@ -323,7 +530,7 @@ impl Connection {
 
     /// IsLeaked 检查连接是否泄漏（超过ConnectionLeakTimeout且仍在使用时）
     pub fn is_leaked(&self, leak_timeout: Duration) -> bool {
-        if leak_timeout.is_zero() || !self.in_use.load(Ordering::Acquire) {
+        if leak_timeout.is_zero() || !self.is_in_use() {
             return false;
         }
         let last_nanos = self.last_used_at.load(Ordering::Acquire);
@@ -338,7 +545,7 @@ impl Connection {
     /// GetLeakedDuration 获取连接的泄漏时间（如果泄漏）
     /// 返回 None 表示未泄漏，Some(Duration) 表示泄漏的时间
     pub fn get_leaked_duration(&self) -> Option<Duration> {
-        if !self.in_use.load(Ordering::Acquire) {
+        if !self.is_in_use() {
             return None;
         }
         let last_nanos = self.last_used_at.load(Ordering::Acquire);
@@ -352,10 +559,17 @@ impl Connection {
 
     /// Close 关闭连接
     ///
-    /// 如果配置了 `on_close` 回调，将执行回调并直接返回。
-    /// 否则，将执行默认关闭策略：TCP 执行 shutdown，UDP 依赖 Drop 物理关闭。
+    /// 如果创建连接时传入了 `on_close` 回调（见 `Connection::new`），将执行回调并直接返回，
+    /// 不再执行默认关闭；否则执行默认关闭策略：TCP 执行 shutdown，UDP 依赖 Drop 物理关闭。
+    ///
+    /// 连接池创建的连接不设置 `on_close`。`Config::close_conn` 由连接池在调用本方法之前执行，
+    /// 不会替代默认关闭。
     pub fn close(&self) -> std::result::Result<(), Box<dyn std::error::Error + Send + Sync>> {
-        if self.closed.swap(true, Ordering::AcqRel) {
+        // 关闭同时置不健康位，健康检查结果不会让已关闭连接恢复可借
+        let prev = self
+            .state
+            .fetch_or(STATE_CLOSED | STATE_UNHEALTHY, Ordering::AcqRel);
+        if prev & STATE_CLOSED != 0 {
             return Ok(());
         }
 
@@ -363,19 +577,19 @@ impl Connection {
             // 执行用户自定义关闭逻辑。注意：此时默认关闭逻辑（如 TCP shutdown）将被跳过，
             // 用户需确保在回调内部处理了连接实体的关闭。
             on_close()?;
-            self.is_healthy.store(false, Ordering::Release);
             return Ok(());
         }
 
-        // 默认关闭策略：TCP 做 shutdown；UDP 无显式 close（drop 时关闭）
+        // 默认关闭策略：TCP 做 shutdown；TLS 先发 close_notify 再 shutdown；
+        // UDP 无显式 close（drop 时关闭）
         match &self.conn {
             ConnectionType::Tcp(stream) => {
                 let _ = stream.shutdown(std::net::Shutdown::Both);
             }
             ConnectionType::Udp(_) => {}
+            #[cfg(feature = "tls")]
+            ConnectionType::Tls(stream) => stream.shutdown(),
         }
-
-        self.is_healthy.store(false, Ordering::Release);
         Ok(())
     }
 
@@ -386,7 +600,7 @@ impl Connection {
 
     /// GetIdleTime 获取空闲时间
     pub fn idle_time(&self) -> Duration {
-        if self.in_use.load(Ordering::Acquire) {
+        if self.is_in_use() {
             return Duration::ZERO;
         }
         let last_nanos = self.last_used_at.load(Ordering::Acquire);
@@ -408,18 +622,86 @@ impl Connection {
         self.reuse_count.load(Ordering::Relaxed)
     }
 
+    /// add_bytes_transferred 累计读写字节数
+    pub(crate) fn add_bytes_transferred(&self, n: usize) {
+        self.bytes_transferred
+            .fetch_add(n as u64, Ordering::Relaxed);
+    }
+
+    /// GetBytesTransferred 获取经 PooledConnection 透传读写的累计字节数
+    pub fn bytes_transferred(&self) -> u64 {
+        self.bytes_transferred.load(Ordering::Relaxed)
+    }
+
+    /// begin_borrow 开始一次借用，返回本次借用的代际令牌
+    pub(crate) fn begin_borrow(&self) -> u64 {
+        self.borrow_epoch.fetch_add(1, Ordering::AcqRel) + 1
+    }
+
+    /// end_borrow 以借出时的令牌结束借用
+    ///
+    /// 令牌与当前代际不符（重复归还，或连接已被再次借出）时返回 false，调用方应忽略该次归还。
+    pub(crate) fn end_borrow(&self, epoch: u64) -> bool {
+        self.borrow_epoch
+            .compare_exchange(epoch, epoch + 1, Ordering::AcqRel, Ordering::Acquire)
+            .is_ok()
+    }
+
     /// IsInUse 检查连接是否正在使用中（线程安全）
     pub fn is_in_use(&self) -> bool {
-        self.in_use.load(Ordering::Acquire)
+        self.state.load(Ordering::Acquire) & STATE_IN_USE != 0
     }
 
-    /// GetHealthStatus 获取连接健康状态（线程安全）
+    /// GetHealthStatus 获取连接健康状态（线程安全，已关闭的连接视为不健康）
     pub fn health_status(&self) -> bool {
-        self.is_healthy.load(Ordering::Acquire)
+        self.state.load(Ordering::Acquire) & (STATE_UNHEALTHY | STATE_CLOSED) == 0
     }
 
     /// is_closed 查询是否已关闭
     pub fn is_closed(&self) -> bool {
-        self.closed.load(Ordering::Acquire)
+        self.state.load(Ordering::Acquire) & STATE_CLOSED != 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::thread;
+
+    fn udp_connection() -> Connection {
+        Connection::new_from_udp(UdpSocket::bind("127.0.0.1:0").unwrap(), None)
+    }
+
+    #[test]
+    fn test_zero_max_lifetime_never_expires() {
+        let conn = udp_connection().with_lifetime_jitter(0.5);
+        thread::sleep(Duration::from_millis(5));
+        assert!(!conn.is_expired(Duration::ZERO));
+        assert!(conn.is_expired(Duration::from_nanos(1)));
+    }
+
+    #[test]
+    fn test_zero_idle_timeout_never_expires() {
+        let conn = udp_connection();
+        conn.mark_idle();
+        thread::sleep(Duration::from_millis(5));
+        assert!(!conn.is_idle_expired(Duration::ZERO));
+        assert!(conn.is_idle_expired(Duration::from_nanos(1)));
+    }
+
+    #[test]
+    fn test_failed_acquire_does_not_set_lease() {
+        let conn = udp_connection();
+        conn.mark_idle();
+        let lease = Some(Duration::from_secs(1));
+
+        // 正被健康检查独占：争抢失败，租约保持不变
+        assert!(conn.try_begin_check());
+        assert!(!conn.try_acquire(lease));
+        assert_eq!(conn.lease(), None);
+
+        conn.end_check();
+        assert!(conn.try_acquire(lease));
+        assert_eq!(conn.lease(), lease);
     }
 }
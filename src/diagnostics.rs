@@ -0,0 +1,146 @@
+// Copyright (c) 2025, vistone
+// All rights reserved.
+
+//! 连接池运行时诊断快照
+//!
+//! `Pool::dump` 一次性导出配置摘要、桶分布、每条连接的状态与统计，
+//! 通过 `Display` 输出人类可读的报告，便于线上排障时写入日志或文件。
+
+use crate::connection::Connection;
+use crate::ipversion::IPVersion;
+use crate::mode::PoolMode;
+use crate::protocol::Protocol;
+use crate::stats::{BucketStat, Stats};
+use std::fmt;
+use std::time::Duration;
+
+/// ConnectionSnapshot 单条连接的状态快照
+#[derive(Debug, Clone)]
+pub struct ConnectionSnapshot {
+    /// ID 连接唯一标识符
+    pub id: u64,
+    /// Protocol 协议类型
+    pub protocol: Protocol,
+    /// IPVersion IP版本
+    pub ip_version: IPVersion,
+    /// Label 用户自定义标签
+    pub label: Option<String>,
+    /// Age 连接年龄
+    pub age: Duration,
+    /// IdleTime 空闲时长（使用中为0）
+    pub idle_time: Duration,
+    /// InUse 是否正在使用中
+    pub in_use: bool,
+    /// ReuseCount 复用次数
+    pub reuse_count: i64,
+    /// Healthy 最近一次健康状态
+    pub healthy: bool,
+    /// BytesTransferred 经 PooledConnection 透传读写的累计字节数
+    pub bytes_transferred: u64,
+}
+
+impl ConnectionSnapshot {
+    pub(crate) fn of(conn: &Connection) -> Self {
+        Self {
+            id: conn.id(),
+            protocol: conn.protocol(),
+            ip_version: conn.ip_version(),
+            label: conn.label().map(str::to_string),
+            age: conn.age(),
+            idle_time: conn.idle_time(),
+            in_use: conn.is_in_use(),
+            reuse_count: conn.reuse_count(),
+            healthy: conn.health_status(),
+            bytes_transferred: conn.bytes_transferred(),
+        }
+    }
+}
+
+/// PoolDump 连接池完整运行时状态（`Pool::dump` 返回）
+///
+/// 连接列表、桶分布与统计中的 current_* 即时量取自同一次连接表快照，彼此一致。
+#[derive(Debug, Clone)]
+pub struct PoolDump {
+    /// Mode 连接池模式
+    pub mode: PoolMode,
+    /// Closed 连接池是否已关闭
+    pub closed: bool,
+    /// MaxConnections 最大连接数（0 表示不限制）
+    pub max_connections: usize,
+    /// MinConnections 最小连接数
+    pub min_connections: usize,
+    /// MaxIdleConnections 每桶最大空闲连接数（运行时值）
+    pub max_idle_connections: usize,
+    /// Waiters 当前等待获取连接的线程数
+    pub waiters: usize,
+    /// Buckets 各桶（协议×IP 版本）的连接分布
+    pub buckets: [BucketStat; 4],
+    /// Connections 各连接的状态（按 ID 升序）
+    pub connections: Vec<ConnectionSnapshot>,
+    /// Stats 统计信息
+    pub stats: Stats,
+}
+
+impl fmt::Display for PoolDump {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "连接池诊断 (mode: {}, closed: {})",
+            self.mode, self.closed
+        )?;
+        writeln!(
+            f,
+            "  配置: max_connections={} min_connections={} max_idle_connections={}",
+            self.max_connections, self.min_connections, self.max_idle_connections
+        )?;
+        let active = self.connections.iter().filter(|c| c.in_use).count();
+        writeln!(
+            f,
+            "  连接: total={} active={} idle={} waiters={}",
+            self.connections.len(),
+            active,
+            self.connections.len() - active,
+            self.waiters
+        )?;
+        writeln!(f, "  桶分布:")?;
+        for bucket in &self.buckets {
+            writeln!(
+                f,
+                "    {}/{}: idle={} active={}",
+                bucket.protocol, bucket.ip_version, bucket.idle, bucket.active
+            )?;
+        }
+        let stats = &self.stats;
+        writeln!(
+            f,
+            "  统计: created={} closed={} gets={} successful={} failed={} timeouts={} reuse_ratio={:.2} avg_get_time={:?} leaked={}",
+            stats.total_connections_created,
+            stats.total_connections_closed,
+            stats.total_get_requests,
+            stats.successful_gets,
+            stats.failed_gets,
+            stats.timeout_gets,
+            stats.reuse_ratio,
+            stats.average_get_time,
+            stats.current_leaked_connections
+        )?;
+        writeln!(f, "  连接列表:")?;
+        for conn in &self.connections {
+            writeln!(
+                f,
+                "    #{} {}/{} age={:?} idle={:?} in_use={} reuse={} healthy={} bytes={} label={}",
+                conn.id,
+                conn.protocol,
+                conn.ip_version,
+                conn.age,
+                conn.idle_time,
+                conn.in_use,
+                conn.reuse_count,
+                conn.healthy,
+                conn.bytes_transferred,
+                conn.label.as_deref().unwrap_or("-")
+            )?;
+        }
+        Ok(())
+    }
+}
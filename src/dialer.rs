@@ -0,0 +1,288 @@
+// Copyright (c) 2025, vistone
+// All rights reserved.
+
+//! 内置 Dialer 模块
+//!
+//! 提供开箱即用的连接创建函数，可直接用于 `Config::dialer`。
+
+use crate::config::{ConnectionType, Dialer};
+use crate::protocol::Protocol;
+use std::io;
+use std::net::{SocketAddr, TcpStream, ToSocketAddrs};
+use std::sync::mpsc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// happy_eyeballs_dialer 创建一个双栈并发建连（Happy Eyeballs）的 TCP Dialer
+///
+/// 每次建连时重新解析 `host:port`，按 IPv6 优先、IPv4/IPv6 交替的顺序发起连接；
+/// 若前一个尝试在 `delay` 内未成功（或已失败），立即并发发起下一个，取最先成功者。
+/// 整个竞速最多耗时 `timeout`，落败的连接在建立后会被直接丢弃关闭。
+///
+/// 该 Dialer 仅支持 TCP，调用方请求 UDP 时返回错误。
+pub fn happy_eyeballs_dialer(
+    host: impl Into<String>,
+    port: u16,
+    delay: Duration,
+    timeout: Duration,
+) -> Dialer {
+    let host = host.into();
+    Box::new(move |protocol| {
+        if protocol == Some(Protocol::UDP) {
+            return Err(Box::new(io::Error::new(
+                io::ErrorKind::Unsupported,
+                "happy eyeballs dialer 仅支持 TCP",
+            )) as Box<dyn std::error::Error + Send + Sync>);
+        }
+        let addrs: Vec<SocketAddr> = (host.as_str(), port).to_socket_addrs()?.collect();
+        let stream = happy_eyeballs_connect(&addrs, delay, timeout)?;
+        Ok(ConnectionType::Tcp(stream))
+    })
+}
+
+/// happy_eyeballs_connect 对给定地址列表执行 Happy Eyeballs 竞速建连
+///
+/// 地址按 IPv6 优先、两个地址族交替排列；每隔 `delay`（或在上一个尝试失败时立即）
+/// 启动下一个尝试，返回最先成功的连接。全部失败时返回最后一个错误，
+/// 超过总预算 `timeout` 仍未成功时返回 TimedOut。
+///
+/// 每个尝试在独立线程中以剩余预算为超时建连：一次调用最多启动 `addrs.len()` 个线程，
+/// 调用返回后落败的线程最迟在 `timeout` 到期时退出。
+pub fn happy_eyeballs_connect(
+    addrs: &[SocketAddr],
+    delay: Duration,
+    timeout: Duration,
+) -> io::Result<TcpStream> {
+    let ordered = interleave_by_family(addrs);
+    if ordered.is_empty() {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "没有可用于建连的地址",
+        ));
+    }
+    if timeout.is_zero() {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "建连超时必须大于0",
+        ));
+    }
+    let deadline = Instant::now() + timeout;
+    let timed_out = || io::Error::new(io::ErrorKind::TimedOut, "happy eyeballs 建连超时");
+
+    let (tx, rx) = mpsc::channel::<io::Result<TcpStream>>();
+    let mut next = 0;
+    let mut pending = 0;
+    let mut last_err = None;
+
+    loop {
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        if remaining.is_zero() {
+            return Err(timed_out());
+        }
+        if next < ordered.len() {
+            let addr = ordered[next];
+            let tx = tx.clone();
+            // 落败者在 send 失败时（接收端已返回）直接 drop 连接
+            thread::Builder::new()
+                .name("happy-eyeballs-attempt".to_string())
+                .spawn(move || {
+                    let _ = tx.send(TcpStream::connect_timeout(&addr, remaining));
+                })?;
+            next += 1;
+            pending += 1;
+        }
+
+        let wait = if next < ordered.len() {
+            delay.min(remaining)
+        } else {
+            remaining
+        };
+        let result = match rx.recv_timeout(wait) {
+            Ok(res) => res,
+            // 超过 delay 仍无结果：启动下一个尝试（或在下一轮判定总预算到期）
+            Err(mpsc::RecvTimeoutError::Timeout) => continue,
+            Err(mpsc::RecvTimeoutError::Disconnected) => break,
+        };
+
+        match result {
+            Ok(stream) => return Ok(stream),
+            Err(e) => {
+                pending -= 1;
+                last_err = Some(e);
+                if pending == 0 && next >= ordered.len() {
+                    break;
+                }
+            }
+        }
+    }
+
+    Err(last_err.unwrap_or_else(|| io::Error::other("所有建连尝试均失败")))
+}
+
+/// multi_addr_dialer 创建一个按地址列表依次故障转移的 TCP Dialer，每个地址使用独立的建连超时
+///
+/// 按给定顺序以各自的超时尝试建连，返回第一个成功的连接，全部失败时返回最后一个错误。
+/// 快后端可配置较短的超时以便快速失败并切换，跨地域等慢后端则给足时间；
+/// 超时为 0 表示该地址不设建连超时（使用系统默认）。
+///
+/// 该 Dialer 仅支持 TCP，调用方请求 UDP 时返回错误。
+pub fn multi_addr_dialer<I>(targets: I) -> Dialer
+where
+    I: IntoIterator<Item = (SocketAddr, Duration)>,
+{
+    let targets: Vec<(SocketAddr, Duration)> = targets.into_iter().collect();
+    Box::new(move |protocol| {
+        if protocol == Some(Protocol::UDP) {
+            return Err(Box::new(io::Error::new(
+                io::ErrorKind::Unsupported,
+                "multi addr dialer 仅支持 TCP",
+            )) as Box<dyn std::error::Error + Send + Sync>);
+        }
+        Ok(ConnectionType::Tcp(connect_in_order(&targets)?))
+    })
+}
+
+/// 依次以各地址自己的超时建连，返回第一个成功的连接
+fn connect_in_order(targets: &[(SocketAddr, Duration)]) -> io::Result<TcpStream> {
+    let mut last_err = None;
+    for (addr, timeout) in targets {
+        let result = if timeout.is_zero() {
+            TcpStream::connect(addr)
+        } else {
+            TcpStream::connect_timeout(addr, *timeout)
+        };
+        match result {
+            Ok(stream) => return Ok(stream),
+            Err(e) => last_err = Some(e),
+        }
+    }
+    Err(last_err
+        .unwrap_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "没有可用于建连的地址")))
+}
+
+/// 按 IPv6 优先、IPv4/IPv6 交替的顺序重排地址（RFC 8305）
+fn interleave_by_family(addrs: &[SocketAddr]) -> Vec<SocketAddr> {
+    let (v6, v4): (Vec<SocketAddr>, Vec<SocketAddr>) =
+        addrs.iter().copied().partition(|a| a.is_ipv6());
+    let mut ordered = Vec::with_capacity(addrs.len());
+    let mut v6 = v6.into_iter();
+    let mut v4 = v4.into_iter();
+    loop {
+        match (v6.next(), v4.next()) {
+            (None, None) => break,
+            (a, b) => ordered.extend(a.into_iter().chain(b)),
+        }
+    }
+    ordered
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::TcpListener;
+
+    #[test]
+    fn test_interleave_by_family() {
+        let addrs: Vec<SocketAddr> = vec![
+            "127.0.0.1:1".parse().unwrap(),
+            "127.0.0.2:1".parse().unwrap(),
+            "[::1]:1".parse().unwrap(),
+        ];
+        let ordered = interleave_by_family(&addrs);
+        assert_eq!(ordered, vec![addrs[2], addrs[0], addrs[1]]);
+    }
+
+    #[test]
+    fn test_happy_eyeballs_falls_back_to_ipv4() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let v4 = listener.local_addr().unwrap();
+        // 100::/64 为丢弃前缀（RFC 6666），连接不会得到响应
+        let v6: SocketAddr = format!("[100::1]:{}", v4.port()).parse().unwrap();
+
+        let start = Instant::now();
+        let stream =
+            happy_eyeballs_connect(&[v6, v4], Duration::from_millis(50), Duration::from_secs(5))
+                .unwrap();
+        assert_eq!(stream.peer_addr().unwrap(), v4);
+        assert!(start.elapsed() < Duration::from_secs(1));
+    }
+
+    #[test]
+    fn test_connect_in_order_tries_targets_in_order() {
+        let first = TcpListener::bind("127.0.0.1:0").unwrap();
+        let second = TcpListener::bind("127.0.0.1:0").unwrap();
+        let (first_addr, second_addr) = (first.local_addr().unwrap(), second.local_addr().unwrap());
+        let closed = {
+            let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+            listener.local_addr().unwrap()
+        };
+        let timeout = Duration::from_secs(1);
+
+        // 都可用时取第一个；前面的地址失败时继续尝试下一个
+        let conn = connect_in_order(&[(first_addr, timeout), (second_addr, timeout)]).unwrap();
+        assert_eq!(conn.peer_addr().unwrap(), first_addr);
+        let conn = connect_in_order(&[(closed, timeout), (second_addr, Duration::ZERO)]).unwrap();
+        assert_eq!(conn.peer_addr().unwrap(), second_addr);
+
+        // 全部失败时返回最后一个地址的错误
+        assert!(connect_in_order(&[(closed, timeout)]).is_err());
+        assert_eq!(
+            connect_in_order(&[]).unwrap_err().kind(),
+            io::ErrorKind::InvalidInput
+        );
+    }
+
+    #[test]
+    fn test_multi_addr_dialer_uses_per_address_timeout() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let live = listener.local_addr().unwrap();
+        // 100::/64 为丢弃前缀（RFC 6666）：一直无响应直到该地址自己的超时，或主机没有 IPv6 路由时立即失败
+        let unroutable: SocketAddr = format!("[100::1]:{}", live.port()).parse().unwrap();
+
+        // 不可路由地址只耗费自己的短超时，而不是后面地址的长超时
+        let dialer = multi_addr_dialer([
+            (unroutable, Duration::from_millis(100)),
+            (live, Duration::from_secs(30)),
+        ]);
+        let start = Instant::now();
+        let conn = dialer(None).unwrap();
+        assert_eq!(conn.peer_addr().unwrap(), live);
+        assert!(start.elapsed() < Duration::from_secs(10));
+
+        assert!(dialer(Some(Protocol::UDP)).is_err());
+        assert!(multi_addr_dialer([])(None).is_err());
+    }
+
+    #[test]
+    fn test_happy_eyeballs_all_failed() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        drop(listener);
+        let timeout = Duration::from_secs(1);
+        assert!(happy_eyeballs_connect(&[addr], Duration::from_millis(10), timeout).is_err());
+        assert!(happy_eyeballs_connect(&[], Duration::from_millis(10), timeout).is_err());
+        assert!(
+            happy_eyeballs_connect(&[addr], Duration::from_millis(10), Duration::ZERO).is_err()
+        );
+    }
+
+    #[test]
+    fn test_happy_eyeballs_respects_overall_timeout() {
+        // 100::/64 为丢弃前缀（RFC 6666），连接不会得到响应
+        let black_hole: SocketAddr = "[100::1]:80".parse().unwrap();
+        let start = Instant::now();
+        match happy_eyeballs_connect(
+            &[black_hole],
+            Duration::from_millis(10),
+            Duration::from_millis(200),
+        ) {
+            // 总预算到期即返回，而不是等待系统建连超时
+            Err(e) if e.kind() == io::ErrorKind::TimedOut => {
+                assert!(start.elapsed() < Duration::from_secs(3))
+            }
+            // 主机没有 IPv6 路由时立即失败
+            Err(_) => {}
+            Ok(_) => panic!("丢弃前缀不应建连成功"),
+        }
+    }
+}
@@ -1,9 +1,56 @@
 // Copyright (c) 2025, vistone
 // All rights reserved.
 
+use std::fmt;
 use std::io;
+use std::time::{Duration, Instant};
 use thiserror::Error;
 
+/// WaitReason get 在池内等待的原因
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WaitReason {
+    /// WaitingForIdle 连接均已借出，等待连接归还
+    WaitingForIdle,
+    /// WaitingForCapacity 已达到连接数上限，且池中的空闲连接不满足本次请求，等待建连额度
+    WaitingForCapacity,
+    /// DialerBackoff 新建连接速率受限，等待令牌补充
+    DialerBackoff,
+    /// WaitingForDial 同时进行的建连数已达上限，等待其它建连完成
+    WaitingForDial,
+}
+
+/// LastDialError 最近一次 Dialer 建连失败的记录，用于定位池满超时的根因
+#[derive(Debug, Clone)]
+pub struct LastDialError {
+    /// Kind 底层 io::Error 的类型（非 io::Error 的自定义错误为 None）
+    pub kind: Option<io::ErrorKind>,
+    /// Message 错误信息
+    pub message: String,
+    /// OccurredAt 失败发生的时间
+    pub occurred_at: Instant,
+}
+
+impl LastDialError {
+    pub(crate) fn new(source: &(dyn std::error::Error + Send + Sync + 'static)) -> Self {
+        Self {
+            kind: source.downcast_ref::<io::Error>().map(io::Error::kind),
+            message: source.to_string(),
+            occurred_at: Instant::now(),
+        }
+    }
+
+    /// 距失败发生已过去的时间
+    pub fn elapsed(&self) -> Duration {
+        self.occurred_at.elapsed()
+    }
+}
+
+impl fmt::Display for LastDialError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} ({:?} 前)", self.message, self.elapsed())
+    }
+}
+
 /// 连接池相关错误定义
 #[derive(Error, Debug)]
 pub enum NetConnPoolError {
@@ -25,6 +72,9 @@ pub enum NetConnPoolError {
     #[error("无效连接 (connection_id: {connection_id}, reason: {reason})")]
     InvalidConnection { connection_id: u64, reason: String },
 
+    #[error("连接健康检查失败: {reason}")]
+    HealthCheckFailed { reason: String },
+
     #[error("连接不健康 (connection_id: {connection_id})")]
     ConnectionUnhealthy { connection_id: u64 },
 
@@ -37,8 +87,60 @@ pub enum NetConnPoolError {
         timeout: std::time::Duration,
     },
 
-    #[error("连接池已耗尽，无法创建新连接 (current: {current}, max: {max})")]
-    PoolExhausted { current: usize, max: usize },
+    #[error(
+        "连接池已耗尽，无法创建新连接 (current: {current}, max: {max}, waited: {waited:?}, wait_reason: {wait_reason:?}){}",
+        .last_dial_error.as_ref().map(|e| format!(", last_dial_error: {}", e)).unwrap_or_default()
+    )]
+    PoolExhausted {
+        current: usize,
+        max: usize,
+        waited: std::time::Duration,
+        wait_reason: WaitReason,
+        /// 最近一次建连失败的根因（最近一次建连成功后清空）
+        last_dial_error: Option<LastDialError>,
+    },
+
+    #[error("等待获取连接的队列已满 (waiters: {waiters}, max: {max})")]
+    QueueFull { waiters: usize, max: usize },
+
+    #[error("新建连接速率受限且等待超时 (waited: {waited:?})")]
+    CreateRateLimited { waited: std::time::Duration },
+
+    #[error("同时进行的建连数已达上限 (in_flight: {in_flight}, max: {max})")]
+    TooManyConcurrentDials { in_flight: usize, max: usize },
+
+    #[error("同时进行的建连数受限且等待超时 (waited: {waited:?})")]
+    DialConcurrencyLimited { waited: std::time::Duration },
+
+    #[error("进程文件描述符接近预算，拒绝新建连接 (open: {open}, budget: {budget})")]
+    FdBudgetExceeded { open: usize, budget: usize },
+
+    #[error("连接池已暂停新建连接，且等待空闲连接超时")]
+    DialingPaused,
+
+    #[error("连接池处于降级态，拒绝可丢弃的请求 (available: {available}, min_availability: {min_availability})")]
+    Degraded {
+        available: usize,
+        min_availability: usize,
+    },
+
+    #[error("获取连接已被取消 (waited: {waited:?})")]
+    Cancelled { waited: std::time::Duration },
+
+    #[error("没有可复用的空闲连接 (waited: {waited:?})")]
+    NoIdleConnection { waited: std::time::Duration },
+
+    #[error("分区不存在: {partition}")]
+    UnknownPartition { partition: String },
+
+    #[error("分区已熔断: {partition}")]
+    PartitionUnavailable { partition: String },
+
+    #[error("所有分区均处于熔断中 (partitions: {partitions})")]
+    NoAvailablePartition { partitions: usize },
+
+    #[error("连接池关闭后仍有资源未释放: {reason}")]
+    CloseVerificationFailed { reason: String },
 
     #[error("不支持的IP版本: {version:?}")]
     UnsupportedIPVersion { version: String },
@@ -52,10 +154,82 @@ pub enum NetConnPoolError {
     #[error("指定协议没有可用连接 (required: {required:?})")]
     NoConnectionForProtocol { required: String },
 
+    #[error("建立连接失败 (retryable: {retryable}): {source}")]
+    DialFailed {
+        source: Box<dyn std::error::Error + Send + Sync>,
+        retryable: bool,
+    },
+
+    #[error("后台线程创建失败 (thread: {thread}): {source}")]
+    ThreadSpawnFailed { thread: String, source: io::Error },
+
     #[error("IO错误: {0}")]
     IoError(#[from] io::Error),
 }
 
+impl NetConnPoolError {
+    /// 获取失败前在池内等待的原因（仅对池满、限速、建连并发受限与仅复用获取失败有意义）
+    pub fn wait_reason(&self) -> Option<WaitReason> {
+        match self {
+            Self::PoolExhausted { wait_reason, .. } => Some(*wait_reason),
+            Self::CreateRateLimited { .. } => Some(WaitReason::DialerBackoff),
+            Self::DialConcurrencyLimited { .. } => Some(WaitReason::WaitingForDial),
+            Self::NoIdleConnection { .. } => Some(WaitReason::WaitingForIdle),
+            _ => None,
+        }
+    }
+
+    /// 获取池满超时时附带的最近一次建连失败根因
+    pub fn last_dial_error(&self) -> Option<&LastDialError> {
+        match self {
+            Self::PoolExhausted {
+                last_dial_error, ..
+            } => last_dial_error.as_ref(),
+            _ => None,
+        }
+    }
+
+    /// 将 Dialer 返回的错误包装为 `DialFailed`，并根据原始错误判断是否可重试
+    ///
+    /// 仅当原始错误为表示网络暂时不可用的 `io::Error`（如 ConnectionRefused、TimedOut）时
+    /// 视为可重试；其他错误（包括自定义错误）视为永久错误。
+    pub fn dial_failed(source: Box<dyn std::error::Error + Send + Sync>) -> Self {
+        let retryable = source
+            .downcast_ref::<io::Error>()
+            .is_some_and(|e| is_retryable_io_kind(e.kind()));
+        Self::DialFailed { source, retryable }
+    }
+
+    /// 是否为可重试的错误
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            Self::DialFailed { retryable, .. } => *retryable,
+            Self::IoError(e) => is_retryable_io_kind(e.kind()),
+            _ => false,
+        }
+    }
+}
+
+/// 判断 io::ErrorKind 是否表示暂时性的网络错误
+fn is_retryable_io_kind(kind: io::ErrorKind) -> bool {
+    matches!(
+        kind,
+        io::ErrorKind::ConnectionRefused
+            | io::ErrorKind::ConnectionReset
+            | io::ErrorKind::ConnectionAborted
+            | io::ErrorKind::NotConnected
+            | io::ErrorKind::BrokenPipe
+            | io::ErrorKind::TimedOut
+            | io::ErrorKind::WouldBlock
+            | io::ErrorKind::Interrupted
+            | io::ErrorKind::AddrInUse
+            | io::ErrorKind::AddrNotAvailable
+            | io::ErrorKind::HostUnreachable
+            | io::ErrorKind::NetworkUnreachable
+            | io::ErrorKind::NetworkDown
+    )
+}
+
 impl PartialEq for NetConnPoolError {
     fn eq(&self, other: &Self) -> bool {
         match (self, other) {
@@ -100,12 +274,42 @@ impl PartialEq for NetConnPoolError {
                 Self::PoolExhausted {
                     current: c1,
                     max: m1,
+                    wait_reason: r1,
+                    ..
                 },
                 Self::PoolExhausted {
                     current: c2,
                     max: m2,
+                    wait_reason: r2,
+                    ..
                 },
-            ) => c1 == c2 && m1 == m2,
+            ) => c1 == c2 && m1 == m2 && r1 == r2,
+            (Self::QueueFull { max: m1, .. }, Self::QueueFull { max: m2, .. }) => m1 == m2,
+            (Self::CreateRateLimited { .. }, Self::CreateRateLimited { .. }) => true,
+            (
+                Self::TooManyConcurrentDials { max: m1, .. },
+                Self::TooManyConcurrentDials { max: m2, .. },
+            ) => m1 == m2,
+            (Self::DialConcurrencyLimited { .. }, Self::DialConcurrencyLimited { .. }) => true,
+            (Self::DialingPaused, Self::DialingPaused) => true,
+            (Self::NoIdleConnection { .. }, Self::NoIdleConnection { .. }) => true,
+            (Self::Degraded { .. }, Self::Degraded { .. }) => true,
+            (Self::Cancelled { .. }, Self::Cancelled { .. }) => true,
+            (Self::HealthCheckFailed { reason: r1 }, Self::HealthCheckFailed { reason: r2 }) => {
+                r1 == r2
+            }
+            (
+                Self::UnknownPartition { partition: p1 },
+                Self::UnknownPartition { partition: p2 },
+            ) => p1 == p2,
+            (
+                Self::PartitionUnavailable { partition: p1 },
+                Self::PartitionUnavailable { partition: p2 },
+            ) => p1 == p2,
+            (
+                Self::NoAvailablePartition { partitions: n1 },
+                Self::NoAvailablePartition { partitions: n2 },
+            ) => n1 == n2,
             (
                 Self::UnsupportedIPVersion { version: v1 },
                 Self::UnsupportedIPVersion { version: v2 },
@@ -122,6 +326,13 @@ impl PartialEq for NetConnPoolError {
                 Self::NoConnectionForProtocol { required: r1 },
                 Self::NoConnectionForProtocol { required: r2 },
             ) => r1 == r2,
+            (Self::DialFailed { retryable: r1, .. }, Self::DialFailed { retryable: r2, .. }) => {
+                r1 == r2
+            }
+            (
+                Self::ThreadSpawnFailed { thread: t1, .. },
+                Self::ThreadSpawnFailed { thread: t2, .. },
+            ) => t1 == t2,
             (Self::IoError(e1), Self::IoError(e2)) => e1.kind() == e2.kind(),
             _ => false,
         }
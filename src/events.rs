@@ -0,0 +1,28 @@
+// Copyright (c) 2025, vistone
+// All rights reserved.
+
+//! 连接池事件流
+//!
+//! 通过 `Config::event_sink` 把连接生命周期与故障事件统一发送到一个有界 channel，
+//! 便于在单独线程中集中消费（日志、监控），代替分散的各类回调。
+//! 发送使用 `try_send`：channel 已满或接收端已断开时丢弃事件并计入 `Pool::dropped_events`，
+//! 不会阻塞连接池。
+
+/// PoolEvent 连接池事件
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PoolEvent {
+    /// Created 新连接纳入连接池
+    Created { connection_id: u64 },
+    /// Borrowed 连接被借出
+    Borrowed { connection_id: u64 },
+    /// Returned 借出的连接被归还
+    Returned { connection_id: u64 },
+    /// Closed 连接被关闭并移出连接池
+    Closed { connection_id: u64 },
+    /// Leaked 连接首次被判定为疑似泄漏
+    Leaked { connection_id: u64 },
+    /// HealthFailed 连接健康检查失败
+    HealthFailed { connection_id: u64, reason: String },
+    /// CircuitOpen 分区熔断（PartitionedPool）
+    CircuitOpen { partition: String },
+}
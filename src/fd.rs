@@ -0,0 +1,102 @@
+// Copyright (c) 2025, vistone
+// All rights reserved.
+
+//! 进程文件描述符监控
+//!
+//! 获取当前进程已打开的文件描述符数与 RLIMIT_NOFILE 软限制，供 `fd_budget` 在建连前
+//! 判定是否接近上限：Linux 读取 `/proc/self/fd` 与 `/proc/self/limits`，macOS 读取
+//! `/dev/fd` 并调用 `getrlimit`。其他平台或读取失败时返回 None，不会 panic。
+
+/// 获取当前进程已打开的文件描述符数，不支持的平台返回 None
+pub fn open_fd_count() -> Option<usize> {
+    imp::open_fd_count()
+}
+
+/// 获取当前进程的 RLIMIT_NOFILE 软限制，不限制或不支持的平台返回 None
+pub fn fd_limit() -> Option<u64> {
+    imp::fd_limit()
+}
+
+#[cfg(any(target_os = "linux", target_os = "android", target_os = "macos"))]
+fn count_dir_entries(dir: &str) -> Option<usize> {
+    // 遍历目录本身会占用一个描述符，计数时扣除
+    let entries = std::fs::read_dir(dir).ok()?.count();
+    Some(entries.saturating_sub(1))
+}
+
+#[cfg(any(target_os = "linux", target_os = "android"))]
+mod imp {
+    pub(super) fn open_fd_count() -> Option<usize> {
+        super::count_dir_entries("/proc/self/fd")
+    }
+
+    pub(super) fn fd_limit() -> Option<u64> {
+        let limits = std::fs::read_to_string("/proc/self/limits").ok()?;
+        let line = limits.lines().find(|l| l.starts_with("Max open files"))?;
+        // 格式：Max open files  <soft>  <hard>  files，soft 为 unlimited 时解析失败返回 None
+        line["Max open files".len()..]
+            .split_whitespace()
+            .next()?
+            .parse()
+            .ok()
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod imp {
+    #[repr(C)]
+    struct Rlimit {
+        rlim_cur: u64,
+        rlim_max: u64,
+    }
+
+    const RLIMIT_NOFILE: i32 = 8;
+    const RLIM_INFINITY: u64 = (1 << 63) - 1;
+
+    extern "C" {
+        fn getrlimit(resource: i32, rlp: *mut Rlimit) -> i32;
+    }
+
+    pub(super) fn open_fd_count() -> Option<usize> {
+        super::count_dir_entries("/dev/fd")
+    }
+
+    pub(super) fn fd_limit() -> Option<u64> {
+        let mut limit = Rlimit {
+            rlim_cur: 0,
+            rlim_max: 0,
+        };
+        // SAFETY: limit 按 struct rlimit 布局定义，getrlimit 只写入该结构体
+        let ret = unsafe { getrlimit(RLIMIT_NOFILE, &mut limit) };
+        (ret == 0 && limit.rlim_cur != RLIM_INFINITY).then_some(limit.rlim_cur)
+    }
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "android", target_os = "macos")))]
+mod imp {
+    /// 不支持的平台
+    pub(super) fn open_fd_count() -> Option<usize> {
+        None
+    }
+
+    /// 不支持的平台
+    pub(super) fn fd_limit() -> Option<u64> {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[cfg(any(target_os = "linux", target_os = "macos"))]
+    fn test_open_fd_count_is_reported() {
+        // 至少有标准输入/输出/错误
+        let open = open_fd_count().unwrap();
+        assert!(open >= 3);
+        if let Some(limit) = fd_limit() {
+            assert!(limit >= open as u64);
+        }
+    }
+}
@@ -0,0 +1,94 @@
+// Copyright (c) 2025, vistone
+// All rights reserved.
+
+//! 连接池健康快照
+//!
+//! 将统计信息汇总为简单的 Healthy/Degraded/Unhealthy 判断，供上层熔断与告警使用。
+
+use std::fmt;
+
+/// HealthStatus 连接池整体健康状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum HealthStatus {
+    /// Healthy 健康
+    Healthy,
+    /// Degraded 降级：仍可服务，但存在容量不足、失败率偏高或连接泄漏等问题
+    Degraded,
+    /// Unhealthy 不健康：连接池已关闭或大部分获取请求失败
+    Unhealthy,
+}
+
+/// HealthIssue 导致连接池降级或不健康的原因
+#[derive(Debug, Clone, PartialEq)]
+pub enum HealthIssue {
+    /// Closed 连接池已关闭
+    Closed,
+    /// HighFailureRate 统计窗口内获取连接的失败率过高
+    HighFailureRate { failed: i64, total: i64 },
+    /// CapacityExhausted 统计窗口内出现了因连接池耗尽而失败的获取请求
+    CapacityExhausted { count: usize },
+    /// LeakedConnections 当前存在疑似泄漏（超过 connection_leak_timeout 仍未归还）的连接
+    LeakedConnections { count: i64 },
+}
+
+impl HealthIssue {
+    /// 该问题对应的健康状态
+    pub fn severity(&self) -> HealthStatus {
+        match self {
+            HealthIssue::Closed => HealthStatus::Unhealthy,
+            HealthIssue::HighFailureRate { failed, total } => {
+                if *failed * 2 >= *total {
+                    HealthStatus::Unhealthy
+                } else {
+                    HealthStatus::Degraded
+                }
+            }
+            HealthIssue::CapacityExhausted { .. } | HealthIssue::LeakedConnections { .. } => {
+                HealthStatus::Degraded
+            }
+        }
+    }
+}
+
+impl fmt::Display for HealthIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HealthIssue::Closed => write!(f, "连接池已关闭"),
+            HealthIssue::HighFailureRate { failed, total } => {
+                write!(f, "获取连接失败率过高 ({}/{})", failed, total)
+            }
+            HealthIssue::CapacityExhausted { count } => {
+                write!(f, "容量不足：{} 次获取因连接池耗尽失败", count)
+            }
+            HealthIssue::LeakedConnections { count } => {
+                write!(f, "存在 {} 条疑似泄漏的连接", count)
+            }
+        }
+    }
+}
+
+/// PoolHealth 连接池健康快照
+#[derive(Debug, Clone, PartialEq)]
+pub struct PoolHealth {
+    /// Status 综合健康状态（取各问题中最严重者）
+    pub status: HealthStatus,
+    /// Issues 导致降级/不健康的原因列表，健康时为空
+    pub issues: Vec<HealthIssue>,
+}
+
+impl PoolHealth {
+    /// 根据问题列表汇总健康快照
+    pub fn from_issues(issues: Vec<HealthIssue>) -> Self {
+        let status = issues
+            .iter()
+            .map(HealthIssue::severity)
+            .max()
+            .unwrap_or(HealthStatus::Healthy);
+        Self { status, issues }
+    }
+
+    /// 是否健康
+    pub fn is_healthy(&self) -> bool {
+        self.status == HealthStatus::Healthy
+    }
+}
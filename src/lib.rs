@@ -26,22 +26,52 @@
 // OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE
 // OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
+#[cfg(feature = "async")]
+pub mod async_pool;
 pub mod config;
 pub mod connection;
+pub mod diagnostics;
+pub mod dialer;
 pub mod errors;
+pub mod events;
+pub mod fd;
+pub mod health;
 pub mod ipversion;
+pub mod memory;
 pub mod mode;
+pub mod partition;
 pub mod pool;
 pub mod protocol;
+#[cfg(feature = "r2d2")]
+pub mod r2d2_adapter;
+pub mod registry;
+pub mod shared_udp;
 pub mod stats;
+#[cfg(feature = "tls")]
+pub mod tls;
 pub mod udp_utils;
 
+#[cfg(feature = "async")]
+pub use async_pool::{AsyncDialer, AsyncPool, AsyncPooledConnection};
 pub use config::{default_config, default_server_config};
-pub use config::{Config, ConfigBuilder, ConnectionType};
+pub use config::{
+    AdaptiveTimeout, Config, ConfigBuilder, ConnectionRole, ConnectionType, SocketOptions,
+};
 pub use connection::Connection;
+pub use diagnostics::{ConnectionSnapshot, PoolDump};
+pub use dialer::{happy_eyeballs_connect, happy_eyeballs_dialer, multi_addr_dialer};
 pub use errors::*;
+pub use events::PoolEvent;
+pub use health::{HealthIssue, HealthStatus, PoolHealth};
 pub use ipversion::{detect_ip_version, parse_ip_version, IPVersion};
 pub use mode::{parse_pool_mode, PoolMode};
+pub use partition::{CircuitBreaker, PartitionedPool};
 pub use pool::Pool;
 pub use protocol::{detect_protocol, parse_protocol, Protocol};
-pub use stats::{Stats, StatsCollector};
+#[cfg(feature = "r2d2")]
+pub use r2d2_adapter::R2d2Manager;
+pub use registry::PoolRegistry;
+pub use shared_udp::{SharedUdpPool, SharedUdpSocket};
+pub use stats::{BucketStat, GetTimeStats, SmoothedStats, Stats, StatsCollector};
+#[cfg(feature = "tls")]
+pub use tls::{tls_dialer, TlsStream};
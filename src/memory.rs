@@ -0,0 +1,157 @@
+// Copyright (c) 2025, vistone
+// All rights reserved.
+
+//! 进程内存监控
+//!
+//! 跨平台获取当前进程的常驻内存（RSS），供压力测试与监控工具复用：
+//! Linux 读取 `/proc/self/status`，macOS 使用 `task_info`，Windows 使用
+//! `GetProcessMemoryInfo`。其他平台或读取失败时返回 0，不会 panic。
+
+/// 获取当前进程的常驻内存大小（字节），不支持的平台返回 0
+pub fn current_rss_bytes() -> u64 {
+    imp::rss_bytes().map(|(current, _)| current).unwrap_or(0)
+}
+
+/// 获取当前进程的峰值常驻内存大小（字节），不支持的平台返回 0
+pub fn peak_rss_bytes() -> u64 {
+    imp::rss_bytes().map(|(_, peak)| peak).unwrap_or(0)
+}
+
+/// 获取当前进程的常驻内存大小（MB），便于测试报告直接打印
+pub fn current_rss_mb() -> f64 {
+    current_rss_bytes() as f64 / (1024.0 * 1024.0)
+}
+
+#[cfg(any(target_os = "linux", target_os = "android"))]
+mod imp {
+    /// 返回 (当前 RSS, 峰值 RSS)，单位字节
+    pub(super) fn rss_bytes() -> Option<(u64, u64)> {
+        let status = std::fs::read_to_string("/proc/self/status").ok()?;
+        let current = parse_kb(&status, "VmRSS:")?;
+        // 部分内核/容器不提供 VmHWM，此时以当前值代替
+        let peak = parse_kb(&status, "VmHWM:").unwrap_or(current);
+        Some((current, peak))
+    }
+
+    fn parse_kb(status: &str, key: &str) -> Option<u64> {
+        let line = status.lines().find(|l| l.starts_with(key))?;
+        let kb: u64 = line[key.len()..].split_whitespace().next()?.parse().ok()?;
+        Some(kb * 1024)
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod imp {
+    #[repr(C)]
+    #[derive(Default)]
+    #[allow(dead_code)]
+    struct TimeValue {
+        seconds: i32,
+        microseconds: i32,
+    }
+
+    #[repr(C)]
+    #[derive(Default)]
+    #[allow(dead_code)]
+    struct MachTaskBasicInfo {
+        virtual_size: u64,
+        resident_size: u64,
+        resident_size_max: u64,
+        user_time: TimeValue,
+        system_time: TimeValue,
+        policy: i32,
+        suspend_count: i32,
+    }
+
+    const MACH_TASK_BASIC_INFO: u32 = 20;
+    const KERN_SUCCESS: i32 = 0;
+
+    extern "C" {
+        static mach_task_self_: u32;
+        fn task_info(
+            target_task: u32,
+            flavor: u32,
+            task_info_out: *mut MachTaskBasicInfo,
+            task_info_out_count: *mut u32,
+        ) -> i32;
+    }
+
+    /// 返回 (当前 RSS, 峰值 RSS)，单位字节
+    pub(super) fn rss_bytes() -> Option<(u64, u64)> {
+        let mut info = MachTaskBasicInfo::default();
+        let mut count =
+            (std::mem::size_of::<MachTaskBasicInfo>() / std::mem::size_of::<u32>()) as u32;
+        // SAFETY: info 为按 mach_task_basic_info 布局定义的结构体，count 为其以 natural_t 计的长度
+        let ret =
+            unsafe { task_info(mach_task_self_, MACH_TASK_BASIC_INFO, &mut info, &mut count) };
+        (ret == KERN_SUCCESS).then_some((info.resident_size, info.resident_size_max))
+    }
+}
+
+#[cfg(windows)]
+mod imp {
+    #[repr(C)]
+    #[derive(Default)]
+    #[allow(dead_code)]
+    struct ProcessMemoryCounters {
+        cb: u32,
+        page_fault_count: u32,
+        peak_working_set_size: usize,
+        working_set_size: usize,
+        quota_peak_paged_pool_usage: usize,
+        quota_paged_pool_usage: usize,
+        quota_peak_non_paged_pool_usage: usize,
+        quota_non_paged_pool_usage: usize,
+        pagefile_usage: usize,
+        peak_pagefile_usage: usize,
+    }
+
+    #[link(name = "kernel32")]
+    extern "system" {
+        fn GetCurrentProcess() -> isize;
+        fn K32GetProcessMemoryInfo(
+            process: isize,
+            counters: *mut ProcessMemoryCounters,
+            cb: u32,
+        ) -> i32;
+    }
+
+    /// 返回 (当前 RSS, 峰值 RSS)，单位字节
+    pub(super) fn rss_bytes() -> Option<(u64, u64)> {
+        let mut counters = ProcessMemoryCounters::default();
+        let cb = std::mem::size_of::<ProcessMemoryCounters>() as u32;
+        counters.cb = cb;
+        // SAFETY: GetCurrentProcess 返回伪句柄无需关闭；counters 按 PROCESS_MEMORY_COUNTERS 布局定义
+        let ok = unsafe { K32GetProcessMemoryInfo(GetCurrentProcess(), &mut counters, cb) };
+        (ok != 0).then_some((
+            counters.working_set_size as u64,
+            counters.peak_working_set_size as u64,
+        ))
+    }
+}
+
+#[cfg(not(any(
+    target_os = "linux",
+    target_os = "android",
+    target_os = "macos",
+    windows
+)))]
+mod imp {
+    /// 不支持的平台
+    pub(super) fn rss_bytes() -> Option<(u64, u64)> {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[cfg(any(target_os = "linux", target_os = "macos", windows))]
+    fn test_rss_is_reported() {
+        let current = current_rss_bytes();
+        assert!(current > 0);
+        assert!(peak_rss_bytes() >= current);
+    }
+}
@@ -0,0 +1,257 @@
+// Copyright (c) 2025, vistone
+// All rights reserved.
+
+//! 分区连接池
+//!
+//! 把连接池按后端实例拆分为若干分区：每个分区是一个独立的 `Pool`（独立计数与上限），
+//! 并带有独立的熔断器。某个实例连续建连失败时熔断其分区，get 在熔断期内跳过该分区、
+//! 轮转到其余健康分区，避免单个故障实例拖慢整个池。
+
+use crate::config::Config;
+use crate::errors::{NetConnPoolError, Result};
+use crate::events::PoolEvent;
+use crate::pool::{Pool, PooledConnection};
+use crate::stats::Stats;
+use std::collections::HashSet;
+use std::sync::atomic::{AtomicU32, AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// CircuitBreaker 分区熔断参数
+///
+/// 分区连续 `failure_threshold` 次获取因后端故障（建连失败、IO 错误）失败后熔断
+/// `open_duration`；熔断到期后放行请求试探，再次失败立即重新熔断，成功一次即恢复。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CircuitBreaker {
+    /// FailureThreshold 触发熔断的连续失败次数
+    pub failure_threshold: u32,
+    /// OpenDuration 熔断持续时间
+    pub open_duration: Duration,
+}
+
+impl Default for CircuitBreaker {
+    fn default() -> Self {
+        Self {
+            failure_threshold: 3,
+            open_duration: Duration::from_secs(10),
+        }
+    }
+}
+
+/// 单个分区：独立的连接池与熔断状态
+#[derive(Debug)]
+struct Partition {
+    name: String,
+    pool: Pool,
+    // 连续失败次数，成功一次清零
+    failures: AtomicU32,
+    // 熔断截止时间，None 表示未熔断
+    open_until: Mutex<Option<Instant>>,
+}
+
+impl Partition {
+    /// 未熔断或熔断已到期（允许试探）
+    fn is_available(&self) -> bool {
+        self.open_until
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .is_none_or(|until| Instant::now() >= until)
+    }
+
+    fn is_open(&self) -> bool {
+        !self.is_available()
+    }
+
+    fn record_success(&self) {
+        if self.failures.swap(0, Ordering::AcqRel) > 0 {
+            *self.open_until.lock().unwrap_or_else(|e| e.into_inner()) = None;
+        }
+    }
+
+    fn record_failure(&self, breaker: &CircuitBreaker) {
+        let failures = self.failures.fetch_add(1, Ordering::AcqRel) + 1;
+        if failures >= breaker.failure_threshold {
+            *self.open_until.lock().unwrap_or_else(|e| e.into_inner()) =
+                Some(Instant::now() + breaker.open_duration);
+            // 熔断事件经该分区连接池的 event_sink 发出
+            self.pool.emit_event(PoolEvent::CircuitOpen {
+                partition: self.name.clone(),
+            });
+        }
+    }
+}
+
+/// 只有后端故障计入熔断；池满、限速等说明分区繁忙而非实例故障
+fn is_backend_failure(err: &NetConnPoolError) -> bool {
+    matches!(
+        err,
+        NetConnPoolError::DialFailed { .. } | NetConnPoolError::IoError(_)
+    )
+}
+
+/// PartitionedPool 按后端实例分区、分区独立熔断的连接池
+#[derive(Debug)]
+pub struct PartitionedPool {
+    partitions: Vec<Partition>,
+    breaker: CircuitBreaker,
+    // 轮转起点，把请求均摊到各健康分区
+    cursor: AtomicUsize,
+}
+
+impl PartitionedPool {
+    /// 按 `(分区名, 配置)` 为每个后端实例创建一个分区
+    ///
+    /// 分区名不能为空且不能重复；任一分区创建失败时返回该错误，已创建的分区随之关闭。
+    pub fn new<I, S>(partitions: I, breaker: CircuitBreaker) -> Result<Self>
+    where
+        I: IntoIterator<Item = (S, Config)>,
+        S: Into<String>,
+    {
+        if breaker.failure_threshold == 0 {
+            return Err(NetConnPoolError::InvalidConfig {
+                reason: "CircuitBreaker failure_threshold 必须大于0".to_string(),
+            });
+        }
+
+        let mut names = HashSet::new();
+        let mut built = Vec::new();
+        for (name, config) in partitions {
+            let name = name.into();
+            if name.is_empty() {
+                return Err(NetConnPoolError::InvalidConfig {
+                    reason: "分区名不能为空".to_string(),
+                });
+            }
+            if !names.insert(name.clone()) {
+                return Err(NetConnPoolError::InvalidConfig {
+                    reason: format!("分区名重复: {}", name),
+                });
+            }
+            built.push(Partition {
+                pool: Pool::new(config)?,
+                name,
+                failures: AtomicU32::new(0),
+                open_until: Mutex::new(None),
+            });
+        }
+        if built.is_empty() {
+            return Err(NetConnPoolError::InvalidConfig {
+                reason: "PartitionedPool 至少需要一个分区".to_string(),
+            });
+        }
+
+        Ok(Self {
+            partitions: built,
+            breaker,
+            cursor: AtomicUsize::new(0),
+        })
+    }
+
+    /// 从健康分区获取一个连接
+    ///
+    /// 从轮转起点依次尝试未熔断的分区，每个分区只尝试复用或新建、不等待；
+    /// 某分区因后端故障失败时计入其熔断并立即换下一个分区。所有分区都只是繁忙（如池满）时，
+    /// 在第一个繁忙分区上等待剩余的 `timeout`。
+    ///
+    /// # 返回值
+    /// - `Ok(PooledConnection)`: 成功获取连接
+    /// - `Err(NetConnPoolError::NoAvailablePartition)`: 所有分区都处于熔断中
+    /// - `Err(NetConnPoolError)`: 最后一个尝试的分区返回的错误
+    pub fn get(&self, timeout: Duration) -> Result<PooledConnection> {
+        let deadline = Instant::now() + timeout;
+        let count = self.partitions.len();
+        let start = self.cursor.fetch_add(1, Ordering::Relaxed);
+
+        let mut busy = None;
+        let mut last_err = None;
+        for offset in 0..count {
+            let partition = &self.partitions[(start + offset) % count];
+            if !partition.is_available() {
+                continue;
+            }
+            match self.get_in(partition, Duration::ZERO) {
+                Ok(conn) => return Ok(conn),
+                Err(e) => {
+                    if busy.is_none() && !is_backend_failure(&e) {
+                        busy = Some(partition);
+                    }
+                    last_err = Some(e);
+                }
+            }
+        }
+
+        if let Some(partition) = busy {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            if !remaining.is_zero() {
+                return self.get_in(partition, remaining);
+            }
+        }
+        Err(last_err.unwrap_or(NetConnPoolError::NoAvailablePartition { partitions: count }))
+    }
+
+    /// 从指定分区获取连接，分区熔断中时返回 `PartitionUnavailable`
+    pub fn get_from(&self, name: &str, timeout: Duration) -> Result<PooledConnection> {
+        let partition = self.find(name)?;
+        if !partition.is_available() {
+            return Err(NetConnPoolError::PartitionUnavailable {
+                partition: name.to_string(),
+            });
+        }
+        self.get_in(partition, timeout)
+    }
+
+    /// 在分区上获取连接，并按结果更新该分区的熔断状态
+    fn get_in(&self, partition: &Partition, timeout: Duration) -> Result<PooledConnection> {
+        let result = partition.pool.get_with_timeout(timeout);
+        match &result {
+            Ok(_) => partition.record_success(),
+            Err(e) if is_backend_failure(e) => partition.record_failure(&self.breaker),
+            Err(_) => {}
+        }
+        result
+    }
+
+    fn find(&self, name: &str) -> Result<&Partition> {
+        self.partitions
+            .iter()
+            .find(|p| p.name == name)
+            .ok_or_else(|| NetConnPoolError::UnknownPartition {
+                partition: name.to_string(),
+            })
+    }
+
+    /// 按名字获取分区的连接池
+    pub fn partition(&self, name: &str) -> Option<&Pool> {
+        self.find(name).ok().map(|p| &p.pool)
+    }
+
+    /// 所有分区名（按创建顺序）
+    pub fn partition_names(&self) -> Vec<&str> {
+        self.partitions.iter().map(|p| p.name.as_str()).collect()
+    }
+
+    /// 分区是否处于熔断中，分区不存在时返回 None
+    pub fn is_partition_open(&self, name: &str) -> Option<bool> {
+        self.find(name).ok().map(Partition::is_open)
+    }
+
+    /// 关闭所有分区，返回遇到的第一个错误
+    pub fn close(&self) -> Result<()> {
+        let mut first_err = None;
+        for partition in &self.partitions {
+            if let Err(e) = partition.pool.close() {
+                first_err.get_or_insert(e);
+            }
+        }
+        first_err.map_or(Ok(()), Err)
+    }
+
+    /// 各分区统计的汇总（见 `Stats::merge`）
+    pub fn aggregate_stats(&self) -> Stats {
+        let mut total = Stats::default();
+        for partition in &self.partitions {
+            total.merge(&partition.pool.stats());
+        }
+        total
+    }
+}
@@ -0,0 +1,62 @@
+// Copyright (c) 2025, vistone
+// All rights reserved.
+
+//! 自适应获取超时模块
+//!
+//! 维护最近若干次建连耗时的滑动窗口，以窗口内 P99 乘以倍数作为默认 get 的等待上限，
+//! 后端变慢时自动放宽、恢复后自动收紧。
+
+use crate::config::AdaptiveTimeout;
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+
+/// AdaptiveBudget 基于建连耗时分位的动态超时
+#[derive(Debug)]
+pub(crate) struct AdaptiveBudget {
+    params: AdaptiveTimeout,
+    ceiling: Duration,
+    samples: Mutex<VecDeque<Duration>>,
+    // 当前动态超时（纳秒），0 表示尚无样本
+    current: AtomicU64,
+}
+
+impl AdaptiveBudget {
+    /// 创建动态超时，结果不超过 `ceiling`（即 get_connection_timeout）
+    pub(crate) fn new(params: AdaptiveTimeout, ceiling: Duration) -> Self {
+        Self {
+            params,
+            ceiling,
+            samples: Mutex::new(VecDeque::with_capacity(params.window)),
+            current: AtomicU64::new(0),
+        }
+    }
+
+    /// 记录一次成功建连的耗时并重新计算动态超时
+    pub(crate) fn record(&self, dial_time: Duration) {
+        let mut samples = self.samples.lock().unwrap_or_else(|e| e.into_inner());
+        if samples.len() == self.params.window {
+            samples.pop_front();
+        }
+        samples.push_back(dial_time);
+
+        let mut sorted: Vec<Duration> = samples.iter().copied().collect();
+        sorted.sort_unstable();
+        let rank = ((sorted.len() as f64 * 0.99).ceil() as usize).clamp(1, sorted.len());
+        let budget = sorted[rank - 1]
+            .mul_f64(self.params.multiplier)
+            .max(self.params.min_timeout)
+            .min(self.ceiling);
+        self.current
+            .store(budget.as_nanos() as u64, Ordering::Relaxed);
+    }
+
+    /// 当前动态超时，尚无样本时返回 None
+    pub(crate) fn current(&self) -> Option<Duration> {
+        match self.current.load(Ordering::Relaxed) {
+            0 => None,
+            nanos => Some(Duration::from_nanos(nanos)),
+        }
+    }
+}
@@ -0,0 +1,68 @@
+// Copyright (c) 2025, vistone
+// All rights reserved.
+
+//! 批量借出的连接容器
+//!
+//! `Pool::get_many` 一次借出多条连接，Drop 时整批归还、只唤醒一次等待者，
+//! 降低扇出场景逐条归还带来的通知与桶操作开销。
+
+use super::{PoolInner, PooledConnection};
+use std::ops::{Deref, DerefMut};
+use std::sync::Weak;
+
+/// ConnectionBatch `Pool::get_many` 返回的连接批次
+///
+/// 以切片方式访问其中的连接；Drop 时整批归还。需要单独归还某条连接时可用 `into_vec` 拆开。
+#[derive(Debug)]
+pub struct ConnectionBatch {
+    conns: Vec<PooledConnection>,
+    pool: Weak<PoolInner>,
+}
+
+impl ConnectionBatch {
+    pub(super) fn with_capacity(pool: Weak<PoolInner>, capacity: usize) -> Self {
+        Self {
+            conns: Vec::with_capacity(capacity),
+            pool,
+        }
+    }
+
+    pub(super) fn push(&mut self, conn: PooledConnection) {
+        self.conns.push(conn);
+    }
+
+    /// 拆成独立的连接，之后各连接在各自 Drop 时逐条归还
+    pub fn into_vec(mut self) -> Vec<PooledConnection> {
+        std::mem::take(&mut self.conns)
+    }
+}
+
+impl Deref for ConnectionBatch {
+    type Target = [PooledConnection];
+    fn deref(&self) -> &Self::Target {
+        &self.conns
+    }
+}
+
+impl DerefMut for ConnectionBatch {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.conns
+    }
+}
+
+impl Drop for ConnectionBatch {
+    fn drop(&mut self) {
+        if self.conns.is_empty() {
+            return;
+        }
+        let Some(pool) = self.pool.upgrade() else {
+            return;
+        };
+        let conns = self
+            .conns
+            .iter_mut()
+            .map(PooledConnection::detach)
+            .collect();
+        pool.return_connections(conns);
+    }
+}
@@ -0,0 +1,51 @@
+// Copyright (c) 2025, vistone
+// All rights reserved.
+
+//! 连接供给迭代器
+//!
+//! 把连接池包装成 `Iterator`，便于以 `for conn in pool.iter(timeout)` 的方式持续取用连接。
+
+use super::{Pool, PooledConnection};
+use crate::errors::{NetConnPoolError, Result};
+use std::iter::FusedIterator;
+use std::time::Duration;
+
+/// PoolIter `Pool::iter` 返回的连接供给迭代器
+///
+/// 每次 `next` 调用一次 `get_with_timeout`，获取失败（如池满超时）时产出 `Err` 并继续；
+/// 连接池关闭后迭代结束，之后始终返回 `None`。
+#[derive(Debug)]
+pub struct PoolIter<'a> {
+    pool: &'a Pool,
+    timeout: Duration,
+    finished: bool,
+}
+
+impl<'a> PoolIter<'a> {
+    pub(super) fn new(pool: &'a Pool, timeout: Duration) -> Self {
+        Self {
+            pool,
+            timeout,
+            finished: false,
+        }
+    }
+}
+
+impl Iterator for PoolIter<'_> {
+    type Item = Result<PooledConnection>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.finished {
+            return None;
+        }
+        match self.pool.get_with_timeout(self.timeout) {
+            Err(NetConnPoolError::PoolClosed) => {
+                self.finished = true;
+                None
+            }
+            result => Some(result),
+        }
+    }
+}
+
+impl FusedIterator for PoolIter<'_> {}
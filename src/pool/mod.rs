@@ -1,32 +1,94 @@
 // Copyright (c) 2025, vistone
 // All rights reserved.
 
+mod adaptive;
+mod batch;
+mod iter;
 mod pooled_connection;
+mod rate_limiter;
+pub(crate) mod validation;
 
-use crate::config::{Config, ConnectionType};
+use crate::config::{Config, ConnectionRole, ConnectionType};
 use crate::connection::Connection;
-use crate::errors::{NetConnPoolError, Result};
+use crate::diagnostics::{ConnectionSnapshot, PoolDump};
+use crate::errors::{LastDialError, NetConnPoolError, Result, WaitReason};
+use crate::events::PoolEvent;
+use crate::health::{HealthIssue, PoolHealth};
 use crate::ipversion::IPVersion;
 use crate::mode::PoolMode;
 use crate::protocol::Protocol;
-use crate::stats::StatsCollector;
+use crate::stats::{BucketStat, StatsCollector};
 use crate::udp_utils::clear_udp_read_buffer;
+use adaptive::AdaptiveBudget;
 use crossbeam_queue::SegQueue;
+use rate_limiter::TokenBucket;
+use socket2::{SockRef, TcpKeepalive};
+use std::cell::RefCell;
 use std::collections::HashMap;
 use std::fmt;
-use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
-use std::sync::{Arc, Condvar, Mutex, RwLock, Weak};
+use std::net::{TcpStream, UdpSocket};
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+use std::sync::{mpsc, Arc, Condvar, Mutex, Once, RwLock, Weak};
 use std::thread;
 use std::time::{Duration, Instant};
 
-pub use pooled_connection::PooledConnection;
+pub use batch::ConnectionBatch;
+pub use iter::PoolIter;
+pub use pooled_connection::{PooledConnection, ReadHalf, WriteHalf};
+
+/// 借出前对空闲连接的额外断言（由 get_where 使用）
+type ConnectionPredicate<'a> = dyn Fn(&Connection) -> bool + 'a;
+
+/// 单次 get 的附加约束
+#[derive(Default)]
+struct GetOptions<'a> {
+    // 空闲连接需满足的断言（get_where）
+    predicate: Option<&'a ConnectionPredicate<'a>>,
+    // 只借出该角色的连接（get_role）
+    role: Option<ConnectionRole>,
+    // 池满等待时的优先级（get_with_priority）
+    priority: u8,
+    // 只复用空闲连接，绝不调用 dialer（get_reused_only）
+    reuse_only: bool,
+    // 总是新建且归还时关闭，绝不复用（get_oneshot）
+    oneshot: bool,
+    // 降级态下快速失败（get_sheddable）
+    sheddable: bool,
+    // 置位后等待中的 get 尽快返回 Cancelled（get_with_cancel）
+    cancel: Option<&'a AtomicBool>,
+    // 优先借出该 ID 的空闲连接，不可用时按普通 get 处理（get_sticky）
+    prefer: Option<u64>,
+    // 本次借用的泄漏判定时长，代替全局 connection_leak_timeout（get_with_lease）
+    lease: Option<Duration>,
+    // 等待建连许可（并发建连已满、限速、暂停建连）的上限，不超过 timeout（adaptive_timeout）
+    dial_budget: Option<Duration>,
+}
 
 /// Pool 连接池
-#[derive(Clone)]
+///
+/// 可廉价克隆，所有克隆共享同一个连接池；最后一个克隆被 Drop 时自动关闭连接池。
 pub struct Pool {
     inner: Arc<PoolInner>,
 }
 
+impl Clone for Pool {
+    fn clone(&self) -> Self {
+        self.inner.handles.fetch_add(1, Ordering::Relaxed);
+        Self {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl Drop for Pool {
+    fn drop(&mut self) {
+        // 后台线程与借出的连接只持有 Weak，最后一个用户句柄释放后不会再有人调用 close
+        if self.inner.handles.fetch_sub(1, Ordering::AcqRel) == 1 {
+            let _ = self.inner.shutdown(false);
+        }
+    }
+}
+
 // 确保 Pool 和 PooledConnection 可以安全地跨线程使用
 // 这些断言在编译期检查，如果类型不满足 Send + Sync 则编译失败
 const _: fn() = || {
@@ -37,12 +99,23 @@ const _: fn() = || {
 
 impl fmt::Debug for Pool {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // 只输出关键配置与运行时计数，不打印 dialer/回调等闭包
+        let config = &self.inner.config;
         f.debug_struct("Pool")
-            .field("closed", &self.inner.closed.load(Ordering::Relaxed))
+            .field("mode", &config.mode)
+            .field("max_connections", &config.max_connections)
+            .field("min_connections", &config.min_connections)
+            .field(
+                "total_count",
+                &self.inner.connection_count.load(Ordering::Relaxed),
+            )
             .field(
                 "active_count",
                 &self.inner.active_count.load(Ordering::Relaxed),
             )
+            .field("idle_count", &self.idle_count())
+            .field("closed", &self.inner.closed.load(Ordering::Relaxed))
+            .field("enable_stats", &config.enable_stats)
             .finish()
     }
 }
@@ -51,22 +124,103 @@ pub(crate) struct PoolInner {
     config: Config,
     // 所有存活的连接，用于管理生命周期和后台清理
     all_connections: RwLock<HashMap<u64, Arc<Connection>>>,
+    // all_connections 的长度镜像（仅在持有写锁时增减），供等待原因判断等无锁读取
+    connection_count: AtomicUsize,
+    // 已占用的连接额度（存活连接 + 正在建连的预占额度），建连前原子预占，超限时不调用 dialer
+    reserved_slots: AtomicUsize,
     // 空闲连接池，按 (Protocol, IPVersion) 分桶（使用无锁队列）
     // 0: TCP IPv4, 1: TCP IPv6, 2: UDP IPv4, 3: UDP IPv6
     idle_connections: [SegQueue<Arc<Connection>>; 4],
     // 每个桶的当前大小（原子计数器，用于 max_idle_connections 限制）
     idle_counts: [AtomicUsize; 4],
+    // 每桶最大空闲连接数（可在运行时通过 set_max_idle_connections 调整）
+    max_idle_connections: AtomicUsize,
     closed: AtomicBool,
     // 当前借出的连接数（不依赖 enable_stats）
     active_count: AtomicUsize,
+    // 按桶统计的借出连接数，与 active_count 同步维护，供 bucket_stats 使用
+    bucket_active_counts: [AtomicUsize; 4],
+    // 按协议统计的存活连接数（0: TCP, 1: UDP），用于每协议上限（不依赖 enable_stats）
+    protocol_counts: [AtomicUsize; 2],
+    // 新建连接速率限制（max_new_connections_per_sec > 0 时启用）
+    create_rate_limiter: Option<TokenBucket>,
+    // 正在进行的建连数（max_concurrent_dials > 0 时作为信号量计数）
+    dials_in_flight: AtomicUsize,
+    // 自适应获取超时（adaptive_timeout），按近期建连耗时分位计算默认 get 的等待上限
+    adaptive_budget: Option<AdaptiveBudget>,
+    // 因连接池耗尽而失败的获取次数（不依赖 enable_stats，用于健康快照）
+    exhausted_gets: AtomicUsize,
+    // 最近一次 Dialer 建连失败（最近一次建连成功后清空）
+    last_dial_error: Mutex<Option<LastDialError>>,
+    // 上一次健康快照时的计数，用于计算统计窗口内的增量
+    health_window: Mutex<HealthWindow>,
     // 用于在连接归还/池状态变化时唤醒 get() 等待者
     wait_lock: Mutex<()>,
+    // 池状态版本号：归还/移除连接时递增，等待者据此发现检查之后发生的变化
+    pool_version: AtomicU64,
+    // 当前在 get 中等待的线程数（用于 max_wait_queue 背压）
+    waiters: AtomicUsize,
+    // 等待者登记表（优先级 + 开始等待时间），用于 get_with_priority 的让行判定
+    wait_entries: Mutex<Vec<WaitEntry>>,
+    // 登记表中优先级大于0的等待者数；为0时跳过让行判定并使用 notify_one
+    priority_waiters: AtomicUsize,
+    next_waiter_id: AtomicU64,
+    // 多桶查找时的轮转起点，避免某个桶（如 IPv4）长期优先、其他桶的连接饥饿
+    bucket_cursor: AtomicUsize,
+    // 生效的文件描述符预算（Pool::new 时解析 fd_budget / fd_budget_ratio）
+    fd_budget: Option<usize>,
+    // 暂停建连开关：置位期间只复用已有空闲连接
+    dialing_paused: AtomicBool,
+    // 容量水位是否处于告警状态（on_high_watermark 边沿触发）
+    watermark_high: AtomicBool,
+    // 存活的 Pool 句柄数（克隆计数），归零时自动关闭
+    handles: AtomicUsize,
+    // 是否处于降级态（可用连接数低于 min_availability）
+    degraded: AtomicBool,
+    // event_sink 已满或已断开而丢弃的事件数
+    dropped_events: AtomicU64,
     wait_cv: Condvar,
     reaper_cv: Condvar,     // 用于 reaper 线程等待
     reaper_lock: Mutex<()>, // 用于 reaper_cv
+    // reaper 线程句柄，close() 时 join 以确认其已退出
+    reaper_handle: Mutex<Option<thread::JoinHandle<()>>>,
+    // 异步归还队列发送端（async_return）；close() 时取走，后台归还线程随之退出
+    return_queue: Mutex<Option<mpsc::Sender<Arc<Connection>>>>,
+    // lazy_init：保证后台线程只启动一次；接收端在启动前暂存于此
+    background_started: Once,
+    pending_return_rx: Mutex<Option<mpsc::Receiver<Arc<Connection>>>>,
+    // 后台建连任务（被淘汰连接的补建、影子连接的预建），由后台线程串行拨号，不阻塞调用方
+    background_dials: Mutex<BackgroundDials>,
     stats_collector: Option<Arc<StatsCollector>>,
+    // 由 shadow_dialer 创建的影子连接池，随主池一起关闭
+    shadow: Option<Pool>,
+}
+
+/// 健康快照统计窗口：只由 reaper 推进，health() 只读
+///
+/// 每个 health_window 周期把 pending 提升为 baseline 并重新采样 pending，
+/// health() 以 baseline 为起点，统计范围为最近 1～2 个周期。
+#[derive(Debug)]
+struct HealthWindow {
+    baseline: HealthCounts,
+    pending: HealthCounts,
+    rotated_at: Instant,
+}
+
+/// 健康快照窗口端点的累计计数
+#[derive(Debug, Default, Clone, Copy)]
+struct HealthCounts {
+    total_gets: i64,
+    failed_gets: i64,
+    exhausted_gets: usize,
 }
 
+/// 健康快照计算失败率所需的最少请求数，避免样本过少时误判
+const HEALTH_MIN_SAMPLES: i64 = 10;
+
+/// 可取消的 get 未配置 wait_poll_interval 时的单次等待上限，保证取消后及时醒来
+const CANCEL_POLL_INTERVAL: Duration = Duration::from_millis(10);
+
 impl fmt::Debug for PoolInner {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("PoolInner")
@@ -84,8 +238,19 @@ impl fmt::Debug for PoolInner {
                     self.idle_counts[3].load(Ordering::Relaxed),
                 ],
             )
+            .field(
+                "connection_count",
+                &self.connection_count.load(Ordering::Relaxed),
+            )
             .field("closed", &self.closed.load(Ordering::Relaxed))
             .field("active_count", &self.active_count.load(Ordering::Relaxed))
+            .field(
+                "protocol_counts",
+                &[
+                    self.protocol_counts[0].load(Ordering::Relaxed),
+                    self.protocol_counts[1].load(Ordering::Relaxed),
+                ],
+            )
             .finish()
     }
 }
@@ -116,16 +281,27 @@ impl Pool {
     pub fn new(mut config: Config) -> Result<Self> {
         config.apply_defaults();
         config.validate()?;
+        let shadow = config.take_shadow_config().map(Pool::new).transpose()?;
 
         let stats_collector = if config.enable_stats {
-            Some(Arc::new(StatsCollector::new()))
+            Some(Arc::new(StatsCollector::with_ewma_window(
+                config.stats_ewma_window,
+            )))
         } else {
             None
         };
 
+        let (return_queue, return_rx) = if config.async_return {
+            let (tx, rx) = mpsc::channel();
+            (Some(tx), Some(rx))
+        } else {
+            (None, None)
+        };
+
         let inner = Arc::new(PoolInner {
-            config,
             all_connections: RwLock::new(HashMap::new()),
+            connection_count: AtomicUsize::new(0),
+            reserved_slots: AtomicUsize::new(0),
             idle_connections: [
                 SegQueue::new(),
                 SegQueue::new(),
@@ -138,63 +314,189 @@ impl Pool {
                 AtomicUsize::new(0),
                 AtomicUsize::new(0),
             ],
+            max_idle_connections: AtomicUsize::new(config.max_idle_connections),
             closed: AtomicBool::new(false),
             active_count: AtomicUsize::new(0),
+            bucket_active_counts: [
+                AtomicUsize::new(0),
+                AtomicUsize::new(0),
+                AtomicUsize::new(0),
+                AtomicUsize::new(0),
+            ],
+            protocol_counts: [AtomicUsize::new(0), AtomicUsize::new(0)],
+            create_rate_limiter: (config.max_new_connections_per_sec > 0)
+                .then(|| TokenBucket::new(config.max_new_connections_per_sec)),
+            dials_in_flight: AtomicUsize::new(0),
+            adaptive_budget: config
+                .adaptive_timeout
+                .map(|params| AdaptiveBudget::new(params, config.get_connection_timeout)),
+            exhausted_gets: AtomicUsize::new(0),
+            last_dial_error: Mutex::new(None),
+            health_window: Mutex::new(HealthWindow {
+                baseline: HealthCounts::default(),
+                pending: HealthCounts::default(),
+                rotated_at: Instant::now(),
+            }),
             wait_lock: Mutex::new(()),
+            pool_version: AtomicU64::new(0),
+            waiters: AtomicUsize::new(0),
+            wait_entries: Mutex::new(Vec::new()),
+            priority_waiters: AtomicUsize::new(0),
+            next_waiter_id: AtomicU64::new(0),
+            bucket_cursor: AtomicUsize::new(0),
+            fd_budget: config.effective_fd_budget(),
+            dialing_paused: AtomicBool::new(false),
+            watermark_high: AtomicBool::new(false),
+            handles: AtomicUsize::new(1),
+            degraded: AtomicBool::new(false),
+            dropped_events: AtomicU64::new(0),
             wait_cv: Condvar::new(),
             reaper_cv: Condvar::new(),
             reaper_lock: Mutex::new(()),
+            reaper_handle: Mutex::new(None),
+            return_queue: Mutex::new(return_queue),
+            background_started: Once::new(),
+            pending_return_rx: Mutex::new(return_rx),
+            background_dials: Mutex::new(BackgroundDials::default()),
             stats_collector,
+            shadow,
+            config,
         });
 
-        // 启动后台清理线程
-        let weak_inner = Arc::downgrade(&inner);
-        thread::Builder::new()
-            .name("connection-pool-reaper".to_string())
-            .spawn(move || {
-                Self::reaper(weak_inner);
-            })
-            .map_err(NetConnPoolError::IoError)?;
+        // 快速失败预检：同步建一条连接，失败则不创建连接池
+        if inner.config.fail_fast_on_new && inner.config.mode == PoolMode::Client {
+            let conn = inner.create_connection(None, None, None)?;
+            inner.add_idle_connection(conn);
+        }
 
-        // 启动预热线程（min_connections）
-        // 仅客户端模式预热；服务器模式预热可能会阻塞在 accept 上。
-        if inner.config.mode == PoolMode::Client && inner.config.min_connections > 0 {
-            let weak_inner = Arc::downgrade(&inner);
-            let _ = thread::Builder::new()
-                .name("connection-pool-prewarmer".to_string())
-                .spawn(move || {
-                    Self::prewarm(weak_inner);
-                });
+        // 启动后台线程：任一线程创建失败（如达到系统线程上限）都让 Pool::new 返回
+        // ThreadSpawnFailed，避免出现"池创建成功但后台任务没起来"的静默不一致。
+        // lazy_init 时推迟到第一次 get
+        if !inner.config.lazy_init {
+            inner.ensure_background_threads()?;
         }
 
         Ok(Self { inner })
     }
 
+    fn spawn_background_threads(
+        inner: &Arc<PoolInner>,
+        return_rx: Option<mpsc::Receiver<Arc<Connection>>>,
+    ) -> Result<()> {
+        // 后台清理线程
+        let weak_inner = Arc::downgrade(inner);
+        let reaper = spawn_background("connection-pool-reaper", move || Self::reaper(weak_inner))?;
+        *inner
+            .reaper_handle
+            .lock()
+            .unwrap_or_else(|e| e.into_inner()) = Some(reaper);
+
+        // 异步归还线程（async_return）
+        if let Some(rx) = return_rx {
+            let weak_inner = Arc::downgrade(inner);
+            spawn_background("connection-pool-returner", move || {
+                Self::returner(weak_inner, rx)
+            })?;
+        }
+
+        // 周期性统计上报线程（on_stats）
+        if inner.config.on_stats.is_some() {
+            let weak_inner = Arc::downgrade(inner);
+            spawn_background("connection-pool-stats", move || {
+                Self::stats_reporter(weak_inner)
+            })?;
+        }
+
+        // 预热线程（min_connections / warm_ratio）
+        // 仅客户端模式预热；服务器模式预热可能会阻塞在 accept 上。
+        if inner.config.mode == PoolMode::Client && inner.config.warm_target() > 0 {
+            let weak_inner = Arc::downgrade(inner);
+            spawn_background("connection-pool-prewarmer", move || {
+                Self::prewarm(weak_inner)
+            })?;
+        }
+        Ok(())
+    }
+
     fn prewarm(inner: Weak<PoolInner>) {
         let pool = match inner.upgrade() {
             Some(p) => p,
             None => return,
         };
 
-        let target = pool.config.min_connections;
+        let target = pool.config.warm_target();
         drop(pool);
 
-        for _ in 0..target {
+        let mut created = 0;
+        while created < target {
             let pool = match inner.upgrade() {
                 Some(p) => p,
                 None => return,
             };
-            if pool.is_closed() {
+            // 最后一个用户句柄已释放时立即停止：句柄计数先于 close 标志变化，
+            // 避免 Pool 被 drop 后仍继续建连
+            if pool.is_closed() || pool.handles.load(Ordering::Acquire) == 0 {
                 return;
             }
 
             // 预热只做 best-effort：创建失败不影响 Pool::new
-            if let Ok(conn) = pool.create_connection(None, None) {
-                pool.add_idle_connection(conn);
-            } else {
+            match pool.create_connection(None, None, None) {
+                Ok(conn) => {
+                    pool.add_idle_connection(conn);
+                    created += 1;
+                }
+                // 预热同样受 max_new_connections_per_sec 限制：等到下一个令牌再继续
+                Err(NetConnPoolError::CreateRateLimited { .. }) => pool.wait_for_create_token(),
                 // dialer 可能暂时不可用（例如测试场景未启动服务），直接停止预热
+                Err(_) => return,
+            }
+        }
+    }
+
+    /// 异步归还任务：在后台完成归还连接的缓冲区清理、on_return 与放回空闲池
+    fn returner(inner: Weak<PoolInner>, rx: mpsc::Receiver<Arc<Connection>>) {
+        // 发送端随 close() 或连接池释放而销毁，此时 recv 返回错误，线程退出
+        while let Ok(conn) = rx.recv() {
+            match inner.upgrade() {
+                Some(pool) => pool.finish_return(conn),
+                None => {
+                    let _ = conn.close();
+                    return;
+                }
+            }
+        }
+    }
+
+    /// 周期性统计上报任务：每隔 stats_interval 采样一次并调用 on_stats
+    fn stats_reporter(inner: Weak<PoolInner>) {
+        loop {
+            let pool = match inner.upgrade() {
+                Some(p) => p,
+                None => return,
+            };
+            if pool.is_closed() {
                 return;
             }
+
+            // 与 reaper 共用 Condvar，关闭时立即唤醒
+            let guard = pool.reaper_lock.lock().unwrap_or_else(|e| e.into_inner());
+            let (guard, timeout_result) = match pool
+                .reaper_cv
+                .wait_timeout(guard, pool.config.stats_interval)
+            {
+                Ok(result) => result,
+                Err(e) => e.into_inner(),
+            };
+            drop(guard);
+
+            // 被唤醒（关闭或伪唤醒）时不上报，回到循环开头复查状态
+            if pool.is_closed() || !timeout_result.timed_out() {
+                continue;
+            }
+
+            if let (Some(on_stats), Some(stats)) = (&pool.config.on_stats, &pool.stats_collector) {
+                on_stats(&stats.get_stats());
+            }
         }
     }
 
@@ -210,17 +512,17 @@ impl Pool {
                 break;
             }
 
-            let interval = if pool.config.health_check_interval.is_zero() {
-                Duration::from_secs(1)
-            } else {
-                pool.config.health_check_interval
-            };
+            let interval = pool.reaper_interval();
 
             // 使用 Condvar 等待，可以在池关闭时立即唤醒
             let guard = match pool.reaper_lock.lock() {
                 Ok(g) => g,
                 Err(_) => return, // 锁被 poison，退出
             };
+            // 持锁复查：close() 先加锁再通知，避免关闭通知落在检查与 wait 之间而多睡一个周期
+            if pool.is_closed() {
+                return;
+            }
             let (guard, timeout_result) = match pool.reaper_cv.wait_timeout(guard, interval) {
                 Ok(result) => result,
                 Err(_) => return, // 锁被 poison，退出
@@ -257,11 +559,15 @@ impl Pool {
             }
 
             pool.cleanup();
+            pool.advance_health_window();
         }
     }
 
     /// 获取一个连接（自动选择IP版本和协议）
     ///
+    /// 优先复用任意协议、任意 IP 版本的空闲连接，各分桶之间轮转查找起点，混合池中不会固定偏向 TCP；
+    /// 没有可用空闲连接时以 `None` 调用 Dialer，由 Dialer 决定新建连接的协议。
+    ///
     /// # 返回值
     /// - `Ok(PooledConnection)`: 成功获取连接
     /// - `Err(NetConnPoolError)`: 获取失败（超时、池已关闭、连接池耗尽等）
@@ -284,7 +590,7 @@ impl Pool {
     /// drop(conn); // 自动归还
     /// ```
     pub fn get(&self) -> Result<PooledConnection> {
-        self.get_with_timeout(self.inner.config.get_connection_timeout)
+        self.get_default(None, None)
     }
 
     /// 尝试获取连接（非阻塞）
@@ -294,7 +600,7 @@ impl Pool {
     ///
     /// # 返回值
     /// - `Ok(PooledConnection)`: 成功获取连接
-    /// - `Err(NetConnPoolError::PoolExhausted)`: 池已耗尽
+    /// - `Err(NetConnPoolError::PoolExhausted)`: 池已耗尽（`waited` 为实际耗时）
     /// - `Err(NetConnPoolError::PoolClosed)`: 池已关闭
     pub fn try_get(&self) -> Result<PooledConnection> {
         self.get_with_timeout(Duration::ZERO)
@@ -309,7 +615,7 @@ impl Pool {
 
     /// GetIPv4 获取一个IPv4连接
     pub fn get_ipv4(&self) -> Result<PooledConnection> {
-        self.get_with_ip_version(IPVersion::IPv4, self.inner.config.get_connection_timeout)
+        self.get_default(None, Some(IPVersion::IPv4))
     }
 
     /// 获取一个IPv6连接
@@ -318,7 +624,7 @@ impl Pool {
     /// - `Ok(PooledConnection)`: 成功获取IPv6连接
     /// - `Err(NetConnPoolError)`: 获取失败（超时、没有可用IPv6连接等）
     pub fn get_ipv6(&self) -> Result<PooledConnection> {
-        self.get_with_ip_version(IPVersion::IPv6, self.inner.config.get_connection_timeout)
+        self.get_default(None, Some(IPVersion::IPv6))
     }
 
     /// 获取一个TCP连接
@@ -327,7 +633,7 @@ impl Pool {
     /// - `Ok(PooledConnection)`: 成功获取TCP连接
     /// - `Err(NetConnPoolError)`: 获取失败（超时、没有可用TCP连接等）
     pub fn get_tcp(&self) -> Result<PooledConnection> {
-        self.get_with_protocol(Protocol::TCP, self.inner.config.get_connection_timeout)
+        self.get_default(Some(Protocol::TCP), None)
     }
 
     /// 获取一个UDP连接
@@ -336,13 +642,32 @@ impl Pool {
     /// - `Ok(PooledConnection)`: 成功获取UDP连接
     /// - `Err(NetConnPoolError)`: 获取失败（超时、没有可用UDP连接等）
     pub fn get_udp(&self) -> Result<PooledConnection> {
-        self.get_with_protocol(Protocol::UDP, self.inner.config.get_connection_timeout)
+        self.get_default(Some(Protocol::UDP), None)
+    }
+
+    /// 使用默认超时获取连接：等待上限为 get_connection_timeout，
+    /// 启用 adaptive_timeout 时等待建连许可的时间另受动态超时限制
+    fn get_default(
+        &self,
+        protocol: Option<Protocol>,
+        ip_version: Option<IPVersion>,
+    ) -> Result<PooledConnection> {
+        let options = GetOptions {
+            dial_budget: self.inner.dial_budget(),
+            ..GetOptions::default()
+        };
+        self.inner.get_connection(
+            protocol,
+            ip_version,
+            self.inner.config.get_connection_timeout,
+            options,
+        )
     }
 
     /// 获取指定协议的连接
     ///
     /// # 参数
-    /// - `protocol`: 协议类型（TCP 或 UDP）
+    /// - `protocol`: 协议类型（TCP 或 UDP；`Unknown` 表示不限制协议，行为与 `get` 相同）
     /// - `timeout`: 获取连接的超时时间
     ///
     /// # 返回值
@@ -353,7 +678,8 @@ impl Pool {
         protocol: Protocol,
         timeout: Duration,
     ) -> Result<PooledConnection> {
-        self.inner.get_connection(Some(protocol), None, timeout)
+        self.inner
+            .get_connection(Some(protocol), None, timeout, GetOptions::default())
     }
 
     /// 获取指定IP版本的连接
@@ -370,7 +696,73 @@ impl Pool {
         ip_version: IPVersion,
         timeout: Duration,
     ) -> Result<PooledConnection> {
-        self.inner.get_connection(None, Some(ip_version), timeout)
+        self.inner
+            .get_connection(None, Some(ip_version), timeout, GetOptions::default())
+    }
+
+    /// 按协议与IP版本组合精确获取连接
+    ///
+    /// 任一参数为 `None`（或 `Unknown`）表示不限制该维度，
+    /// 例如 `get_filtered(Some(Protocol::UDP), Some(IPVersion::IPv6), timeout)` 只会命中 UDP/IPv6 桶。
+    ///
+    /// # 参数
+    /// - `protocol`: 协议类型约束
+    /// - `ip_version`: IP版本约束
+    /// - `timeout`: 获取连接的超时时间
+    ///
+    /// # 返回值
+    /// - `Ok(PooledConnection)`: 成功获取符合条件的连接
+    /// - `Err(NetConnPoolError)`: 获取失败（超时、新建连接不满足条件等）
+    pub fn get_filtered(
+        &self,
+        protocol: Option<Protocol>,
+        ip_version: Option<IPVersion>,
+        timeout: Duration,
+    ) -> Result<PooledConnection> {
+        self.inner
+            .get_connection(protocol, ip_version, timeout, GetOptions::default())
+    }
+
+    /// 获取一个满足自定义断言的连接
+    ///
+    /// 从空闲池取出连接并通过内置有效性检查后，再执行 `predicate`；
+    /// 不满足的连接会放回空闲池，继续查找下一条。空闲池中找不到时，
+    /// 建连与超时行为与 `get_with_timeout` 一致。
+    ///
+    /// # 参数
+    /// - `predicate`: 对候选连接的断言，返回 `true` 表示可借出
+    /// - `timeout`: 获取连接的超时时间
+    ///
+    /// # 注意
+    /// `predicate` 只作用于空闲连接的复用：空闲池中找不到满足条件的连接时，
+    /// 新建的连接不经过 `predicate` 直接借出。依赖会话状态等条件的调用方
+    /// 需要在拿到连接后自行检查 `reuse_count() == 0` 的新连接。
+    pub fn get_where<F>(&self, predicate: F, timeout: Duration) -> Result<PooledConnection>
+    where
+        F: Fn(&Connection) -> bool,
+    {
+        let options = GetOptions {
+            predicate: Some(&predicate),
+            ..GetOptions::default()
+        };
+        self.inner.get_connection(None, None, timeout, options)
+    }
+
+    /// 获取一个指定角色（只读/只写）的连接
+    ///
+    /// 只借出标注为 `role` 的空闲连接；没有可用连接时用 `role_dialer` 新建并标注该角色。
+    /// 带角色的连接归还后仍只会被同角色的 `get_role` 借出。
+    ///
+    /// # 返回值
+    /// - `Ok(PooledConnection)`: 成功获取该角色的连接
+    /// - `Err(NetConnPoolError::InvalidConfig)`: 未配置 `role_dialer` 且没有该角色的空闲连接
+    /// - `Err(NetConnPoolError)`: 其他获取失败（超时、池已关闭等）
+    pub fn get_role(&self, role: ConnectionRole, timeout: Duration) -> Result<PooledConnection> {
+        let options = GetOptions {
+            role: Some(role),
+            ..GetOptions::default()
+        };
+        self.inner.get_connection(None, None, timeout, options)
     }
 
     /// 获取一个连接（带超时，自动选择IP版本和协议）
@@ -380,9 +772,299 @@ impl Pool {
     ///
     /// # 返回值
     /// - `Ok(PooledConnection)`: 成功获取连接
-    /// - `Err(NetConnPoolError)`: 获取失败（超时、池已关闭等）
+    /// - `Err(NetConnPoolError::PoolExhausted)`: 池已满且在 `timeout` 内没有连接归还
+    /// - `Err(NetConnPoolError::GetConnectionTimeout)`: 反复丢弃失效的空闲连接等重试耗尽了 `timeout`
+    /// - `Err(NetConnPoolError)`: 其他获取失败（池已关闭、建连失败等）
     pub fn get_with_timeout(&self, timeout: Duration) -> Result<PooledConnection> {
-        self.inner.get_connection(None, None, timeout)
+        self.inner
+            .get_connection(None, None, timeout, GetOptions::default())
+    }
+
+    /// 只复用空闲连接获取，绝不调用 dialer 新建连接
+    ///
+    /// 适用于建连成本高或会触发后端限流、宁可拿不到也不现场建连的场景。
+    /// 空闲池为空时在 `timeout` 内等待连接归还，`timeout` 为 0 时立即返回。
+    ///
+    /// # 返回值
+    /// - `Ok(PooledConnection)`: 成功复用一条空闲连接
+    /// - `Err(NetConnPoolError::NoIdleConnection)`: 在 `timeout` 内没有可复用的空闲连接
+    /// - `Err(NetConnPoolError)`: 其他获取失败（池已关闭、等待队列已满等）
+    pub fn get_reused_only(&self, timeout: Duration) -> Result<PooledConnection> {
+        let options = GetOptions {
+            reuse_only: true,
+            ..GetOptions::default()
+        };
+        self.inner.get_connection(None, None, timeout, options)
+    }
+
+    /// 以单独的租约借出连接：持有超过 `lease` 即视为泄漏
+    ///
+    /// 本次借用的泄漏判定（计入 leaked_connections、发出 Leaked 事件、force_reclaim_on_leak
+    /// 下的强制回收）按 `lease` 而非全局 `connection_leak_timeout` 进行，适合已知耗时
+    /// 远短于或远长于全局值的请求。`lease` 为 0 时本次借用不做泄漏判定。
+    pub fn get_with_lease(&self, timeout: Duration, lease: Duration) -> Result<PooledConnection> {
+        let options = GetOptions {
+            lease: Some(lease),
+            ..GetOptions::default()
+        };
+        self.inner.get_connection(None, None, timeout, options)
+    }
+
+    /// 获取一条一次性连接：总是新建，Drop 时直接关闭而不放回空闲池
+    ///
+    /// 适用于带特殊会话状态或需要隔离、绝不能复用的请求。一次性连接借出期间计入
+    /// max_connections，但从不进入空闲池，也不占用 max_idle_connections。
+    /// 池满时在 `timeout` 内等待建连额度。
+    ///
+    /// # 返回值
+    /// - `Ok(PooledConnection)`: 新建的一次性连接
+    /// - `Err(NetConnPoolError::PoolExhausted)`: 池已满且在 `timeout` 内没有腾出额度
+    /// - `Err(NetConnPoolError)`: 其他获取失败（池已关闭、建连失败等）
+    pub fn get_oneshot(&self, timeout: Duration) -> Result<PooledConnection> {
+        let options = GetOptions {
+            oneshot: true,
+            ..GetOptions::default()
+        };
+        self.inner.get_connection(None, None, timeout, options)
+    }
+
+    /// 一次借出 `count` 条连接，返回 Drop 时整批归还的 `ConnectionBatch`
+    ///
+    /// 各连接共享同一个 `timeout` 截止时间；任一条获取失败时，已借出的连接整批归还并返回该错误。
+    pub fn get_many(&self, count: usize, timeout: Duration) -> Result<ConnectionBatch> {
+        let deadline = Instant::now() + timeout;
+        let mut batch = ConnectionBatch::with_capacity(Arc::downgrade(&self.inner), count);
+        for _ in 0..count {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            batch.push(self.get_with_timeout(remaining)?);
+        }
+        Ok(batch)
+    }
+
+    /// 返回连续获取连接的迭代器，每次 `next` 以 `timeout` 调用 `get_with_timeout`
+    ///
+    /// 获取失败时产出 `Err` 并可继续迭代；连接池关闭后迭代结束。
+    /// 注意迭代器本身不持有连接，产出的连接需要在使用完后 drop 归还，否则池满后每次都会等待到超时。
+    ///
+    /// # 示例
+    /// ```rust,no_run
+    /// # fn demo(pool: &netconnpool::Pool) {
+    /// use std::time::Duration;
+    ///
+    /// for conn in pool.iter(Duration::from_secs(1)) {
+    ///     match conn {
+    ///         Ok(conn) => println!("got connection {}", conn.id()),
+    ///         Err(e) => eprintln!("get failed: {}", e),
+    ///     }
+    /// }
+    /// # }
+    /// ```
+    pub fn iter(&self, timeout: Duration) -> PoolIter<'_> {
+        PoolIter::new(self, timeout)
+    }
+
+    /// 获取连接，等待期间可由其它线程置位 `cancel` 取消
+    ///
+    /// 等待被切成不超过 wait_poll_interval（未配置时为 10ms）的片，每片醒来检查 `cancel`，
+    /// 因此上游取消后等待中的线程能及时退出而不必等满 `timeout`。进行中的建连不会被打断。
+    ///
+    /// # 返回值
+    /// - `Ok(PooledConnection)`: 成功获取连接
+    /// - `Err(NetConnPoolError::Cancelled)`: 获取前或等待期间 `cancel` 被置位
+    /// - `Err(NetConnPoolError)`: 其他获取失败（池已满超时、池已关闭、建连失败等）
+    pub fn get_with_cancel(
+        &self,
+        timeout: Duration,
+        cancel: Arc<AtomicBool>,
+    ) -> Result<PooledConnection> {
+        let options = GetOptions {
+            cancel: Some(&cancel),
+            ..GetOptions::default()
+        };
+        self.inner.get_connection(None, None, timeout, options)
+    }
+
+    /// 获取连接，优先借回本线程上次通过 get_sticky 借到的那条连接
+    ///
+    /// 适用于同一逻辑会话的连续请求需要落在同一条连接上（延续连接上的状态）的场景。
+    /// 上次的连接已被其他线程借走、已关闭或不再健康时，与 `get_with_timeout` 相同。
+    /// 粘性只是尽力而为：需要严格绑定时应持有连接而不是归还。
+    pub fn get_sticky(&self, timeout: Duration) -> Result<PooledConnection> {
+        let key = Arc::as_ptr(&self.inner) as usize;
+        let prefer = STICKY_CONNECTIONS.with(|last| {
+            let mut last = last.borrow_mut();
+            // 顺带清理已释放连接池的记录，长期存活的线程不会无限累积
+            last.retain(|_, (pool, _)| pool.strong_count() > 0);
+            last.get(&key).map(|(_, id)| *id)
+        });
+        let options = GetOptions {
+            prefer,
+            ..GetOptions::default()
+        };
+        let conn = self.inner.get_connection(None, None, timeout, options)?;
+        STICKY_CONNECTIONS.with(|last| {
+            last.borrow_mut()
+                .insert(key, (Arc::downgrade(&self.inner), conn.id()))
+        });
+        Ok(conn)
+    }
+
+    /// 获取主连接，并附带一条来自 shadow_dialer 的影子连接
+    ///
+    /// 用于灰度/对拍：调用方把请求同时发往两条连接。影子连接只复用影子池中现成的空闲连接，
+    /// 不在调用线程上建连：影子池没有空闲连接时本次为 None，并在后台新建一条供后续调用使用，
+    /// 影子侧的延迟与失败都不影响主连接。未配置 shadow_dialer 时始终为 None。
+    /// 两条连接分别归还各自的连接池。
+    ///
+    /// # 返回值
+    /// - `Ok((PooledConnection, Option<PooledConnection>))`: 主连接与可选的影子连接
+    /// - `Err(NetConnPoolError)`: 主连接获取失败（与 `get_with_timeout` 相同）
+    pub fn get_with_shadow(
+        &self,
+        timeout: Duration,
+    ) -> Result<(PooledConnection, Option<PooledConnection>)> {
+        let primary = self.get_with_timeout(timeout)?;
+        let shadow = self.inner.shadow.as_ref().and_then(|shadow| {
+            let conn = shadow.get_reused_only(Duration::ZERO).ok();
+            if conn.is_none() {
+                shadow.inner.dial_idle_in_background();
+            }
+            conn
+        });
+        Ok((primary, shadow))
+    }
+
+    /// 影子连接池（配置了 shadow_dialer 时），可用于查看影子侧的统计
+    pub fn shadow(&self) -> Option<&Pool> {
+        self.inner.shadow.as_ref()
+    }
+
+    /// 获取可丢弃请求的连接：连接池处于降级态时立即返回 `Degraded`，把剩余连接留给重要请求
+    ///
+    /// 未处于降级态时与 `get_with_timeout` 相同。降级态由 `min_availability` 判定。
+    pub fn get_sheddable(&self, timeout: Duration) -> Result<PooledConnection> {
+        let options = GetOptions {
+            sheddable: true,
+            ..GetOptions::default()
+        };
+        self.inner.get_connection(None, None, timeout, options)
+    }
+
+    /// 是否处于降级态（可用连接数低于 min_availability）
+    pub fn is_degraded(&self) -> bool {
+        self.inner.degraded.load(Ordering::Acquire)
+    }
+
+    /// 按优先级获取连接（数值越大越优先，其他 get 方法的优先级为 0）
+    ///
+    /// 池满时存在更高优先级的等待者，低优先级请求会让行，连接归还时优先被高优先级请求拿到。
+    /// 每等待一个 `priority_aging` 周期有效优先级提升一级，低优先级请求不会被永久饿死。
+    /// 让行判定不区分协议/IP 版本，高优先级请求等待其他类型连接时低优先级请求同样让行。
+    pub fn get_with_priority(&self, priority: u8, timeout: Duration) -> Result<PooledConnection> {
+        let options = GetOptions {
+            priority,
+            ..GetOptions::default()
+        };
+        self.inner.get_connection(None, None, timeout, options)
+    }
+
+    /// 借出一条连接执行闭包，结束后自动归还
+    ///
+    /// 闭包的返回值原样透传；闭包 panic 时连接同样会在栈展开过程中归还，不会泄漏。
+    ///
+    /// # 参数
+    /// - `f`: 使用连接的闭包
+    /// - `timeout`: 获取连接的超时时间
+    pub fn with_connection<T, F>(&self, f: F, timeout: Duration) -> Result<T>
+    where
+        F: FnOnce(&Connection) -> T,
+    {
+        let conn = self.get_with_timeout(timeout)?;
+        Ok(f(&conn))
+    }
+
+    /// 借出一条 TCP 连接执行闭包，结束后自动归还（语义同 [`Pool::with_connection`]）
+    pub fn with_tcp<T, F>(&self, f: F, timeout: Duration) -> Result<T>
+    where
+        F: FnOnce(&TcpStream) -> T,
+    {
+        let conn = self.get_with_protocol(Protocol::TCP, timeout)?;
+        let stream = conn
+            .tcp_conn()
+            .ok_or_else(|| NetConnPoolError::NoConnectionForProtocol {
+                required: format!("{:?}", Protocol::TCP),
+            })?;
+        Ok(f(stream))
+    }
+
+    /// 借出一条 UDP 连接执行闭包，结束后自动归还（语义同 [`Pool::with_connection`]）
+    pub fn with_udp<T, F>(&self, f: F, timeout: Duration) -> Result<T>
+    where
+        F: FnOnce(&UdpSocket) -> T,
+    {
+        let conn = self.get_with_protocol(Protocol::UDP, timeout)?;
+        let socket = conn
+            .udp_conn()
+            .ok_or_else(|| NetConnPoolError::NoConnectionForProtocol {
+                required: format!("{:?}", Protocol::UDP),
+            })?;
+        Ok(f(socket))
+    }
+
+    /// 获取连接，对可重试错误自动退避重试
+    ///
+    /// 仅对 [`NetConnPoolError::is_retryable`] 为 true 的瞬时错误（如建连被拒绝/重置）重试，
+    /// 退避时间从 `backoff` 开始每次翻倍；池已关闭、配置错误、池耗尽等错误立即返回。
+    /// 所有尝试（含等待与退避）的总耗时不超过 `timeout`。
+    ///
+    /// # 参数
+    /// - `timeout`: 整体耗时预算
+    /// - `max_attempts`: 最大尝试次数（0 视为 1）
+    /// - `backoff`: 首次重试前的退避时间
+    pub fn get_retry(
+        &self,
+        timeout: Duration,
+        max_attempts: usize,
+        backoff: Duration,
+    ) -> Result<PooledConnection> {
+        let deadline = Instant::now() + timeout;
+        let mut delay = backoff;
+        let mut attempt = 1;
+        loop {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            let err = match self.get_with_timeout(remaining) {
+                Ok(conn) => return Ok(conn),
+                Err(e) => e,
+            };
+            if !err.is_retryable() || attempt >= max_attempts {
+                return Err(err);
+            }
+            // 剩余预算不足以完成退避后再试一次，直接返回本次错误
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            if remaining <= delay {
+                return Err(err);
+            }
+            thread::sleep(delay);
+            delay = delay.saturating_mul(2);
+            attempt += 1;
+        }
+    }
+
+    /// 将外部已建立的连接注入连接池，作为空闲连接统一管理复用
+    ///
+    /// 连接按池内规则统一设置（阻塞模式、TCP 选项、socket 选项、默认读写超时）后放入对应空闲桶，
+    /// 受 max_connections 与按协议上限约束；不会调用 on_created（连接由调用方自行初始化）。
+    /// 对应空闲桶已满（达到 max_idle_connections）时连接会被直接关闭。
+    pub fn insert(&self, conn: ConnectionType) -> Result<()> {
+        if self.inner.is_closed() {
+            return Err(NetConnPoolError::PoolClosed);
+        }
+        let slot = self.inner.reserve_slot()?;
+        let conn = self
+            .inner
+            .admit_connection(conn, None, None, slot, None, None)?;
+        self.inner.add_idle_connection(conn);
+        Ok(())
     }
 
     /// 关闭连接池
@@ -390,8 +1072,9 @@ impl Pool {
     /// 关闭连接池会：
     /// - 停止接受新的连接请求
     /// - 关闭所有空闲连接
-    /// - 等待所有使用中的连接归还后关闭
-    /// - 停止后台清理线程
+    /// - 等待使用中的连接归还（最多 `shutdown_timeout`，未设置时为 `connection_leak_timeout`），超时后强制关闭
+    /// - 停止后台清理线程，并等待其退出（正在进行的一轮清理结束后返回）
+    /// - 启用 `verify_on_close` 时校验所有连接均已释放，否则返回 `CloseVerificationFailed`
     ///
     /// # 返回值
     /// - `Ok(())`: 成功关闭连接池
@@ -399,10 +1082,22 @@ impl Pool {
     ///
     /// # 注意
     /// 关闭后的连接池不能再次使用。多次调用 `close()` 是安全的（幂等操作）。
+    /// 未显式关闭时，最后一个 Pool 克隆被 Drop 会自动关闭：关闭空闲连接并停止后台线程，
+    /// 但不等待借出的连接归还，这些连接在持有者释放时关闭。
     pub fn close(&self) -> Result<()> {
         self.inner.close()
     }
 
+    /// event_sink 已满或接收端已断开而被丢弃的事件数
+    pub fn dropped_events(&self) -> u64 {
+        self.inner.dropped_events.load(Ordering::Relaxed)
+    }
+
+    /// 向 event_sink 发送池外组件产生的事件（如 PartitionedPool 的分区熔断）
+    pub(crate) fn emit_event(&self, event: PoolEvent) {
+        self.inner.emit(|| event);
+    }
+
     /// 获取连接池统计信息
     ///
     /// 返回连接池的统计信息，包括：
@@ -427,7 +1122,7 @@ impl Pool {
     /// let pool = Pool::new(config).unwrap();
     /// let stats = pool.stats();
     /// println!("当前连接数: {}", stats.current_connections);
-    /// println!("连接复用率: {:.2}%", stats.average_reuse_count * 100.0);
+    /// println!("连接复用率: {:.2}%", stats.reuse_ratio * 100.0);
     /// ```
     pub fn stats(&self) -> crate::stats::Stats {
         if let Some(stats) = &self.inner.stats_collector {
@@ -437,11 +1132,96 @@ impl Pool {
         }
     }
 
+    /// 清零累计型统计计数器，用于区间统计（current_* 即时量不受影响）
+    ///
+    /// 各计数器分别清零，与并发的 `stats()` 之间不保证跨计数器一致。未启用统计时为空操作。
+    pub fn reset_stats(&self) {
+        if let Some(stats) = &self.inner.stats_collector {
+            stats.reset();
+        }
+    }
+
+    /// 获取连接池健康快照
+    ///
+    /// 综合连接池是否关闭、统计窗口内的获取失败率、是否因耗尽而拒绝请求以及泄漏连接数，
+    /// 给出 Healthy/Degraded/Unhealthy 判断与原因列表。统计窗口由 reaper 按 `health_window`
+    /// 周期推进，覆盖最近 1～2 个周期；调用本方法不会改变窗口，多个调用方互不影响。
+    /// 失败率依赖 `enable_stats`。
+    pub fn health(&self) -> PoolHealth {
+        if self.inner.is_closed() {
+            return PoolHealth::from_issues(vec![HealthIssue::Closed]);
+        }
+
+        let stats = self.stats();
+        let exhausted = self.inner.exhausted_gets.load(Ordering::Relaxed);
+        let window = self
+            .inner
+            .health_window
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .baseline;
+        // reset_stats 后计数会小于窗口起点，此时以当前值作为窗口增量
+        let delta = |current: i64, start: i64| {
+            if current >= start {
+                current - start
+            } else {
+                current
+            }
+        };
+        let total = delta(stats.total_get_requests, window.total_gets);
+        let failed = delta(stats.failed_gets, window.failed_gets);
+        let exhausted_in_window = exhausted - window.exhausted_gets;
+
+        let mut issues = Vec::new();
+        // 失败率不低于 10% 视为降级
+        if total >= HEALTH_MIN_SAMPLES && failed * 10 >= total {
+            issues.push(HealthIssue::HighFailureRate { failed, total });
+        }
+        if exhausted_in_window > 0 {
+            issues.push(HealthIssue::CapacityExhausted {
+                count: exhausted_in_window,
+            });
+        }
+        if stats.current_leaked_connections > 0 {
+            issues.push(HealthIssue::LeakedConnections {
+                count: stats.current_leaked_connections,
+            });
+        }
+        PoolHealth::from_issues(issues)
+    }
+
     /// 检查连接池是否已关闭
     pub fn is_closed(&self) -> bool {
         self.inner.is_closed()
     }
 
+    /// 暂停新建连接（故障演练、后端维护窗口等场景）
+    ///
+    /// 暂停期间 get 只复用已有空闲连接，没有空闲连接时在超时内等待归还，
+    /// 到期返回 `NetConnPoolError::DialingPaused`；预热与淘汰补建也会跳过。
+    pub fn pause_dialing(&self) {
+        self.inner.dialing_paused.store(true, Ordering::Release);
+    }
+
+    /// 恢复新建连接，并唤醒因暂停而等待的 get
+    pub fn resume_dialing(&self) {
+        if self.inner.dialing_paused.swap(false, Ordering::AcqRel) {
+            self.inner.pool_version.fetch_add(1, Ordering::AcqRel);
+            drop(
+                self.inner
+                    .wait_lock
+                    .lock()
+                    .unwrap_or_else(|e| e.into_inner()),
+            );
+            self.inner.wait_cv.notify_all();
+        }
+    }
+
+    /// 是否处于暂停建连状态
+    pub fn is_dialing_paused(&self) -> bool {
+        self.inner.dialing_paused.load(Ordering::Acquire)
+    }
+
     /// 获取当前活跃（借出）的连接数
     ///
     /// 此计数器独立于 `enable_stats` 配置，始终可用。
@@ -449,14 +1229,333 @@ impl Pool {
         self.inner.active_count.load(Ordering::Relaxed)
     }
 
-    /// 获取当前空闲连接数（所有分桶之和）
-    pub fn idle_count(&self) -> usize {
+    /// 获取当前阻塞在 get 中等待连接的线程数
+    ///
+    /// 请求首次进入等待（池满、限速、暂停建连等）时计入，直到该次 get 返回才扣除；
+    /// 持续大于0说明请求在排队等连接，可作为扩容依据。此计数器独立于 `enable_stats` 配置。
+    pub fn waiters(&self) -> usize {
+        self.inner.waiters.load(Ordering::Acquire)
+    }
+
+    /// 获取默认 get（`get`、`get_tcp` 等）当前等待建连许可的上限
+    ///
+    /// 启用 `adaptive_timeout` 时随近期建连耗时变化，否则恒为 `get_connection_timeout`。
+    /// 等待连接归还的上限始终为 `get_connection_timeout`。
+    pub fn current_get_timeout(&self) -> Duration {
+        self.inner
+            .dial_budget()
+            .unwrap_or(self.inner.config.get_connection_timeout)
+    }
+
+    /// 获取当前每桶最大空闲连接数
+    pub fn max_idle_connections(&self) -> usize {
+        self.inner.max_idle_connections.load(Ordering::Relaxed)
+    }
+
+    /// 在运行时调整每桶最大空闲连接数
+    ///
+    /// 调大立即生效；调小后超出新上限的空闲连接会被立即关闭，
+    /// 之后的归还也按新上限执行，reaper 每轮清理时同样会收敛到新上限。
+    ///
+    /// # 返回值
+    /// - `Ok(())`: 调整成功
+    /// - `Err(NetConnPoolError::InvalidConfig)`: `n` 为 0 或大于 `max_connections`
+    pub fn set_max_idle_connections(&self, n: usize) -> Result<()> {
+        let max_connections = self.inner.config.max_connections;
+        if n == 0 {
+            return Err(NetConnPoolError::InvalidConfig {
+                reason: "max_idle_connections 必须大于 0".to_string(),
+            });
+        }
+        if max_connections > 0 && n > max_connections {
+            return Err(NetConnPoolError::InvalidConfig {
+                reason: format!(
+                    "max_idle_connections ({}) 不能大于 max_connections ({})",
+                    n, max_connections
+                ),
+            });
+        }
+        self.inner.max_idle_connections.store(n, Ordering::Relaxed);
+        self.inner.trim_idle_connections();
+        Ok(())
+    }
+
+    /// 获取当前空闲连接数（所有分桶之和）
+    pub fn idle_count(&self) -> usize {
         self.inner
             .idle_counts
             .iter()
             .map(|c| c.load(Ordering::Relaxed))
             .sum()
     }
+
+    /// 遍历当前空闲连接的快照，用于自定义维护（如应用层 keepalive、证书有效期检查）
+    ///
+    /// 快照取自连接表而非 idle 队列，不会借出连接、也不影响空闲计数；
+    /// 遍历开始后新归还的连接不在本次遍历范围内。回调执行期间连接仍可能被其他线程借出，
+    /// 回调中不应对连接做会破坏其状态的读写。
+    pub fn for_each_idle<F>(&self, mut f: F)
+    where
+        F: FnMut(&Connection),
+    {
+        let idle: Vec<Arc<Connection>> = match self.inner.all_connections.read() {
+            Ok(connections) => connections
+                .values()
+                .filter(|c| !c.is_in_use() && !c.is_closed())
+                .cloned()
+                .collect(),
+            Err(_) => return,
+        };
+        for conn in &idle {
+            f(conn);
+        }
+    }
+
+    /// 获取四个桶（TCP-IPv4、TCP-IPv6、UDP-IPv4、UDP-IPv6）的连接分布快照
+    ///
+    /// 数据取自各桶的原子计数器，不扫描连接表、不加锁，也不依赖 enable_stats；用于诊断某一类连接是否不足。
+    /// 空闲数与 `idle_count` 口径一致：已被移除但尚未出队的连接在下一轮清理前仍可能计入。
+    pub fn bucket_stats(&self) -> [BucketStat; 4] {
+        std::array::from_fn(|idx| {
+            let (protocol, ip_version) = BUCKETS[idx];
+            BucketStat {
+                protocol,
+                ip_version,
+                idle: self.inner.idle_counts[idx].load(Ordering::Relaxed),
+                active: self.inner.bucket_active_counts[idx].load(Ordering::Relaxed),
+            }
+        })
+    }
+
+    /// 导出连接池完整运行时状态的诊断快照（配置摘要、桶分布、每条连接详情与统计）
+    ///
+    /// 返回的 `PoolDump` 实现了 `Display`，可直接以 `{}` 格式写入日志或文件。
+    /// 连接列表、桶分布与 current_* 统计取自同一次连接表快照；未启用统计时累计型字段为0。
+    pub fn dump(&self) -> PoolDump {
+        let config = &self.inner.config;
+        let connections = self
+            .inner
+            .all_connections
+            .read()
+            .unwrap_or_else(|e| e.into_inner());
+        let mut stats = self.stats();
+        PoolInner::fill_current_counts(&mut stats, &connections);
+        let buckets = PoolInner::bucket_stats_of(&connections);
+        let mut snapshots: Vec<ConnectionSnapshot> = connections
+            .values()
+            .filter(|c| !c.is_closed())
+            .map(|c| ConnectionSnapshot::of(c))
+            .collect();
+        drop(connections);
+        snapshots.sort_by_key(|c| c.id);
+
+        PoolDump {
+            mode: config.mode,
+            closed: self.is_closed(),
+            max_connections: config.max_connections,
+            min_connections: config.min_connections,
+            max_idle_connections: self.max_idle_connections(),
+            waiters: self.inner.waiters.load(Ordering::Acquire),
+            buckets,
+            connections: snapshots,
+            stats,
+        }
+    }
+
+    /// 获取内部自洽的统计快照
+    ///
+    /// 在连接表读锁内统一计算 current_* 即时量（期间不会有连接被创建或移除），
+    /// 保证 `current_connections == current_active_connections + current_idle_connections`，
+    /// 且按 IP 版本、协议拆分的计数与总数一致；累计型计数器取自统计收集器。
+    /// 未启用统计时累计型字段为0，即时量仍按连接表计算。
+    pub fn consistent_stats(&self) -> crate::stats::Stats {
+        let connections = self
+            .inner
+            .all_connections
+            .read()
+            .unwrap_or_else(|e| e.into_inner());
+        let mut stats = self.stats();
+        PoolInner::fill_current_counts(&mut stats, &connections);
+        stats
+    }
+
+    /// 将统计收集器中的 current_* 即时计数校正为连接表的真实值
+    ///
+    /// 用于修复长时间运行后即时计数的漂移，返回被校正的字段数；未启用统计时返回0。
+    /// 与连接借还并发调用时可能再次引入偏差，建议在静止态（如对账前）调用。
+    pub fn reconcile_stats(&self) -> usize {
+        let Some(collector) = &self.inner.stats_collector else {
+            return 0;
+        };
+        let connections = self
+            .inner
+            .all_connections
+            .read()
+            .unwrap_or_else(|e| e.into_inner());
+        let mut actual = crate::stats::Stats::default();
+        PoolInner::fill_current_counts(&mut actual, &connections);
+        collector.reconcile_current(&actual)
+    }
+}
+
+thread_local! {
+    /// get_sticky：本线程在各连接池上次借到的连接 ID，以 PoolInner 地址区分连接池
+    /// 记录持有 Weak 引用，在 get_sticky 时清理已释放的连接池（Weak 存在期间地址不会被复用）
+    static STICKY_CONNECTIONS: RefCell<HashMap<usize, (Weak<PoolInner>, u64)>> =
+        RefCell::new(HashMap::new());
+}
+
+#[cfg(test)]
+thread_local! {
+    /// 测试注入：在当前线程创建指定名称的后台线程时模拟失败
+    static FAIL_SPAWN: std::cell::Cell<Option<&'static str>> = const { std::cell::Cell::new(None) };
+    /// 当前线程的 get 在 wait_for_pool_change 中返回的次数（每次返回意味着重试一轮）
+    static POOL_WAIT_RETURNS: std::cell::Cell<usize> = const { std::cell::Cell::new(0) };
+}
+
+/// 创建具名后台线程，失败时返回 ThreadSpawnFailed
+fn spawn_background<F>(name: &'static str, f: F) -> Result<thread::JoinHandle<()>>
+where
+    F: FnOnce() + Send + 'static,
+{
+    let spawn_failed = |source| NetConnPoolError::ThreadSpawnFailed {
+        thread: name.to_string(),
+        source,
+    };
+    #[cfg(test)]
+    if FAIL_SPAWN.with(|n| n.get()) == Some(name) {
+        return Err(spawn_failed(std::io::Error::other(
+            "injected spawn failure",
+        )));
+    }
+    thread::Builder::new()
+        .name(name.to_string())
+        .spawn(f)
+        .map_err(spawn_failed)
+}
+
+/// 空闲桶顺序，与 get_bucket_index 的下标一致
+const BUCKETS: [(Protocol, IPVersion); 4] = [
+    (Protocol::TCP, IPVersion::IPv4),
+    (Protocol::TCP, IPVersion::IPv6),
+    (Protocol::UDP, IPVersion::IPv4),
+    (Protocol::UDP, IPVersion::IPv6),
+];
+
+/// 后台建连队列：待建连接的协议、IP 版本与角色约束
+#[derive(Default)]
+struct BackgroundDials {
+    targets: Vec<(Option<Protocol>, Option<IPVersion>, Option<ConnectionRole>)>,
+    // 是否已有补建线程在运行
+    running: bool,
+}
+
+/// 等待者登记信息
+struct WaitEntry {
+    id: u64,
+    priority: u8,
+    since: Instant,
+}
+
+/// get 的等待者登记，drop 时注销
+struct WaiterGuard<'a> {
+    pool: &'a PoolInner,
+    id: u64,
+    priority: u8,
+}
+
+impl Drop for WaiterGuard<'_> {
+    fn drop(&mut self) {
+        self.pool
+            .wait_entries
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .retain(|e| e.id != self.id);
+        self.pool.waiters.fetch_sub(1, Ordering::AcqRel);
+        if self.priority > 0 {
+            self.pool.priority_waiters.fetch_sub(1, Ordering::AcqRel);
+            // 正在让行的等待者需要重新判定，加锁避免通知落在其复查与 wait 之间
+            drop(
+                self.pool
+                    .wait_lock
+                    .lock()
+                    .unwrap_or_else(|e| e.into_inner()),
+            );
+            self.pool.wait_cv.notify_all();
+        }
+    }
+}
+
+/// 截断单次等待，使等待者在慢获取软阈值处醒来触发 on_slow_get
+fn slow_get_wait(slow_get_at: Option<Instant>, wait: Duration) -> Duration {
+    match slow_get_at {
+        Some(at) => wait.min(at.saturating_duration_since(Instant::now())),
+        None => wait,
+    }
+}
+
+/// 非阻塞 peek 一个字节判断对端是否已关闭：读到 EOF 或出错（如 RST）视为已关闭，
+/// 暂无数据（WouldBlock）或有待读数据视为存活
+fn tcp_peer_closed(stream: &TcpStream) -> bool {
+    if stream.set_nonblocking(true).is_err() {
+        return false;
+    }
+    let mut buf = [0u8; 1];
+    let closed = match stream.peek(&mut buf) {
+        Ok(0) => true,
+        Ok(_) => false,
+        Err(e) => !matches!(
+            e.kind(),
+            std::io::ErrorKind::WouldBlock | std::io::ErrorKind::Interrupted
+        ),
+    };
+    let _ = stream.set_nonblocking(false);
+    closed
+}
+
+/// 探测 TCP/TLS 连接的对端是否已关闭；UDP 没有连接状态，总是返回 false
+///
+/// 会临时切换非阻塞模式，只能对未借出的连接调用。
+pub(crate) fn peer_closed(conn_type: &ConnectionType) -> bool {
+    match conn_type {
+        ConnectionType::Tcp(stream) => tcp_peer_closed(stream),
+        #[cfg(feature = "tls")]
+        ConnectionType::Tls(stream) => stream.with_tcp(tcp_peer_closed),
+        ConnectionType::Udp(_) => false,
+    }
+}
+
+/// 建连期间预占的连接额度；未提交即被丢弃（建连失败）时归还额度
+struct SlotReservation<'a> {
+    pool: &'a PoolInner,
+    committed: bool,
+}
+
+impl SlotReservation<'_> {
+    /// 连接已插入连接表，额度转为存活连接占用，由 remove_connection 归还
+    fn commit(mut self) {
+        self.committed = true;
+    }
+}
+
+impl Drop for SlotReservation<'_> {
+    fn drop(&mut self) {
+        if !self.committed {
+            self.pool.reserved_slots.fetch_sub(1, Ordering::AcqRel);
+        }
+    }
+}
+
+/// 建连并发许可：建连结束（成功或失败）时释放，并唤醒一个等待建连的 get
+struct DialPermit<'a> {
+    pool: &'a PoolInner,
+}
+
+impl Drop for DialPermit<'_> {
+    fn drop(&mut self) {
+        self.pool.dials_in_flight.fetch_sub(1, Ordering::AcqRel);
+        self.pool.notify_waiter();
+    }
 }
 
 impl PoolInner {
@@ -464,14 +1563,48 @@ impl PoolInner {
         self.closed.load(Ordering::Acquire)
     }
 
+    /// 默认 get 等待建连许可的动态上限：启用 adaptive_timeout 且已有建连样本时为 Some
+    fn dial_budget(&self) -> Option<Duration> {
+        self.adaptive_budget
+            .as_ref()
+            .and_then(AdaptiveBudget::current)
+    }
+
     fn close(&self) -> Result<()> {
+        self.shutdown(true)
+    }
+
+    /// 关闭连接池
+    ///
+    /// `drain_active` 为 true 时（显式 close）等待借出的连接归还并强制关闭剩余连接；
+    /// 为 false 时（最后一个 Pool 句柄 Drop）只关闭空闲连接，借出的连接由持有者释放时关闭，
+    /// 避免 Drop 阻塞在等待归还上。
+    fn shutdown(&self, drain_active: bool) -> Result<()> {
         if self.closed.swap(true, Ordering::SeqCst) {
             return Ok(());
         }
 
+        // 影子流量是尽力而为的：影子池关闭失败不影响主池关闭
+        if let Some(shadow) = &self.shadow {
+            let _ = shadow.inner.shutdown(drain_active);
+        }
+
+        // 停止异步归还：之后的归还在持有者线程中同步处理
+        self.return_queue
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .take();
+
         // 唤醒所有等待 get() 的线程
+        // 先获取一次 wait_lock：等待者在持锁状态下复查 closed 后才进入 wait，
+        // 这里加锁可保证 notify 不会落在其复查与 wait 之间而丢失
+        drop(self.wait_lock.lock().unwrap_or_else(|e| e.into_inner()));
         self.wait_cv.notify_all();
 
+        // 唤醒 reaper 与统计上报线程，使其立即退出
+        drop(self.reaper_lock.lock().unwrap_or_else(|e| e.into_inner()));
+        self.reaper_cv.notify_all();
+
         // 1) 先关闭所有 idle 连接（不影响正在使用的连接）
         // 为了保持 idle 统计一致性，这里显式扣减 idle 统计（因为我们会直接 drain bucket）
         let mut idle_conns: Vec<Arc<Connection>> = Vec::new();
@@ -491,9 +1624,17 @@ impl PoolInner {
             let _ = self.remove_connection(conn);
         }
 
+        if !drain_active {
+            self.join_reaper();
+            return Ok(());
+        }
+
         // 2) 等待活跃连接归还（优雅关闭）
-        // 为避免 close 永久阻塞，最多等待 connection_leak_timeout（为 0 则不等待）
-        let wait_budget = self.config.connection_leak_timeout;
+        // 为避免 close 永久阻塞，最多等待 shutdown_timeout（未设置时回退 connection_leak_timeout，为 0 则不等待）
+        let wait_budget = self
+            .config
+            .shutdown_timeout
+            .unwrap_or(self.config.connection_leak_timeout);
         if !wait_budget.is_zero() {
             let deadline = Instant::now() + wait_budget;
             let mut guard = self.wait_lock.lock().unwrap_or_else(|e| e.into_inner());
@@ -531,9 +1672,105 @@ impl PoolInner {
             }
         }
 
+        // 4) 等待 reaper 退出：已被唤醒，正在进行的一轮清理结束后即退出
+        self.join_reaper();
+
+        if self.config.verify_on_close {
+            self.verify_closed()?;
+        }
+        Ok(())
+    }
+
+    /// 校验关闭后的资源不变量：连接表为空、空闲队列与计数全零、活跃计数为零
+    fn verify_closed(&self) -> Result<()> {
+        let mut issues = Vec::new();
+        let remaining = self
+            .all_connections
+            .read()
+            .map(|c| c.len())
+            .unwrap_or_else(|e| e.into_inner().len());
+        if remaining > 0 {
+            issues.push(format!("连接表仍有 {} 条连接", remaining));
+        }
+        for (idx, idle) in self.idle_connections.iter().enumerate() {
+            let counted = self.idle_counts[idx].load(Ordering::Acquire);
+            if counted > 0 || !idle.is_empty() {
+                issues.push(format!(
+                    "空闲分桶 {} 未清空 (idle_count: {}, queued: {})",
+                    idx,
+                    counted,
+                    idle.len()
+                ));
+            }
+        }
+        let active = self.active_count.load(Ordering::Acquire);
+        if active > 0 {
+            issues.push(format!("active_count 为 {}", active));
+        }
+
+        if issues.is_empty() {
+            return Ok(());
+        }
+        let reason = issues.join("; ");
+        eprintln!("警告: 连接池关闭校验失败: {}", reason);
+        Err(NetConnPoolError::CloseVerificationFailed { reason })
+    }
+
+    /// 启动后台线程（仅首次调用生效）
+    ///
+    /// 启动失败时关闭连接池并返回 ThreadSpawnFailed，之后的 get 将返回 PoolClosed。
+    fn ensure_background_threads(self: &Arc<Self>) -> Result<()> {
+        let mut result = Ok(());
+        self.background_started.call_once(|| {
+            let return_rx = self
+                .pending_return_rx
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .take();
+            result = Pool::spawn_background_threads(self, return_rx);
+        });
+        if let Err(e) = result {
+            let _ = self.close();
+            return Err(e);
+        }
         Ok(())
     }
 
+    /// 推进健康快照窗口（仅由 reaper 调用），未满一个 health_window 周期时不变
+    fn advance_health_window(&self) {
+        let mut window = self.health_window.lock().unwrap_or_else(|e| e.into_inner());
+        if window.rotated_at.elapsed() < self.config.health_window {
+            return;
+        }
+        let (total_gets, failed_gets) = self
+            .stats_collector
+            .as_ref()
+            .map_or((0, 0), |stats| stats.get_request_counts());
+        window.baseline = window.pending;
+        window.pending = HealthCounts {
+            total_gets,
+            failed_gets,
+            exhausted_gets: self.exhausted_gets.load(Ordering::Relaxed),
+        };
+        window.rotated_at = Instant::now();
+    }
+
+    /// 等待 reaper 线程退出
+    ///
+    /// 在 reaper 线程自身中调用 close()（如健康检查回调里关闭连接池）时跳过，避免 join 自己造成死锁。
+    fn join_reaper(&self) {
+        let handle = self
+            .reaper_handle
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .take();
+        if let Some(handle) = handle {
+            if handle.thread().id() != thread::current().id() {
+                let _ = handle.join();
+            }
+        }
+    }
+
     // 计算分桶索引
     fn get_bucket_index(protocol: Protocol, ip_version: IPVersion) -> Option<usize> {
         let p_idx = match protocol {
@@ -550,6 +1787,8 @@ impl PoolInner {
     }
 
     // 获取符合条件的空闲连接索引列表
+    //
+    // 命中多个桶时每次调用轮转遍历起点，各桶的空闲连接被均衡取用
     fn get_target_buckets(
         &self,
         protocol: Option<Protocol>,
@@ -583,6 +1822,10 @@ impl PoolInner {
                 }
             }
         }
+        if indices.len() > 1 {
+            let start = self.bucket_cursor.fetch_add(1, Ordering::Relaxed) % indices.len();
+            indices.rotate_left(start);
+        }
         indices
     }
 
@@ -591,25 +1834,72 @@ impl PoolInner {
         protocol: Option<Protocol>,
         ip_version: Option<IPVersion>,
         timeout: Duration,
+        options: GetOptions<'_>,
     ) -> Result<PooledConnection> {
+        let GetOptions {
+            predicate,
+            role,
+            priority,
+            reuse_only,
+            oneshot,
+            sheddable,
+            cancel,
+            prefer,
+            lease,
+            dial_budget,
+        } = options;
+
         if self.is_closed() {
             return Err(NetConnPoolError::PoolClosed);
         }
+        self.ensure_background_threads()?;
 
         if let Some(stats) = &self.stats_collector {
             stats.increment_total_get_requests();
         }
 
+        if sheddable && self.degraded.load(Ordering::Acquire) {
+            if let Some(stats) = &self.stats_collector {
+                stats.increment_failed_gets();
+            }
+            return Err(NetConnPoolError::Degraded {
+                available: self.availability(),
+                min_availability: self.config.min_availability,
+            });
+        }
+
+        // Unknown 等同于不限制：跨分桶复用空闲连接，建连时以 None 交给 Dialer 决定
+        let protocol = protocol.filter(|p| *p != Protocol::Unknown);
+        let ip_version = ip_version.filter(|ip| *ip != IPVersion::Unknown);
+
         let start_time = Instant::now();
+        // 等待建连许可的上限；等待连接归还仍以 timeout 为准
+        let dial_timeout = dial_budget.map_or(timeout, |budget| budget.min(timeout));
         let bucket_indices = self.get_target_buckets(protocol, ip_version);
+        // 首次需要等待时登记为等待者，直到本次 get 返回才注销
+        let mut waiter: Option<WaiterGuard<'_>> = None;
+        let mut slow_get_at = self.slow_get_deadline(start_time);
+        // 首轮总会尝试一次；上一轮等待过或刚腾出额度时，本轮由各分支按自身语义判定到期
+        let mut retried = false;
+        let mut deadline_deferred = false;
 
         loop {
             if self.is_closed() {
                 return Err(NetConnPoolError::PoolClosed);
             }
+            self.check_slow_get(&mut slow_get_at, start_time);
+            if cancel.is_some_and(|c| c.load(Ordering::Acquire)) {
+                if let Some(stats) = &self.stats_collector {
+                    stats.increment_failed_gets();
+                }
+                return Err(NetConnPoolError::Cancelled {
+                    waited: start_time.elapsed(),
+                });
+            }
 
+            // 未经等待就重试（丢弃无效空闲连接等）时也要受 timeout 约束，避免无限重试
             let elapsed = start_time.elapsed();
-            if elapsed > timeout {
+            if retried && !deadline_deferred && elapsed > timeout {
                 if let Some(stats) = &self.stats_collector {
                     stats.increment_failed_gets();
                     stats.increment_timeout_gets();
@@ -619,62 +1909,169 @@ impl PoolInner {
                     waited: elapsed,
                 });
             }
+            retried = true;
+            deadline_deferred = false;
 
-            // 1. 尝试从空闲池获取（无锁操作）
-            for &idx in &bucket_indices {
-                let conn = self.idle_connections[idx].pop();
+            let observed_version = self.pool_version.load(Ordering::Acquire);
 
-                if let Some(conn) = conn {
-                    // 更新计数器
-                    self.idle_counts[idx].fetch_sub(1, Ordering::Relaxed);
-                    // 从 idle 移除即应更新 idle 统计（无论最终是否可用）
-                    if let Some(stats) = &self.stats_collector {
-                        self.update_stats_on_idle_pop(stats, &conn);
-                    }
+            // 有更高优先级的请求在等待时让行：既不取空闲连接也不建连
+            if self.should_yield_to_priority(priority, start_time, waiter.as_ref().map(|w| w.id)) {
+                let wait_reason = self.capacity_wait_reason();
+                let waited = start_time.elapsed();
+                if waited >= timeout {
+                    return Err(self.exhausted_error(
+                        self.connection_count.load(Ordering::Acquire),
+                        self.config.max_connections,
+                        waited,
+                        timeout,
+                        wait_reason,
+                    ));
+                }
+                self.enter_wait_queue(&mut waiter, priority, start_time)?;
+                // 有效优先级随等待时间提升，至多等待一个老化周期后重新判定
+                let mut wait = timeout.saturating_sub(waited);
+                if !self.config.priority_aging.is_zero() {
+                    wait = wait.min(self.config.priority_aging);
+                }
+                let wait = self.bounded_wait(slow_get_at, cancel, wait);
+                self.wait_for_priority_turn(priority, start_time, waiter.as_ref(), wait);
+                deadline_deferred = true;
+                continue;
+            }
 
-                    if !self.is_connection_valid_for_borrow(&conn) {
-                        let _ = self.remove_connection(&conn);
-                        continue;
-                    }
+            // 等待后的到期判定放在各分支中处理：
+            // 无论 timeout 是否为 0，至少先尝试一次 idle/创建，
+            // 池满且已到期时统一返回 PoolExhausted（附带 waited）
 
-                    // 优化：在 get() 时清理 UDP 缓冲区，避免阻塞归还操作
-                    // 由即将使用该连接的线程负责清理历史残存数据
-                    if self.config.clear_udp_buffer_on_return
-                        && conn.protocol() == Protocol::UDP
-                    {
-                        if let Some(udp_socket) = conn.udp_conn() {
-                            let timeout = self.config.udp_buffer_clear_timeout;
-                            let max_packets = self.config.max_buffer_clear_packets;
-                            // 非阻塞清理，不会阻塞 get() 操作
-                            let _ = clear_udp_read_buffer(udp_socket, timeout, max_packets);
+            // 1. 尝试从空闲池获取（无锁操作），一次性获取不复用空闲连接
+            let idle = (!oneshot)
+                .then(|| {
+                    prefer
+                        .and_then(|id| self.take_preferred_idle(&bucket_indices, id, role))
+                        .or_else(|| self.take_idle_connection(&bucket_indices, predicate, role))
+                })
+                .flatten();
+            if let Some(conn) = idle {
+                // 先独占再做任何 I/O：借出与 reaper 的健康检查、标记不健康在同一原子状态上竞争，
+                // 已被判定不健康的连接绝不借出，正被检查的连接放回队列；租约随独占成功一并写入
+                if !conn.try_acquire(lease) {
+                    match Self::get_bucket_index(conn.protocol(), conn.ip_version()) {
+                        Some(idx) if conn.is_checking() && conn.health_status() => {
+                            self.try_push_idle(conn, idx)
+                        }
+                        _ => {
+                            let _ = self.remove_connection(&conn);
                         }
                     }
+                    continue;
+                }
 
-                    conn.mark_in_use();
-                    conn.increment_reuse_count();
-                    self.active_count.fetch_add(1, Ordering::Relaxed);
+                // peek_on_borrow：跳过对端已关闭的连接（连接已被独占，探测不会干扰他人）
+                if self.config.peek_on_borrow && peer_closed(conn.connection_type()) {
+                    self.discard_claimed(&conn);
+                    continue;
+                }
 
-                    if let Some(on_borrow) = &self.config.on_borrow {
-                        on_borrow(conn.connection_type());
+                // 优化：在 get() 时清理 UDP 缓冲区，避免阻塞归还操作
+                // 由即将使用该连接的线程负责清理历史残存数据
+                if self.config.clear_udp_buffer_on_return && conn.protocol() == Protocol::UDP {
+                    if let Some(udp_socket) = conn.udp_conn() {
+                        let timeout = self.config.udp_buffer_clear_timeout;
+                        let max_packets = self.config.max_buffer_clear_packets;
+                        // 非阻塞清理，不会阻塞 get() 操作
+                        let _ = clear_udp_read_buffer(udp_socket, timeout, max_packets);
                     }
+                }
 
-                    if let Some(stats) = &self.stats_collector {
-                        self.update_stats_on_get_success(stats, true, start_time.elapsed());
+                // 恢复默认读写超时（调用方上次借用期间可能修改过）
+                if self.apply_default_timeouts(conn.connection_type()).is_err() {
+                    self.discard_claimed(&conn);
+                    continue;
+                }
+
+                // testOnBorrow：验证往返失败的连接直接丢弃，继续尝试下一条
+                if self.config.test_on_borrow {
+                    let result = self.run_validation(&conn);
+                    if !self.record_health_check(&conn, &result) {
+                        self.discard_claimed(&conn);
+                        continue;
                     }
+                }
 
-                    return Ok(PooledConnection::new(conn, Arc::downgrade(self)));
+                if self.run_on_acquire(&conn).is_err() {
+                    // 无法恢复到干净状态：丢弃该连接，继续尝试下一条空闲连接或新建
+                    self.discard_claimed(&conn);
+                    continue;
                 }
+
+                conn.increment_reuse_count();
+                self.add_active(&conn);
+
+                if let Some(on_borrow) = &self.config.on_borrow {
+                    on_borrow(conn.connection_type());
+                }
+                self.emit(|| PoolEvent::Borrowed {
+                    connection_id: conn.id(),
+                });
+                self.check_availability();
+
+                if let Some(stats) = &self.stats_collector {
+                    self.update_stats_on_get_success(stats, true, start_time.elapsed());
+                }
+
+                return Ok(PooledConnection::new(conn, Arc::downgrade(self)));
+            }
+
+            // 仅复用模式：不建连，只等待空闲连接归还
+            if reuse_only {
+                let waited = start_time.elapsed();
+                if waited >= timeout {
+                    if let Some(stats) = &self.stats_collector {
+                        stats.increment_failed_gets();
+                        if !timeout.is_zero() {
+                            stats.increment_timeout_gets();
+                        }
+                    }
+                    return Err(NetConnPoolError::NoIdleConnection { waited });
+                }
+
+                let remaining = timeout.saturating_sub(waited);
+                self.enter_wait_queue(&mut waiter, priority, start_time)?;
+                self.wait_for_pool_change(
+                    self.bounded_wait(slow_get_at, cancel, remaining),
+                    observed_version,
+                );
+                deadline_deferred = true;
+                continue;
             }
 
-            // 2. 创建新连接（若并发下已满，会返回 MaxConnectionsReached）
-            match self.create_connection(protocol, ip_version) {
+            // 2. 创建新连接（若并发下已满，会返回 MaxConnectionsReached；超过新建速率返回 CreateRateLimited）
+            let created = self
+                .create_connection(protocol, ip_version, role)
+                .and_then(|conn| match self.run_on_acquire(&conn) {
+                    Ok(()) => Ok(conn),
+                    Err(e) => {
+                        // 新建连接也无法初始化时直接失败，避免反复建连
+                        let _ = self.remove_connection(&conn);
+                        Err(e)
+                    }
+                });
+            match created {
                 Ok(conn) => {
+                    if oneshot {
+                        conn.mark_oneshot();
+                    }
+                    conn.set_lease(lease);
                     conn.mark_in_use();
-                    self.active_count.fetch_add(1, Ordering::Relaxed);
+                    self.add_active(&conn);
 
                     if let Some(on_borrow) = &self.config.on_borrow {
                         on_borrow(conn.connection_type());
                     }
+                    self.emit(|| PoolEvent::Borrowed {
+                        connection_id: conn.id(),
+                    });
+                    self.check_availability();
 
                     if let Some(stats) = &self.stats_collector {
                         self.update_stats_on_get_success(stats, false, start_time.elapsed());
@@ -682,35 +2079,113 @@ impl PoolInner {
 
                     return Ok(PooledConnection::new(conn, Arc::downgrade(self)));
                 }
-                Err(NetConnPoolError::MaxConnectionsReached { .. }) => {
+                Err(NetConnPoolError::MaxConnectionsReached { current, max }) => {
+                    // 在判定池满的当下记录等待原因，之后的状态变化不影响错误中的原因
+                    let wait_reason = self.capacity_wait_reason();
+                    // 一次性获取不会复用空闲连接：池被空闲连接占满时关闭一条腾出额度
+                    if oneshot {
+                        if let Some(idle) = self.take_idle_connection(&bucket_indices, None, None) {
+                            let _ = self.remove_connection(&idle);
+                            deadline_deferred = true;
+                            continue;
+                        }
+                    }
                     // 池已满：在 timeout 内等待连接归还（避免自旋 & 过早失败）
-                    if timeout.is_zero() {
-                        // 明确的快速失败语义
-                        let current = self
-                            .all_connections
-                            .read()
-                            .map_err(|e| {
-                                NetConnPoolError::IoError(std::io::Error::other(format!(
-                                    "读取连接数失败: {}",
-                                    e
-                                )))
-                            })?
-                            .len();
-                        return Err(NetConnPoolError::PoolExhausted {
+                    // timeout 为 0 与 timeout 到期走同一路径，错误语义保持一致
+                    let waited = start_time.elapsed();
+                    if waited >= timeout {
+                        return Err(self.exhausted_error(
                             current,
-                            max: self.config.max_connections,
-                        });
+                            max,
+                            waited,
+                            timeout,
+                            wait_reason,
+                        ));
                     }
 
-                    let remaining = timeout.saturating_sub(start_time.elapsed());
-                    let guard = self.wait_lock.lock().unwrap_or_else(|e| e.into_inner());
-                    let _ = match self.wait_cv.wait_timeout(guard, remaining) {
-                        Ok(res) => res,
-                        Err(e) => e.into_inner(),
-                    };
+                    let remaining = timeout.saturating_sub(waited);
+                    self.enter_wait_queue(&mut waiter, priority, start_time)?;
+                    // 只是到了软上限：登记为等待者后若已满足突发条件，立即按硬上限重试建连
+                    if max < self.config.max_connections && self.connection_cap() > max {
+                        deadline_deferred = true;
+                        continue;
+                    }
+                    self.wait_for_pool_change(
+                        self.bounded_wait(slow_get_at, cancel, remaining),
+                        observed_version,
+                    );
+                    deadline_deferred = true;
                     // 被唤醒/超时后继续循环：重试 idle 或创建
                     continue;
                 }
+                Err(NetConnPoolError::TooManyConcurrentDials { .. }) => {
+                    // 建连并发已满：在 dial_timeout 内等待其它建连完成或空闲连接归还
+                    let waited = start_time.elapsed();
+                    if waited >= dial_timeout {
+                        if let Some(stats) = &self.stats_collector {
+                            stats.increment_failed_gets();
+                            if !timeout.is_zero() {
+                                stats.increment_timeout_gets();
+                            }
+                        }
+                        return Err(NetConnPoolError::DialConcurrencyLimited { waited });
+                    }
+
+                    let remaining = dial_timeout.saturating_sub(waited);
+                    self.enter_wait_queue(&mut waiter, priority, start_time)?;
+                    self.wait_for_pool_change(
+                        self.bounded_wait(slow_get_at, cancel, remaining),
+                        observed_version,
+                    );
+                    deadline_deferred = true;
+                    continue;
+                }
+                Err(NetConnPoolError::CreateRateLimited { .. }) => {
+                    // 超过新建速率：不调用 dialer，在 dial_timeout 内等待空闲连接归还或令牌补充
+                    let waited = start_time.elapsed();
+                    if waited >= dial_timeout {
+                        if let Some(stats) = &self.stats_collector {
+                            stats.increment_failed_gets();
+                            if !timeout.is_zero() {
+                                stats.increment_timeout_gets();
+                            }
+                        }
+                        return Err(NetConnPoolError::CreateRateLimited { waited });
+                    }
+
+                    let remaining = dial_timeout
+                        .saturating_sub(waited)
+                        .min(self.next_create_token_in());
+                    self.enter_wait_queue(&mut waiter, priority, start_time)?;
+                    self.wait_for_pool_change(
+                        self.bounded_wait(slow_get_at, cancel, remaining),
+                        observed_version,
+                    );
+                    deadline_deferred = true;
+                    continue;
+                }
+                Err(NetConnPoolError::DialingPaused) => {
+                    // 暂停建连：在 dial_timeout 内等待空闲连接归还或 resume_dialing
+                    let waited = start_time.elapsed();
+                    if waited >= dial_timeout {
+                        if let Some(stats) = &self.stats_collector {
+                            stats.increment_failed_gets();
+                            if !timeout.is_zero() {
+                                stats.increment_timeout_gets();
+                            }
+                        }
+                        return Err(NetConnPoolError::DialingPaused);
+                    }
+
+                    let remaining = dial_timeout.saturating_sub(waited);
+                    self.enter_wait_queue(&mut waiter, priority, start_time)?;
+                    self.wait_for_pool_change(
+                        self.bounded_wait(slow_get_at, cancel, remaining),
+                        observed_version,
+                    );
+                    deadline_deferred = true;
+                    continue;
+                }
                 Err(e) => {
                     // 只有在确定无法创建符合要求的连接时才返回错误
                     // 如果是因为协议不匹配（比如随机创建了UDP但需要TCP），应该继续循环？
@@ -727,87 +2202,519 @@ impl PoolInner {
         }
     }
 
-    fn create_connection(
+    /// 借出前执行 on_acquire 初始化
+    fn run_on_acquire(&self, conn: &Arc<Connection>) -> Result<()> {
+        if let Some(on_acquire) = &self.config.on_acquire {
+            on_acquire(conn.connection_type()).map_err(|e| {
+                NetConnPoolError::InvalidConnection {
+                    connection_id: conn.id(),
+                    reason: format!("on_acquire 失败: {}", e),
+                }
+            })?;
+        }
+        Ok(())
+    }
+
+    /// 池满等待超时：记录统计并构造 PoolExhausted
+    ///
+    /// `wait_reason` 由调用方在判定池满时记录，而不是在这里按当前状态重新推断。
+    fn exhausted_error(
         &self,
-        required_protocol: Option<Protocol>,
-        required_ip_version: Option<IPVersion>,
-    ) -> Result<Arc<Connection>> {
-        // Double check max connections to ensure consistency
-        // 第一次检查：快速检查（read lock，不阻塞其他读取）
-        // 创建连接（耗时操作，不持锁）
-        // 第二次检查：最终检查（write lock，确保原子性）
-        // 这样可以避免在创建连接期间持有锁，同时确保不会超出限制
-
-        // 第一次检查：快速预检查
-        if self.config.max_connections > 0 {
-            let current = self
-                .all_connections
-                .read()
-                .map_err(|e| {
-                    NetConnPoolError::IoError(std::io::Error::other(format!(
-                        "读取连接数失败: {}",
-                        e
-                    )))
-                })?
-                .len();
-            if current >= self.config.max_connections {
-                return Err(NetConnPoolError::MaxConnectionsReached {
-                    current,
-                    max: self.config.max_connections,
-                });
+        current: usize,
+        max: usize,
+        waited: Duration,
+        timeout: Duration,
+        wait_reason: WaitReason,
+    ) -> NetConnPoolError {
+        if let Some(stats) = &self.stats_collector {
+            stats.increment_failed_gets();
+            if !timeout.is_zero() {
+                stats.increment_timeout_gets();
             }
         }
+        self.exhausted_gets.fetch_add(1, Ordering::Relaxed);
+        NetConnPoolError::PoolExhausted {
+            current,
+            max,
+            waited,
+            wait_reason,
+            last_dial_error: self
+                .last_dial_error
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .clone(),
+        }
+    }
 
-        let conn_type = match self.config.mode {
-            PoolMode::Client => {
-                if let Some(dialer) = &self.config.dialer {
-                    dialer(required_protocol).map_err(|e| {
-                        NetConnPoolError::IoError(std::io::Error::other(e.to_string()))
-                    })?
-                } else {
-                    return Err(NetConnPoolError::InvalidConfig {
-                        reason: "客户端模式需要 Dialer".to_string(),
-                    });
-                }
-            }
-            PoolMode::Server => {
-                if let Some(listener) = &self.config.listener {
-                    let acceptor = self.config.acceptor.as_ref().ok_or_else(|| {
-                        NetConnPoolError::InvalidConfig {
-                            reason: "服务器模式需要 Acceptor".to_string(),
-                        }
-                    })?;
-                    ConnectionType::Tcp(acceptor(listener).map_err(|e| {
-                        NetConnPoolError::IoError(std::io::Error::other(e.to_string()))
-                    })?)
-                } else {
-                    return Err(NetConnPoolError::InvalidConfig {
-                        reason: "服务器模式需要 Listener".to_string(),
-                    });
-                }
-            }
+    /// 等待 `since` 起计的有效优先级：每等待一个 priority_aging 周期提升一级
+    fn effective_priority(&self, priority: u8, since: Instant, now: Instant) -> u64 {
+        let aging = self.config.priority_aging;
+        let boost = if aging.is_zero() {
+            0
+        } else {
+            (now.saturating_duration_since(since).as_nanos() / aging.as_nanos()) as u64
         };
+        priority as u64 + boost
+    }
+
+    /// 是否存在有效优先级高于本请求的其他等待者
+    ///
+    /// 低优先级请求等待越久有效优先级越高，超过新到的高优先级请求后反过来由对方让行，避免饿死。
+    fn should_yield_to_priority(&self, priority: u8, since: Instant, own: Option<u64>) -> bool {
+        if self.priority_waiters.load(Ordering::Acquire) == 0 {
+            return false;
+        }
+        let now = Instant::now();
+        let mine = self.effective_priority(priority, since, now);
+        self.wait_entries
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .iter()
+            .any(|e| Some(e.id) != own && self.effective_priority(e.priority, e.since, now) > mine)
+    }
+
+    /// 连接归还/容量释放时唤醒等待者
+    ///
+    /// 存在带优先级的等待者时唤醒全部，由让行判定决定谁先拿到连接；
+    /// 否则只唤醒一个，避免惊群效应。
+    fn notify_waiter(&self) {
+        self.notify_waiters(1);
+    }
+
+    /// 一次放回 `count` 条连接后唤醒等待者：只递增一次版本号、加一次锁，
+    /// 唤醒不超过 `count` 个等待者（存在带优先级的等待者时唤醒全部）
+    fn notify_waiters(&self, count: usize) {
+        self.pool_version.fetch_add(1, Ordering::AcqRel);
+        // 等待者持锁复查版本号后才进入 wait，加锁保证通知不会落在复查与 wait 之间
+        drop(self.wait_lock.lock().unwrap_or_else(|e| e.into_inner()));
+        if self.priority_waiters.load(Ordering::Acquire) > 0
+            || count >= self.waiters.load(Ordering::Acquire).max(2)
+        {
+            self.wait_cv.notify_all();
+        } else {
+            for _ in 0..count {
+                self.wait_cv.notify_one();
+            }
+        }
+    }
+
+    /// 登记为等待者；已登记时直接返回，等待者已达 max_wait_queue 时返回 QueueFull
+    fn enter_wait_queue<'a>(
+        &'a self,
+        waiter: &mut Option<WaiterGuard<'a>>,
+        priority: u8,
+        since: Instant,
+    ) -> Result<()> {
+        if waiter.is_some() {
+            return Ok(());
+        }
+        let max = self.config.max_wait_queue;
+        if let Err(waiters) = self
+            .waiters
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |n| {
+                (max == 0 || n < max).then_some(n + 1)
+            })
+        {
+            if let Some(stats) = &self.stats_collector {
+                stats.increment_failed_gets();
+            }
+            return Err(NetConnPoolError::QueueFull { waiters, max });
+        }
+        let id = self.next_waiter_id.fetch_add(1, Ordering::Relaxed);
+        self.wait_entries
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .push(WaitEntry {
+                id,
+                priority,
+                since,
+            });
+        if priority > 0 {
+            self.priority_waiters.fetch_add(1, Ordering::AcqRel);
+        }
+        *waiter = Some(WaiterGuard {
+            pool: self,
+            id,
+            priority,
+        });
+        Ok(())
+    }
+
+    /// get 单次等待的上限：不越过慢获取触发时刻；可取消的 get 未配置 wait_poll_interval 时
+    /// 不超过 CANCEL_POLL_INTERVAL，醒来后在循环开头检查取消
+    fn bounded_wait(
+        &self,
+        slow_get_at: Option<Instant>,
+        cancel: Option<&AtomicBool>,
+        wait: Duration,
+    ) -> Duration {
+        let wait = slow_get_wait(slow_get_at, wait);
+        match (cancel, self.config.wait_poll_interval) {
+            (Some(_), None) => wait.min(CANCEL_POLL_INTERVAL),
+            _ => wait,
+        }
+    }
+
+    /// 单次等待时长：配置了 wait_poll_interval 时不超过它，醒来后由调用方重试
+    fn wait_slice(&self, timeout: Duration) -> Duration {
+        match self.config.wait_poll_interval {
+            Some(poll) => timeout.min(poll),
+            None => timeout,
+        }
+    }
+
+    /// 慢获取触发时刻：设置了 on_slow_get 时为 get 开始时间加 slow_get_threshold
+    fn slow_get_deadline(&self, start_time: Instant) -> Option<Instant> {
+        self.config
+            .on_slow_get
+            .as_ref()
+            .map(|_| start_time + self.config.slow_get_threshold)
+    }
+
+    /// 等待已越过慢获取软阈值时调用 on_slow_get，触发后清空触发时刻，保证每次 get 至多一次
+    fn check_slow_get(&self, slow_get_at: &mut Option<Instant>, start_time: Instant) {
+        if !slow_get_at.is_some_and(|at| Instant::now() >= at) {
+            return;
+        }
+        *slow_get_at = None;
+        if let Some(on_slow_get) = &self.config.on_slow_get {
+            on_slow_get(start_time.elapsed());
+        }
+    }
+
+    /// 在 get 中等待连接归还/池状态变化，最多等待 `timeout`
+    ///
+    /// 持有 wait_lock 后按谓词等待 closed 或池状态版本号变化：close() 与归还/移除连接
+    /// 都会先获取 wait_lock 再 notify，因此 `observed_version` 之后的变化不会在检查与进入 wait 之间丢失；
+    /// 虚假唤醒与无关的通知（如优先级等待者注销）不会返回，避免调用方无谓地重试一轮。
+    fn wait_for_pool_change(&self, timeout: Duration, observed_version: u64) {
+        let guard = self.wait_lock.lock().unwrap_or_else(|e| e.into_inner());
+        let _ = match self
+            .wait_cv
+            .wait_timeout_while(guard, self.wait_slice(timeout), |_| {
+                !self.is_closed() && self.pool_version.load(Ordering::Acquire) == observed_version
+            }) {
+            Ok(res) => res,
+            Err(e) => e.into_inner(),
+        };
+        #[cfg(test)]
+        POOL_WAIT_RETURNS.with(|n| n.set(n.get() + 1));
+    }
+
+    /// 让行时等待，直到不再需要让行（更高优先级的等待者离开）或连接池关闭，最多等待 `timeout`
+    ///
+    /// 持有 wait_lock 后按让行条件等待：WaiterGuard 注销后会先获取 wait_lock 再 notify_all，
+    /// 因此注销通知不会在复查与进入 wait 之间丢失；虚假唤醒与归还通知不改变让行判定，继续等待。
+    fn wait_for_priority_turn(
+        &self,
+        priority: u8,
+        since: Instant,
+        waiter: Option<&WaiterGuard<'_>>,
+        timeout: Duration,
+    ) {
+        let guard = self.wait_lock.lock().unwrap_or_else(|e| e.into_inner());
+        let _ = match self
+            .wait_cv
+            .wait_timeout_while(guard, self.wait_slice(timeout), |_| {
+                !self.is_closed()
+                    && self.should_yield_to_priority(priority, since, waiter.map(|w| w.id))
+            }) {
+            Ok(res) => res,
+            Err(e) => e.into_inner(),
+        };
+    }
+
+    /// 判断池满时的等待原因：连接全部借出时在等归还，否则是空闲连接不满足请求而在等建连额度
+    fn capacity_wait_reason(&self) -> WaitReason {
+        let total = self.connection_count.load(Ordering::Acquire);
+        if self.active_count.load(Ordering::Relaxed) >= total {
+            WaitReason::WaitingForIdle
+        } else {
+            WaitReason::WaitingForCapacity
+        }
+    }
+
+    /// 从目标桶中取出一条可借出的空闲连接
+    ///
+    /// 无效连接直接移除；不满足 `predicate` 的连接在扫描结束后放回原桶。
+    /// 带 `predicate` 时每个桶最多扫描当前空闲数量，避免与放回的连接形成循环。
+    fn take_idle_connection(
+        &self,
+        bucket_indices: &[usize],
+        predicate: Option<&ConnectionPredicate<'_>>,
+        role: Option<ConnectionRole>,
+    ) -> Option<Arc<Connection>> {
+        let mut rejected = Vec::new();
+        let mut found = None;
+        // 有断言或池中可能存在带角色的连接时，需要扫描整个桶才能找到匹配的连接
+        let filtered = predicate.is_some() || role.is_some() || self.config.role_dialer.is_some();
+
+        'buckets: for &idx in bucket_indices {
+            let budget = if filtered {
+                self.idle_counts[idx].load(Ordering::Relaxed).max(1)
+            } else {
+                1
+            };
+            for _ in 0..budget {
+                let conn = match self.idle_connections[idx].pop() {
+                    Some(conn) => conn,
+                    None => break,
+                };
+                // 更新计数器
+                self.idle_counts[idx].fetch_sub(1, Ordering::Relaxed);
+                // 从 idle 移除即应更新 idle 统计（无论最终是否可用）
+                if let Some(stats) = &self.stats_collector {
+                    self.update_stats_on_idle_pop(stats, &conn);
+                }
+
+                if !self.is_connection_valid_for_borrow(&conn) {
+                    let _ = self.remove_connection(&conn);
+                    continue;
+                }
+                // 正在做健康检查的连接放回队列，检查结束后再借出
+                if conn.is_checking() {
+                    rejected.push((idx, conn));
+                    continue;
+                }
+                // 角色精确匹配：普通 get 只借出未标注角色的连接
+                if conn.role() != role {
+                    rejected.push((idx, conn));
+                    continue;
+                }
+                if let Some(predicate) = predicate {
+                    if !predicate(&conn) {
+                        rejected.push((idx, conn));
+                        continue;
+                    }
+                }
+                found = Some(conn);
+                break 'buckets;
+            }
+        }
+
+        for (idx, conn) in rejected {
+            self.try_push_idle(conn, idx);
+        }
+        found
+    }
+
+    /// 取出指定 ID 的空闲连接：连接已被借出、已关闭或不在目标桶中时返回 None
+    ///
+    /// 先查连接表确认其空闲，避免连接已被别人拿走时白白扫描整个桶。
+    fn take_preferred_idle(
+        &self,
+        bucket_indices: &[usize],
+        id: u64,
+        role: Option<ConnectionRole>,
+    ) -> Option<Arc<Connection>> {
+        let idx = {
+            let connections = self
+                .all_connections
+                .read()
+                .unwrap_or_else(|e| e.into_inner());
+            let conn = connections.get(&id)?;
+            if conn.is_in_use() || conn.is_closed() {
+                return None;
+            }
+            Self::get_bucket_index(conn.protocol(), conn.ip_version())?
+        };
+        if !bucket_indices.contains(&idx) {
+            return None;
+        }
+        self.take_idle_connection(&[idx], Some(&|c: &Connection| c.id() == id), role)
+    }
+
+    fn create_connection(
+        &self,
+        required_protocol: Option<Protocol>,
+        required_ip_version: Option<IPVersion>,
+        role: Option<ConnectionRole>,
+    ) -> Result<Arc<Connection>> {
+        if self.dialing_paused.load(Ordering::Acquire) {
+            return Err(NetConnPoolError::DialingPaused);
+        }
+
+        // 先预占额度再建连：超限时直接返回，不会出现"建了又拆"浪费后端资源；
+        // 额度在连接插入连接表后转为存活连接占用，任何失败路径上随 slot 释放
+        let slot = self.reserve_slot()?;
+
+        // 所有建连路径（get、预热、快速失败预检、后台补建）共用同一个令牌桶；
+        // 池满时上面已返回，不消耗令牌
+        let rate_limiter = self.create_rate_limiter.as_ref();
+        if let Some(limiter) = rate_limiter {
+            limiter
+                .try_acquire()
+                .map_err(|_| NetConnPoolError::CreateRateLimited {
+                    waited: Duration::ZERO,
+                })?;
+        }
+
+        // 未调用 dialer 就失败（协议上限、FD 预算、建连并发已满）时退还令牌，
+        // 避免这些请求消耗建连速率、拖慢之后真正的建连
+        let pre_dial = || -> Result<_> {
+            // 按协议上限：指定协议时建连前检查；未指定时 dialer 产出的协议事先未知，
+            // 只能在 admit_connection 中按实际协议检查，超限的连接建立后即关闭
+            if let Some(protocol) = required_protocol {
+                self.check_protocol_limit(protocol)?;
+            }
+            self.check_fd_budget()?;
+            self.acquire_dial_permit()
+        };
+        let _permit = pre_dial().inspect_err(|_| {
+            if let Some(limiter) = rate_limiter {
+                limiter.refund();
+            }
+        })?;
+
+        let mut fallback = false;
+        let (conn_type, label) = match self.config.mode {
+            PoolMode::Client => {
+                let dial_start = Instant::now();
+                let dialed = if let Some(role) = role {
+                    let dialer = self.config.role_dialer.as_ref().ok_or_else(|| {
+                        NetConnPoolError::InvalidConfig {
+                            reason: "按角色获取连接需要 RoleDialer".to_string(),
+                        }
+                    })?;
+                    dialer(required_protocol, role).map(|conn_type| (conn_type, None))
+                } else if let Some(dialer) = &self.config.labeled_dialer {
+                    dialer(required_protocol)
+                } else if let Some(dialer) = &self.config.dialer {
+                    dialer(required_protocol).map(|conn_type| (conn_type, None))
+                } else {
+                    return Err(NetConnPoolError::InvalidConfig {
+                        reason: "客户端模式需要 Dialer".to_string(),
+                    });
+                };
+                // 记录最近一次建连失败（成功后清空），池满超时时作为根因附在错误里
+                let mut last_dial_error = self
+                    .last_dial_error
+                    .lock()
+                    .unwrap_or_else(|e| e.into_inner());
+                match &dialed {
+                    Ok(_) => *last_dial_error = None,
+                    Err(e) => *last_dial_error = Some(LastDialError::new(e.as_ref())),
+                }
+                drop(last_dial_error);
+                if let (Ok(_), Some(budget)) = (&dialed, &self.adaptive_budget) {
+                    budget.record(dial_start.elapsed());
+                }
+                match (dialed, &self.config.fallback_dialer) {
+                    (Ok(dialed), _) => dialed,
+                    // 主 Dialer 失败时退回降级后端；降级也失败则返回主 Dialer 的错误
+                    (Err(e), Some(fallback_dialer)) => match fallback_dialer(required_protocol) {
+                        Ok(conn_type) => {
+                            fallback = true;
+                            (conn_type, None)
+                        }
+                        Err(_) => return Err(NetConnPoolError::dial_failed(e)),
+                    },
+                    (Err(e), None) => return Err(NetConnPoolError::dial_failed(e)),
+                }
+            }
+            PoolMode::Server => {
+                if let Some(listener) = &self.config.listener {
+                    let acceptor = self.config.acceptor.as_ref().ok_or_else(|| {
+                        NetConnPoolError::InvalidConfig {
+                            reason: "服务器模式需要 Acceptor".to_string(),
+                        }
+                    })?;
+                    let stream = acceptor(listener).map_err(|e| {
+                        NetConnPoolError::IoError(std::io::Error::other(e.to_string()))
+                    })?;
+                    (ConnectionType::Tcp(stream), None)
+                } else {
+                    return Err(NetConnPoolError::InvalidConfig {
+                        reason: "服务器模式需要 Listener".to_string(),
+                    });
+                }
+            }
+        };
+
+        // 建连期间连接池已关闭（如 Pool 刚被 drop）：直接关闭新连接，不再初始化和纳入连接池
+        if self.is_closed() {
+            self.close_connection(&Arc::new(Connection::new(conn_type, None)));
+            return Err(NetConnPoolError::PoolClosed);
+        }
 
         if let Some(on_created) = &self.config.on_created {
             on_created(&conn_type)
                 .map_err(|e| NetConnPoolError::IoError(std::io::Error::other(e.to_string())))?;
         }
 
+        let conn = self.admit_connection(
+            conn_type,
+            label,
+            role,
+            slot,
+            required_protocol,
+            required_ip_version,
+        )?;
+        if fallback {
+            conn.mark_fallback();
+        }
+        Ok(conn)
+    }
+
+    /// 将已建立的连接纳入连接池管理：统一 socket 设置后插入连接表并更新统计
+    ///
+    /// `slot` 为调用方预占的连接额度，插入成功后转为存活连接占用。
+    /// 连接不满足 `required_protocol` / `required_ip_version` 或超出协议上限时关闭并返回错误。
+    fn admit_connection(
+        &self,
+        conn_type: ConnectionType,
+        label: Option<String>,
+        role: Option<ConnectionRole>,
+        slot: SlotReservation<'_>,
+        required_protocol: Option<Protocol>,
+        required_ip_version: Option<IPVersion>,
+    ) -> Result<Arc<Connection>> {
+        self.apply_default_timeouts(&conn_type)
+            .map_err(NetConnPoolError::IoError)?;
+
         // 连接池内部统一使用阻塞模式（与 UDP 清缓冲逻辑保持一致）
         let conn = match conn_type {
             ConnectionType::Tcp(stream) => {
                 stream
                     .set_nonblocking(false)
                     .map_err(NetConnPoolError::IoError)?;
-                Arc::new(Connection::new_from_tcp(stream, None))
+                self.apply_tcp_options(&stream)?;
+                self.config
+                    .socket_options
+                    .apply(SockRef::from(&stream), true)
+                    .map_err(NetConnPoolError::IoError)?;
+                Connection::new_from_tcp(stream, None)
             }
             ConnectionType::Udp(socket) => {
                 socket
                     .set_nonblocking(false)
                     .map_err(NetConnPoolError::IoError)?;
-                Arc::new(Connection::new_from_udp(socket, None))
+                self.config
+                    .socket_options
+                    .apply(SockRef::from(&socket), false)
+                    .map_err(NetConnPoolError::IoError)?;
+                Connection::new_from_udp(socket, None)
+            }
+            #[cfg(feature = "tls")]
+            ConnectionType::Tls(tls) => {
+                tls.with_tcp(|stream| -> Result<()> {
+                    stream
+                        .set_nonblocking(false)
+                        .map_err(NetConnPoolError::IoError)?;
+                    self.apply_tcp_options(stream)?;
+                    self.config
+                        .socket_options
+                        .apply(SockRef::from(stream), true)
+                        .map_err(NetConnPoolError::IoError)
+                })?;
+                Connection::new(ConnectionType::Tls(tls), None)
             }
         };
+        let conn = Arc::new(
+            conn.with_lifetime_jitter(self.config.lifetime_jitter)
+                .with_label(label)
+                .with_role(role),
+        );
 
         // Check requirements
         if let Some(p) = required_protocol {
@@ -835,9 +2742,7 @@ impl PoolInner {
             }
         }
 
-        // 第二次检查：最终检查并插入（write lock，确保原子性）
-        // 这是 double-check 的关键：即使第一次检查通过，在插入前再次检查
-        // 可以防止多个线程同时通过第一次检查后都创建连接导致超出限制
+        // 插入连接表（write lock）；额度已预占，这里的 max 检查仅作兜底
         {
             let mut connections = self.all_connections.write().map_err(|e| {
                 NetConnPoolError::IoError(std::io::Error::other(format!(
@@ -856,6 +2761,13 @@ impl PoolInner {
                 });
             }
 
+            // 按协议上限检查：dialer 实际创建的协议可能与请求不同，以实际协议为准
+            if let Err(e) = self.check_protocol_limit(conn.protocol()) {
+                drop(connections);
+                self.close_connection(&conn);
+                return Err(e);
+            }
+
             // 检查连接 ID 是否冲突（虽然概率极低，但需要处理）
             // 如果冲突，说明 ID 生成器溢出后重置，且旧连接仍存在
             // 这种情况下，我们递增 ID 直到找到不冲突的
@@ -886,6 +2798,12 @@ impl PoolInner {
             }
 
             connections.insert(final_id, conn.clone());
+            slot.commit();
+            self.connection_count
+                .store(connections.len(), Ordering::Release);
+            if let Some(idx) = Self::protocol_index(conn.protocol()) {
+                self.protocol_counts[idx].fetch_add(1, Ordering::Relaxed);
+            }
         }
 
         if let Some(stats) = &self.stats_collector {
@@ -901,61 +2819,470 @@ impl PoolInner {
                 _ => {}
             }
         }
+        self.check_watermark();
+        self.check_availability();
+        self.emit(|| PoolEvent::Created {
+            connection_id: conn.id(),
+        });
 
         Ok(conn)
     }
 
-    fn return_connection(&self, conn: Arc<Connection>) {
+    /// 可用连接数：空闲连接 + 剩余可新建额度
+    fn availability(&self) -> usize {
+        let idle: usize = self
+            .idle_counts
+            .iter()
+            .map(|c| c.load(Ordering::Relaxed))
+            .sum();
+        idle + self
+            .config
+            .max_connections
+            .saturating_sub(self.reserved_slots.load(Ordering::Acquire))
+    }
+
+    /// 可用连接数变化后检查降级态：低于 min_availability 时进入、回到阈值及以上时退出，
+    /// 状态切换时调用一次 on_degradation
+    fn check_availability(&self) {
+        let min = self.config.min_availability;
+        if min == 0 {
+            return;
+        }
+        let available = self.availability();
+        let degraded = available < min;
+        if self.degraded.swap(degraded, Ordering::AcqRel) != degraded {
+            if let Some(callback) = &self.config.on_degradation {
+                callback(degraded, available);
+            }
+        }
+    }
+
+    /// 生效的健康检查间隔：降级态下加倍检查频率，尽快剔除坏连接
+    fn health_check_interval(&self) -> Duration {
+        if self.degraded.load(Ordering::Acquire) {
+            self.config.health_check_interval / 2
+        } else {
+            self.config.health_check_interval
+        }
+    }
+
+    /// 连接数变化后检查容量水位：升到阈值时触发一次 on_high_watermark，回落到阈值以下后重新布防
+    fn check_watermark(&self) {
+        let Some((ratio, callback)) = &self.config.on_high_watermark else {
+            return;
+        };
+        let usage = self.connection_count.load(Ordering::Acquire) as f64
+            / self.config.max_connections as f64;
+        if usage < *ratio {
+            self.watermark_high.store(false, Ordering::Release);
+            return;
+        }
+        if !self.watermark_high.swap(true, Ordering::AcqRel) {
+            if let Some(stats) = &self.stats_collector {
+                callback(&stats.get_stats());
+            }
+        }
+    }
+
+    /// 借出计数加一（总数与所在桶）
+    fn add_active(&self, conn: &Connection) {
+        self.active_count.fetch_add(1, Ordering::Relaxed);
+        if let Some(idx) = Self::get_bucket_index(conn.protocol(), conn.ip_version()) {
+            self.bucket_active_counts[idx].fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    /// 借出计数减一（总数与所在桶）
+    fn sub_active(&self, conn: &Connection) {
+        self.active_count.fetch_sub(1, Ordering::Relaxed);
+        if let Some(idx) = Self::get_bucket_index(conn.protocol(), conn.ip_version()) {
+            self.bucket_active_counts[idx].fetch_sub(1, Ordering::Relaxed);
+        }
+    }
+
+    /// 按连接表统计四个桶的连接分布（已关闭的连接不计入）
+    fn bucket_stats_of(connections: &HashMap<u64, Arc<Connection>>) -> [BucketStat; 4] {
+        let mut buckets = BUCKETS.map(|(protocol, ip_version)| BucketStat {
+            protocol,
+            ip_version,
+            idle: 0,
+            active: 0,
+        });
+        for conn in connections.values() {
+            let Some(idx) = Self::get_bucket_index(conn.protocol(), conn.ip_version()) else {
+                continue;
+            };
+            if conn.is_in_use() {
+                buckets[idx].active += 1;
+            } else if !conn.is_closed() {
+                buckets[idx].idle += 1;
+            }
+        }
+        buckets
+    }
+
+    /// 按连接表的真实状态填充 current_* 即时量（已关闭、待移除的连接不计入）
+    fn fill_current_counts(
+        stats: &mut crate::stats::Stats,
+        connections: &HashMap<u64, Arc<Connection>>,
+    ) {
+        let mut counts = [0i64; 11];
+        for conn in connections.values().filter(|c| !c.is_closed()) {
+            let idle = !conn.is_in_use();
+            let mut add = |i: usize, cond: bool| counts[i] += cond as i64;
+            add(0, true);
+            add(1, idle);
+            add(2, !idle);
+            let ipv4 = conn.ip_version() == IPVersion::IPv4;
+            let ipv6 = conn.ip_version() == IPVersion::IPv6;
+            let tcp = conn.protocol() == Protocol::TCP;
+            let udp = conn.protocol() == Protocol::UDP;
+            add(3, ipv4);
+            add(4, ipv6);
+            add(5, ipv4 && idle);
+            add(6, ipv6 && idle);
+            add(7, tcp);
+            add(8, udp);
+            add(9, tcp && idle);
+            add(10, udp && idle);
+        }
+        [
+            stats.current_connections,
+            stats.current_idle_connections,
+            stats.current_active_connections,
+            stats.current_ipv4_connections,
+            stats.current_ipv6_connections,
+            stats.current_ipv4_idle_connections,
+            stats.current_ipv6_idle_connections,
+            stats.current_tcp_connections,
+            stats.current_udp_connections,
+            stats.current_tcp_idle_connections,
+            stats.current_udp_idle_connections,
+        ] = counts;
+    }
+
+    /// 当前生效的连接数上限：启用软上限且等待者未达 burst_waiter_threshold 时为软上限，
+    /// 否则为 max_connections
+    fn connection_cap(&self) -> usize {
+        let soft = self.config.soft_max_connections;
+        if soft > 0 && self.waiters.load(Ordering::Acquire) < self.config.burst_waiter_threshold {
+            soft
+        } else {
+            self.config.max_connections
+        }
+    }
+
+    /// 原子预占一个连接额度，已达生效上限（见 connection_cap）时返回 MaxConnectionsReached
+    fn reserve_slot(&self) -> Result<SlotReservation<'_>> {
+        let max = self.connection_cap();
+        self.reserved_slots
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |n| {
+                (max == 0 || n < max).then_some(n + 1)
+            })
+            .map_err(|current| NetConnPoolError::MaxConnectionsReached { current, max })?;
+        Ok(SlotReservation {
+            pool: self,
+            committed: false,
+        })
+    }
+
+    /// 距离下一个建连令牌可用的等待时间；未限速时为 0
+    fn next_create_token_in(&self) -> Duration {
+        self.create_rate_limiter
+            .as_ref()
+            .map_or(Duration::ZERO, TokenBucket::next_token_in)
+    }
+
+    /// 后台建连（预热、补建）遇到速率限制时等待下一个令牌，连接池关闭时提前唤醒
+    fn wait_for_create_token(&self) {
+        let guard = self.reaper_lock.lock().unwrap_or_else(|e| e.into_inner());
+        if self.is_closed() {
+            return;
+        }
+        let _ = self
+            .reaper_cv
+            .wait_timeout(guard, self.next_create_token_in());
+    }
+
+    /// 占用一个建连并发许可，已有 max_concurrent_dials 个建连在进行时返回 TooManyConcurrentDials
+    ///
+    /// 未设置上限时不计数，返回 None。
+    fn acquire_dial_permit(&self) -> Result<Option<DialPermit<'_>>> {
+        let max = self.config.max_concurrent_dials;
+        if max == 0 {
+            return Ok(None);
+        }
+        self.dials_in_flight
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |n| {
+                (n < max).then_some(n + 1)
+            })
+            .map_err(|in_flight| NetConnPoolError::TooManyConcurrentDials { in_flight, max })?;
+        Ok(Some(DialPermit { pool: self }))
+    }
+
+    /// 进程已打开的描述符数达到 fd_budget 时拒绝建连（读取不到描述符数时放行）
+    fn check_fd_budget(&self) -> Result<()> {
+        let Some(budget) = self.fd_budget else {
+            return Ok(());
+        };
+        match crate::fd::open_fd_count() {
+            Some(open) if open >= budget => {
+                Err(NetConnPoolError::FdBudgetExceeded { open, budget })
+            }
+            _ => Ok(()),
+        }
+    }
+
+    fn protocol_index(protocol: Protocol) -> Option<usize> {
+        match protocol {
+            Protocol::TCP => Some(0),
+            Protocol::UDP => Some(1),
+            _ => None,
+        }
+    }
+
+    /// 检查指定协议的存活连接数是否已达到 max_tcp_connections / max_udp_connections
+    fn check_protocol_limit(&self, protocol: Protocol) -> Result<()> {
+        let max = match protocol {
+            Protocol::TCP => self.config.max_tcp_connections,
+            Protocol::UDP => self.config.max_udp_connections,
+            _ => 0,
+        };
+        if max == 0 {
+            return Ok(());
+        }
+        let current = Self::protocol_index(protocol)
+            .map(|idx| self.protocol_counts[idx].load(Ordering::Relaxed))
+            .unwrap_or(0);
+        if current >= max {
+            return Err(NetConnPoolError::MaxConnectionsReached { current, max });
+        }
+        Ok(())
+    }
+
+    /// 按配置为 TCP 连接设置 nodelay / keepalive
+    fn apply_tcp_options(&self, stream: &TcpStream) -> Result<()> {
+        if self.config.tcp_nodelay {
+            stream
+                .set_nodelay(true)
+                .map_err(NetConnPoolError::IoError)?;
+        }
+        if let Some(idle) = self.config.tcp_keepalive {
+            let keepalive = TcpKeepalive::new().with_time(idle);
+            SockRef::from(stream)
+                .set_tcp_keepalive(&keepalive)
+                .map_err(NetConnPoolError::IoError)?;
+        }
+        Ok(())
+    }
+
+    /// 将配置的默认读写超时应用到底层 socket（未配置时不做任何修改）
+    fn apply_default_timeouts(&self, conn_type: &ConnectionType) -> std::io::Result<()> {
+        let read = self.config.default_read_timeout;
+        let write = self.config.default_write_timeout;
+        let apply_tcp = |stream: &TcpStream| -> std::io::Result<()> {
+            if read.is_some() {
+                stream.set_read_timeout(read)?;
+            }
+            if write.is_some() {
+                stream.set_write_timeout(write)?;
+            }
+            Ok(())
+        };
+        match conn_type {
+            ConnectionType::Tcp(stream) => apply_tcp(stream),
+            ConnectionType::Udp(socket) => {
+                if read.is_some() {
+                    socket.set_read_timeout(read)?;
+                }
+                if write.is_some() {
+                    socket.set_write_timeout(write)?;
+                }
+                Ok(())
+            }
+            #[cfg(feature = "tls")]
+            ConnectionType::Tls(tls) => tls.with_tcp(apply_tcp),
+        }
+    }
+
+    /// 向 event_sink 发送事件（未配置时不构造事件），channel 已满或已断开时丢弃并计数
+    fn emit(&self, event: impl FnOnce() -> PoolEvent) {
+        if let Some(sink) = &self.config.event_sink {
+            if sink.try_send(event()).is_err() {
+                self.dropped_events.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+    }
+
+    /// 首次发现连接疑似泄漏时计入累计与当前泄漏统计
+    fn report_leak(&self, conn: &Connection) {
+        if conn.report_leak_once() {
+            if let Some(stats) = &self.stats_collector {
+                stats.increment_leaked_connections();
+                stats.increment_current_leaked_connections(1);
+            }
+            self.emit(|| PoolEvent::Leaked {
+                connection_id: conn.id(),
+            });
+        }
+    }
+
+    /// 疑似泄漏的连接归还或被移除时，当前泄漏计数回落（累计计数保留）
+    fn clear_leak(&self, conn: &Connection) {
+        if conn.clear_leak_report() {
+            if let Some(stats) = &self.stats_collector {
+                stats.increment_current_leaked_connections(-1);
+            }
+        }
+    }
+
+    fn return_connection(self: &Arc<Self>, conn: Arc<Connection>, epoch: u64) {
+        if let Some(conn) = self.release_borrow(conn, epoch) {
+            self.finish_return(conn);
+        }
+    }
+
+    /// 批量归还：逐条完成归还，整批放回空闲池后只唤醒一次等待者
+    fn return_connections(self: &Arc<Self>, conns: Vec<(Arc<Connection>, u64)>) {
+        let mut pushed = 0;
+        for (conn, epoch) in conns {
+            if let Some(conn) = self.release_borrow(conn, epoch) {
+                if self.restore_idle(conn) {
+                    pushed += 1;
+                }
+            }
+        }
+        if pushed > 0 {
+            self.notify_waiters(pushed);
+        }
+    }
+
+    /// 结束一次借用并切换为空闲状态；需要在当前线程完成归还时返回连接
+    ///
+    /// 令牌不匹配（重复或过期的归还）以及已投递到异步归还队列时返回 None。
+    fn release_borrow(&self, conn: Arc<Connection>, epoch: u64) -> Option<Arc<Connection>> {
+        // 借用令牌不匹配：同一次借用的重复归还，或旧句柄在连接被再次借出后归还，直接忽略
+        if !conn.end_borrow(epoch) {
+            return None;
+        }
+        self.emit(|| PoolEvent::Returned {
+            connection_id: conn.id(),
+        });
+
+        self.clear_leak(&conn);
+
         // 归还：从 active -> idle（避免重复扣减 active 统计）
         // 使用 try_mark_idle 原子操作，防止与 reaper 线程强制驱逐产生竞态
         if conn.try_mark_idle() {
-            self.active_count.fetch_sub(1, Ordering::Relaxed);
+            self.sub_active(&conn);
             if let Some(stats) = &self.stats_collector {
                 stats.increment_current_active_connections(-1);
             }
-            // 优化：使用 notify_one() 避免惊群效应
-            // 归还一个连接时，只需要唤醒一个等待的线程
-            self.wait_cv.notify_one();
         }
 
+        // 异步归还：其余工作交给后台线程，持有者的 Drop 到此返回
+        self.enqueue_return(conn)
+    }
+
+    /// 把已切换为空闲状态的连接投递到异步归还队列，未启用或队列已关闭时原样返回
+    fn enqueue_return(&self, conn: Arc<Connection>) -> Option<Arc<Connection>> {
+        match &*self.return_queue.lock().unwrap_or_else(|e| e.into_inner()) {
+            Some(tx) => tx.send(conn).err().map(|e| e.0),
+            None => Some(conn),
+        }
+    }
+
+    /// 完成归还：校验有效性、执行 on_return，放回空闲池或关闭
+    fn finish_return(self: &Arc<Self>, conn: Arc<Connection>) {
+        if self.restore_idle(conn) {
+            self.notify_waiter();
+        }
+    }
+
+    /// 完成归还但不唤醒等待者，返回连接是否已放回空闲池
+    fn restore_idle(self: &Arc<Self>, conn: Arc<Connection>) -> bool {
         if self.is_closed() {
             let _ = self.remove_connection(&conn);
-            return;
+            return false;
         }
 
         if !self.is_connection_valid_for_borrow(&conn) {
             let _ = self.remove_connection(&conn);
-            return;
+            return false;
+        }
+
+        if conn.protocol() == Protocol::UDP
+            && conn.is_rebind_due(
+                self.config.udp_rebind_after_reuses,
+                self.config.udp_rebind_after,
+            )
+        {
+            self.rebind_udp(&conn);
+            return false;
+        }
+
+        // 异步归还时清理本就不在持有者线程上，直接在此清空 UDP 缓冲区
+        if self.config.async_return && self.config.clear_udp_buffer_on_return {
+            if let Some(udp_socket) = conn.udp_conn() {
+                let _ = clear_udp_read_buffer(
+                    udp_socket,
+                    self.config.udp_buffer_clear_timeout,
+                    self.config.max_buffer_clear_packets,
+                );
+            }
+        }
+
+        // 突发过后超出软上限的连接：无人等待时直接关闭，让连接数回落到软上限
+        let soft = self.config.soft_max_connections;
+        if soft > 0
+            && self.connection_count.load(Ordering::Acquire) > soft
+            && self.waiters.load(Ordering::Acquire) == 0
+        {
+            let _ = self.remove_connection(&conn);
+            return false;
         }
 
         if let Some(on_return) = &self.config.on_return {
             on_return(conn.connection_type());
         }
 
-        // 优化：UDP 缓冲区清理延迟到 get() 时进行，避免阻塞归还操作
+        // 同步归还时 UDP 缓冲区清理延迟到 get() 时进行，避免阻塞归还操作
         // 这样可以确保 return_connection 操作极致轻量，不会因为底层 I/O 阻塞
 
         // Put back to idle list (无锁操作)
         if let Some(idx) = Self::get_bucket_index(conn.protocol(), conn.ip_version()) {
             // 使用提取的辅助方法处理 CAS 逻辑
-            self.try_push_idle(conn, idx);
+            self.push_idle(conn, idx)
         } else {
             // Unknown protocol/ip, cannot pool efficiently. Close it.
             let _ = self.remove_connection(&conn);
+            false
         }
     }
 
+    /// 关闭达到重绑定阈值的 UDP 连接，并交给后台建连线程补建一条同类型连接
+    ///
+    /// 归还发生在持有者的 Drop 里，补建要经过用户 Dialer（可能解析 DNS 等阻塞操作），
+    /// 因此不在当前线程拨号；补建完成前下一个 get 会现场建连，新连接换到新的本地端口。
+    fn rebind_udp(self: &Arc<Self>, conn: &Arc<Connection>) {
+        let _ = self.remove_connection(conn);
+        self.schedule_background_dials(vec![(
+            Some(Protocol::UDP),
+            Some(conn.ip_version()),
+            conn.role(),
+        )]);
+    }
+
     fn remove_connection(&self, conn: &Arc<Connection>) -> Result<()> {
+        self.clear_leak(conn);
+
         // 如果在关闭/清理过程中强制移除使用中的连接，修正 active 统计
         // 使用 try_mark_idle 原子操作，防止与 return_connection 产生竞态
         if conn.try_mark_idle() {
-            self.active_count.fetch_sub(1, Ordering::Relaxed);
+            self.sub_active(conn);
             if let Some(stats) = &self.stats_collector {
                 stats.increment_current_active_connections(-1);
             }
-            // 优化：使用 notify_one() 避免惊群效应
-            // 移除一个连接时，只需要唤醒一个等待的线程
-            self.wait_cv.notify_one();
         }
         // 注意：如果连接在idle队列中，我们不在这里更新idle_counts计数器
         // 因为SegQueue不支持删除特定元素，连接仍在队列中
@@ -978,27 +3305,85 @@ impl PoolInner {
 
             // 使用 conn.id() 移除连接
             // ID 冲突已在 create_connection 中处理，确保 conn.id() 与 key 一致
-            connections.remove(&conn.id());
+            // 已被移除过（如 reaper 强制回收后持有者再归还）时不重复扣减计数
+            if connections.remove(&conn.id()).is_none() {
+                return Ok(());
+            }
+            self.reserved_slots.fetch_sub(1, Ordering::AcqRel);
+            self.connection_count
+                .store(connections.len(), Ordering::Release);
+            if let Some(idx) = Self::protocol_index(conn.protocol()) {
+                self.protocol_counts[idx].fetch_sub(1, Ordering::Relaxed);
+            }
+        }
+        // 容量已释放，唤醒等待建连额度的 get
+        self.notify_waiter();
+
+        if let Some(stats) = &self.stats_collector {
+            stats.increment_total_connections_closed();
+            match conn.ip_version() {
+                IPVersion::IPv4 => stats.increment_current_ipv4_connections(-1),
+                IPVersion::IPv6 => stats.increment_current_ipv6_connections(-1),
+                _ => {}
+            }
+            match conn.protocol() {
+                Protocol::TCP => stats.increment_current_tcp_connections(-1),
+                Protocol::UDP => stats.increment_current_udp_connections(-1),
+                _ => {}
+            }
         }
+        self.check_watermark();
+        self.check_availability();
+        self.emit(|| PoolEvent::Closed {
+            connection_id: conn.id(),
+        });
+
+        Ok(())
+    }
+
+    /// 丢弃已被 get 独占（try_acquire 成功）但尚未借出的连接
+    ///
+    /// 此时尚未计入 active_count，先撤销占用，避免 remove_connection 误扣减活跃计数。
+    fn discard_claimed(&self, conn: &Arc<Connection>) {
+        conn.mark_idle();
+        let _ = self.remove_connection(conn);
+    }
+
+    /// 将各桶空闲连接数收敛到当前的 max_idle_connections 上限
+    ///
+    /// 超限的桶按最近使用时间排序，优先回收最久未用的连接，保留热连接；
+    /// 保留的连接按从旧到新的顺序放回队列，维持 FIFO 借出顺序。
+    fn trim_idle_connections(&self) {
+        let max_idle = self.max_idle_connections.load(Ordering::Relaxed);
+        for (idx, idle) in self.idle_connections.iter().enumerate() {
+            if self.idle_counts[idx].load(Ordering::Relaxed) <= max_idle {
+                continue;
+            }
+
+            // 取出整个桶的有序视图；与 get_connection 竞争时以实际 pop 结果为准
+            let mut conns = Vec::new();
+            while let Some(conn) = idle.pop() {
+                conns.push(conn);
+            }
+            conns.sort_by_key(|conn| std::cmp::Reverse(conn.last_used_nanos()));
 
-        if let Some(stats) = &self.stats_collector {
-            stats.increment_total_connections_closed();
-            match conn.ip_version() {
-                IPVersion::IPv4 => stats.increment_current_ipv4_connections(-1),
-                IPVersion::IPv6 => stats.increment_current_ipv6_connections(-1),
-                _ => {}
+            let keep = conns.len().min(max_idle);
+            for conn in conns.drain(keep..) {
+                self.idle_counts[idx].fetch_sub(1, Ordering::Relaxed);
+                if let Some(stats) = &self.stats_collector {
+                    self.update_stats_on_idle_pop(stats, &conn);
+                }
+                let _ = self.remove_connection(&conn);
             }
-            match conn.protocol() {
-                Protocol::TCP => stats.increment_current_tcp_connections(-1),
-                Protocol::UDP => stats.increment_current_udp_connections(-1),
-                _ => {}
+            for conn in conns.into_iter().rev() {
+                idle.push(conn);
             }
         }
-
-        Ok(())
     }
 
-    fn cleanup(&self) {
+    fn cleanup(self: &Arc<Self>) {
+        self.trim_idle_connections();
+
         let conns: Vec<Arc<Connection>> = {
             // 如果获取锁失败，返回空列表（清理失败不影响主流程）
             if let Ok(connections) = self.all_connections.read() {
@@ -1009,6 +3394,7 @@ impl PoolInner {
         };
 
         let mut to_remove = Vec::new();
+        let mut to_check = Vec::new();
 
         for conn in conns {
             if self.is_closed() {
@@ -1017,23 +3403,25 @@ impl PoolInner {
 
             // 连接使用中：检查是否严重泄漏，如果是则强制驱逐
             if conn.is_in_use() {
-                let is_leaked = conn.is_leaked(self.config.connection_leak_timeout);
+                // 通过 get_with_lease 借出的连接按各自租约判定，其余沿用全局 connection_leak_timeout
+                let leak_timeout = conn.lease().unwrap_or(self.config.connection_leak_timeout);
+                let is_leaked = conn.is_leaked(leak_timeout);
                 let is_expired = conn.is_expired(self.config.max_lifetime);
 
                 // 如果连接严重泄漏（超过 leak_timeout 的 2 倍），强制驱逐
                 // 这是为了保护连接池内存不被用户代码错误导致的泄漏连接撑爆
+                // 严格模式（force_reclaim_on_leak）下超过 leak_timeout 即驱逐
                 if is_leaked {
-                    let leak_timeout = self.config.connection_leak_timeout;
                     if !leak_timeout.is_zero() {
+                        let reclaim_after = if self.config.force_reclaim_on_leak {
+                            leak_timeout
+                        } else {
+                            leak_timeout * 2
+                        };
                         // 获取具体的泄漏时间
                         if let Some(leaked_duration) = conn.get_leaked_duration() {
-                            // 如果泄漏时间超过配置的 2 倍，强制驱逐
-                            if leaked_duration > leak_timeout * 2 {
-                                if conn.report_leak_once() {
-                                    if let Some(stats) = &self.stats_collector {
-                                        stats.increment_leaked_connections();
-                                    }
-                                }
+                            if leaked_duration > reclaim_after {
+                                self.report_leak(&conn);
                                 eprintln!(
                                     "警告: 强制驱逐严重泄漏的连接 ID {} (泄漏时间: {:?})",
                                     conn.id(),
@@ -1046,11 +3434,7 @@ impl PoolInner {
                         }
                     }
 
-                    if conn.report_leak_once() {
-                        if let Some(stats) = &self.stats_collector {
-                            stats.increment_leaked_connections();
-                        }
-                    }
+                    self.report_leak(&conn);
                     conn.mark_unhealthy();
                 }
                 if is_expired {
@@ -1059,40 +3443,385 @@ impl PoolInner {
                 continue;
             }
 
-            // 健康检查（仅对 idle 连接）
-            if self.config.enable_health_check {
-                if let Some(checker) = &self.config.health_checker {
-                    if conn.should_health_check(self.config.health_check_interval) {
-                        if let Some(stats) = &self.stats_collector {
-                            stats.increment_health_check_attempts();
-                        }
-                        let ok = checker(conn.connection_type());
-                        if !ok {
-                            if let Some(stats) = &self.stats_collector {
-                                stats.increment_health_check_failures();
-                                stats.increment_unhealthy_connections();
-                            }
-                            conn.update_health(false);
-                            to_remove.push(conn.clone());
-                            continue;
-                        }
-                        conn.update_health(true);
-                    }
-                }
+            // 健康检查（仅对 idle 连接）：先收集，稍后统一（可并行）执行
+            if self.config.enable_health_check
+                && self.has_health_checker()
+                && conn.should_health_check(self.health_check_interval())
+            {
+                to_check.push(conn);
+                continue;
             }
 
-            if !self.is_connection_valid_for_borrow(&conn) {
+            // 只移除仍未被借出的连接：判定与借出之间连接可能已被 get 取走
+            if !self.is_connection_valid_for_borrow(&conn) && conn.mark_unhealthy_if_idle() {
                 to_remove.push(conn.clone());
             }
         }
 
+        // 检查涉及 I/O（验证往返会读写 socket）：先独占连接，期间 get 不会借出它；
+        // 收集后已被借出的连接留给下一轮
+        to_check.retain(|conn| conn.try_begin_check());
+        let results = self.run_health_checks(&to_check);
+        for (conn, result) in to_check.into_iter().zip(results) {
+            // 未执行检查（连接池关闭或工作线程异常退出）：连接保持原状态，留给下一轮
+            let Some(result) = result else {
+                conn.end_check();
+                continue;
+            };
+            let healthy = self.record_health_check(&conn, &result);
+            conn.end_check();
+            if !healthy {
+                to_remove.push(conn);
+                continue;
+            }
+            if !self.is_connection_valid_for_borrow(&conn) && conn.mark_unhealthy_if_idle() {
+                to_remove.push(conn);
+            }
+        }
+
+        let mut replacements = Vec::new();
         for conn in to_remove {
             let _ = self.remove_connection(&conn);
+            if self.needs_replacement(&conn) {
+                replacements.push((Some(conn.protocol()), Some(conn.ip_version()), conn.role()));
+            }
+        }
+        self.schedule_background_dials(replacements);
+
+        self.purge_invalid_idle_connections();
+    }
+
+    /// 从空闲队列中摘除已失效的连接（空闲超时、过期、已关闭、对端已关闭）
+    ///
+    /// SegQueue 不支持按元素删除，被移除的连接原本要等到 get 时才会被 pop 出来，
+    /// 期间仍占用 FD 和空闲计数。这里只处理含有空闲已超过
+    /// idle_timeout / max_lifetime / idle_probe_after 中最小阈值的连接（或残留条目）的桶，
+    /// 从队首起检查到第一条未达阈值的有效连接为止：有效连接放回队尾，失效连接立即释放，
+    /// 与 enable_health_check 无关；三者均未启用时跳过。
+    fn purge_invalid_idle_connections(&self) {
+        // 只有空闲超过最小阈值的连接才可能需要回收；均未启用时不动空闲队列
+        let Some(threshold) = [
+            self.config.idle_timeout,
+            self.config.max_lifetime,
+            self.config.idle_probe_after,
+        ]
+        .into_iter()
+        .filter(|d| !d.is_zero())
+        .min() else {
+            return;
+        };
+
+        // 先只读扫描连接表：桶内没有空闲超过阈值的连接、队列里也没有已移出连接表的残留时，
+        // 不弹出任何连接，保持队列顺序
+        let mut live_idle = [0usize; 4];
+        let mut stale = [false; 4];
+        if let Ok(connections) = self.all_connections.read() {
+            for conn in connections.values().filter(|c| !c.is_in_use()) {
+                if let Some(idx) = Self::get_bucket_index(conn.protocol(), conn.ip_version()) {
+                    live_idle[idx] += 1;
+                    stale[idx] |= conn.idle_time() >= threshold;
+                }
+            }
+        }
+
+        for (idx, idle) in self.idle_connections.iter().enumerate() {
+            if !stale[idx] && self.idle_counts[idx].load(Ordering::Relaxed) <= live_idle[idx] {
+                continue;
+            }
+            let queued = idle.len();
+            for _ in 0..queued {
+                if self.is_closed() {
+                    return;
+                }
+                // 队首是最早放回的连接：遇到空闲未达阈值的有效连接即停止，
+                // 不把整条队列倒一遍，避免并发 get 看到空桶而多余建连或等待
+                let conn = match idle.pop() {
+                    Some(conn) => conn,
+                    None => break,
+                };
+                if self.is_connection_valid_for_borrow(&conn) && !self.idle_peer_closed(&conn) {
+                    // 仍在空闲状态，计数不变
+                    let young = conn.idle_time() < threshold;
+                    idle.push(conn);
+                    if young {
+                        break;
+                    }
+                    continue;
+                }
+                self.idle_counts[idx].fetch_sub(1, Ordering::Relaxed);
+                if let Some(stats) = &self.stats_collector {
+                    self.update_stats_on_idle_pop(stats, &conn);
+                }
+                let _ = self.remove_connection(&conn);
+            }
+        }
+    }
+
+    /// 空闲超过 idle_probe_after 的 TCP/TLS 连接：探测对端是否已关闭
+    ///
+    /// 仅对从空闲队列取出的连接调用，探测期间连接不会被借出，临时切换非阻塞模式不影响使用者。
+    fn idle_peer_closed(&self, conn: &Connection) -> bool {
+        let probe_after = self.config.idle_probe_after;
+        if probe_after.is_zero() || conn.idle_time() < probe_after {
+            return false;
+        }
+        peer_closed(conn.connection_type())
+    }
+
+    /// 判断被 reaper 移除的空闲连接是否需要补建一条同类型连接（热替换）
+    ///
+    /// 仅在连接总数低于预热目标（min_connections / warm_ratio）或该桶已无可用空闲连接时补建，
+    /// 避免下一个 get 现场建连带来的延迟。
+    fn needs_replacement(&self, evicted: &Arc<Connection>) -> bool {
+        if !self.config.replace_evicted_connections
+            || self.config.mode != PoolMode::Client
+            || self.is_closed()
+        {
+            return false;
+        }
+
+        let protocol = evicted.protocol();
+        let ip_version = evicted.ip_version();
+        match self.all_connections.read() {
+            Ok(connections) => {
+                // idle_counts 延迟更新，这里直接统计该桶内实际存活的空闲连接
+                let bucket_empty = !connections.values().any(|c| {
+                    !c.is_in_use()
+                        && !c.is_closed()
+                        && c.protocol() == protocol
+                        && c.ip_version() == ip_version
+                });
+                bucket_empty || connections.len() < self.config.warm_target()
+            }
+            Err(_) => false,
+        }
+    }
+
+    /// 将建连任务交给后台建连线程
+    ///
+    /// 拨号可能因后端缓慢或不可达而阻塞，放在 reaper 或调用方线程上会拖住清理、健康检查或借出，
+    /// 因此这里只入队；同一时刻最多一个后台建连线程，它持有 Weak 引用，池销毁后自行退出。
+    fn schedule_background_dials(
+        self: &Arc<Self>,
+        targets: Vec<(Option<Protocol>, Option<IPVersion>, Option<ConnectionRole>)>,
+    ) {
+        if targets.is_empty() {
+            return;
+        }
+        let Ok(mut queue) = self.background_dials.lock() else {
+            return;
+        };
+        queue.targets.extend(targets);
+        if queue.running {
+            return;
+        }
+        let weak = Arc::downgrade(self);
+        match spawn_background("connection-pool-dialer", move || {
+            Self::background_dial_loop(weak)
+        }) {
+            Ok(_) => queue.running = true,
+            Err(_) => {
+                // 后台建连是尽力而为，无法创建线程时放弃本轮任务，get 仍会按需建连
+                queue.targets.clear();
+            }
+        }
+    }
+
+    /// 在后台新建一条空闲连接；已有后台建连在进行时跳过
+    fn dial_idle_in_background(self: &Arc<Self>) {
+        let busy = self
+            .background_dials
+            .lock()
+            .map_or(true, |queue| queue.running);
+        if !busy {
+            self.schedule_background_dials(vec![(None, None, None)]);
+        }
+    }
+
+    /// 后台建连线程主循环：逐个拨号并放入空闲队列，队列清空后退出
+    fn background_dial_loop(inner: Weak<PoolInner>) {
+        loop {
+            let Some(pool) = inner.upgrade() else {
+                return;
+            };
+            let target = match pool.background_dials.lock() {
+                Ok(mut queue) => match queue.targets.pop() {
+                    Some(target) => target,
+                    None => {
+                        queue.running = false;
+                        return;
+                    }
+                },
+                Err(_) => return,
+            };
+            if pool.is_closed() {
+                continue;
+            }
+
+            let (protocol, ip_version, role) = target;
+            match pool.create_connection(protocol, ip_version, role) {
+                Ok(conn) => pool.add_idle_connection(conn),
+                Err(NetConnPoolError::CreateRateLimited { .. }) => {
+                    // 超过新建速率：任务放回队列，等到下一个令牌再拨号
+                    if let Ok(mut queue) = pool.background_dials.lock() {
+                        queue.targets.push(target);
+                    }
+                    pool.wait_for_create_token();
+                }
+                Err(
+                    NetConnPoolError::DialingPaused
+                    | NetConnPoolError::TooManyConcurrentDials { .. }
+                    | NetConnPoolError::MaxConnectionsReached { .. }
+                    | NetConnPoolError::PoolClosed,
+                ) => {}
+                Err(_) => {
+                    if let Some(stats) = &pool.stats_collector {
+                        stats.increment_connection_errors();
+                    }
+                }
+            }
+        }
+    }
+
+    /// 对一批连接执行健康检查，结果顺序与输入一致
+    ///
+    /// `health_check_concurrency` 大于 1 时使用有界的工作线程并行检查，
+    /// reaper 线程自身也参与执行，确保即使工作线程创建失败也能完成本轮检查。
+    /// 未执行检查的连接（连接池已关闭、工作线程 panic）结果为 None。
+    fn run_health_checks(
+        &self,
+        conns: &[Arc<Connection>],
+    ) -> Vec<Option<std::result::Result<(), String>>> {
+        let workers = self.config.health_check_concurrency.min(conns.len());
+        if workers <= 1 {
+            return conns
+                .iter()
+                .map(|c| Some(self.run_health_check(c)))
+                .collect();
+        }
+
+        let next = AtomicUsize::new(0);
+        let worker = || {
+            let mut done = Vec::new();
+            loop {
+                let idx = next.fetch_add(1, Ordering::Relaxed);
+                if idx >= conns.len() || self.is_closed() {
+                    break;
+                }
+                done.push((idx, self.run_health_check(&conns[idx])));
+            }
+            done
+        };
+
+        let mut results: Vec<Option<std::result::Result<(), String>>> = vec![None; conns.len()];
+        thread::scope(|scope| {
+            let handles: Vec<_> = (1..workers)
+                .filter_map(|_| {
+                    thread::Builder::new()
+                        .name("connection-pool-health-checker".to_string())
+                        .spawn_scoped(scope, worker)
+                        .ok()
+                })
+                .collect();
+            for (idx, res) in worker() {
+                results[idx] = Some(res);
+            }
+            for handle in handles {
+                if let Ok(done) = handle.join() {
+                    for (idx, res) in done {
+                        results[idx] = Some(res);
+                    }
+                }
+            }
+        });
+        results
+    }
+
+    /// reaper 的运行间隔
+    ///
+    /// 未配置 reaper_interval 时取 health_check_interval 的 1/4（不超过1秒）：
+    /// 健康检查按每条连接的上次检查时间独立节流，reaper 周期只决定检查时刻的精度。
+    /// 若 reaper 周期等于 health_check_interval，稍晚于某轮创建/检查的连接要到下下轮才会被检查，
+    /// 实际检查间隔接近两倍 interval。
+    fn reaper_interval(&self) -> Duration {
+        const MAX_DEFAULT_REAPER_INTERVAL: Duration = Duration::from_secs(1);
+        match self.config.reaper_interval {
+            Some(interval) => interval,
+            None if self.config.health_check_interval.is_zero() => MAX_DEFAULT_REAPER_INTERVAL,
+            None => (self.health_check_interval() / 4)
+                .clamp(Duration::from_millis(1), MAX_DEFAULT_REAPER_INTERVAL),
+        }
+    }
+
+    fn has_health_checker(&self) -> bool {
+        self.config.health_checker_with_reason.is_some()
+            || self.config.health_checker.is_some()
+            || (self.config.test_while_idle && self.config.validation_query.is_some())
+    }
+
+    /// 执行单个连接的健康检查，未配置检查函数时视为健康
+    ///
+    /// 优先使用带原因的检查函数；旧的 bool 检查函数失败时使用固定原因。
+    /// 启用 test_while_idle 时还需通过 validation_query。
+    fn run_health_check(&self, conn: &Connection) -> std::result::Result<(), String> {
+        if let Some(checker) = &self.config.health_checker_with_reason {
+            checker(conn.connection_type())?;
+        } else if let Some(checker) = &self.config.health_checker {
+            if !checker(conn.connection_type()) {
+                return Err("health_checker returned false".to_string());
+            }
+        }
+        if self.config.test_while_idle {
+            self.run_validation(conn)?;
+        }
+        Ok(())
+    }
+
+    /// 执行 validation_query 验证往返，未配置时视为通过
+    fn run_validation(&self, conn: &Connection) -> std::result::Result<(), String> {
+        let Some((request, expected)) = &self.config.validation_query else {
+            return Ok(());
+        };
+        let timeout = if self.config.health_check_timeout.is_zero() {
+            Duration::from_secs(3)
+        } else {
+            self.config.health_check_timeout
+        };
+        validation::validate(conn.connection_type(), request, expected, timeout)
+    }
+
+    /// 记录一次健康检查结果（统计、失败回调、连接健康状态），返回是否通过
+    fn record_health_check(
+        &self,
+        conn: &Connection,
+        result: &std::result::Result<(), String>,
+    ) -> bool {
+        if let Some(stats) = &self.stats_collector {
+            stats.increment_health_check_attempts();
         }
+        if let Err(reason) = result {
+            if let Some(stats) = &self.stats_collector {
+                stats.increment_health_check_failures();
+                stats.increment_unhealthy_connections();
+                stats.record_health_check_failure_reason(reason);
+            }
+            if let Some(on_failure) = &self.config.on_health_check_failure {
+                on_failure(conn.connection_type(), reason);
+            }
+            self.emit(|| PoolEvent::HealthFailed {
+                connection_id: conn.id(),
+                reason: reason.clone(),
+            });
+            // 已被 get 独占（test_on_borrow）时不标记，由调用方直接丢弃
+            conn.mark_unhealthy_if_idle();
+            return false;
+        }
+        conn.update_health(true);
+        true
     }
 
     fn is_connection_valid_for_borrow(&self, conn: &Connection) -> bool {
-        if conn.is_closed() {
+        if conn.is_closed() || conn.is_oneshot() {
             return false;
         }
         if !conn.health_status() {
@@ -1101,6 +3830,9 @@ impl PoolInner {
         if conn.is_expired(self.config.max_lifetime) {
             return false;
         }
+        if conn.is_bytes_exhausted(self.config.max_bytes_per_conn) {
+            return false;
+        }
         if conn.is_idle_expired(self.config.idle_timeout) {
             return false;
         }
@@ -1147,6 +3879,7 @@ impl PoolInner {
             stats.increment_total_connections_reused();
         }
         stats.record_get_time(get_duration);
+        stats.record_get_time_by_source(get_duration, is_reused);
     }
 
     fn add_idle_connection(&self, conn: Arc<Connection>) {
@@ -1170,13 +3903,21 @@ impl PoolInner {
     /// 使用 CAS 操作原子地检查和增加计数器，避免竞态条件。
     /// 如果超过最大空闲连接数，会移除连接。
     fn try_push_idle(&self, conn: Arc<Connection>, idx: usize) {
-        let max_idle = self.config.max_idle_connections;
+        // 连接入队后再唤醒等待者，保证被唤醒的 get 能取到它
+        if self.push_idle(conn, idx) {
+            self.notify_waiter();
+        }
+    }
+
+    /// 放回空闲队列但不唤醒等待者，超过 max_idle_connections 时关闭连接并返回 false
+    fn push_idle(&self, conn: Arc<Connection>, idx: usize) -> bool {
+        let max_idle = self.max_idle_connections.load(Ordering::Relaxed);
         loop {
             let current = self.idle_counts[idx].load(Ordering::Relaxed);
             if current >= max_idle {
                 // 超过最大空闲连接数，直接移除
                 let _ = self.remove_connection(&conn);
-                break;
+                return false;
             }
             // 尝试原子地增加计数器
             match self.idle_counts[idx].compare_exchange_weak(
@@ -1192,7 +3933,8 @@ impl PoolInner {
                     if let Some(stats) = &self.stats_collector {
                         self.update_stats_on_idle_push(stats, &conn);
                     }
-                    break;
+                    self.check_availability();
+                    return true;
                 }
                 Err(_) => {
                     // CAS 失败，其他线程修改了计数器，重试
@@ -1204,12 +3946,436 @@ impl PoolInner {
 
     fn close_connection(&self, conn: &Arc<Connection>) {
         if let Some(closer) = &self.config.close_conn {
-            let _ = closer(conn.connection_type());
+            if closer(conn.connection_type()).is_err() {
+                if let Some(stats) = &self.stats_collector {
+                    stats.increment_close_callback_failures();
+                }
+            }
+        }
+        // 即使 close_conn 回调失败，也必须执行默认关闭，确保底层 socket 真正关闭
+        if conn.close().is_err() {
+            if let Some(stats) = &self.stats_collector {
+                stats.increment_close_failures();
+            }
         }
-        let _ = conn.close();
     }
 
     // remove_from_idle_if_present 已移除
     // 现在完全依赖 is_connection_valid_for_borrow 在 get() 时进行延迟清理
     // 这样可以避免队列顺序混乱和性能问题
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::TcpListener;
+
+    fn tcp_config() -> Config {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let mut config = crate::config::default_config();
+        config.min_connections = 1;
+        config.dialer = Some(Box::new(move |_| {
+            let _ = &listener;
+            Ok(ConnectionType::Tcp(TcpStream::connect(addr)?))
+        }));
+        config
+    }
+
+    #[test]
+    fn test_background_thread_spawn_failure_is_reported() {
+        for name in ["connection-pool-reaper", "connection-pool-prewarmer"] {
+            FAIL_SPAWN.with(|n| n.set(Some(name)));
+            match Pool::new(tcp_config()) {
+                Err(NetConnPoolError::ThreadSpawnFailed { thread, .. }) => {
+                    assert_eq!(thread, name)
+                }
+                other => panic!("期望 ThreadSpawnFailed，实际: {:?}", other.map(|_| ())),
+            }
+        }
+        FAIL_SPAWN.with(|n| n.set(None));
+        assert!(Pool::new(tcp_config()).is_ok());
+    }
+
+    #[test]
+    fn test_sticky_records_of_dropped_pools_are_pruned() {
+        for _ in 0..3 {
+            let pool = Pool::new(tcp_config()).unwrap();
+            drop(pool.get_sticky(Duration::from_secs(1)).unwrap());
+        }
+        thread::sleep(Duration::from_millis(50));
+
+        let pool = Pool::new(tcp_config()).unwrap();
+        let id = pool.get_sticky(Duration::from_secs(1)).unwrap().id();
+        // 已释放连接池的记录在本次 get_sticky 时被清理，只剩当前连接池
+        STICKY_CONNECTIONS.with(|last| {
+            let last = last.borrow();
+            assert_eq!(last.len(), 1);
+            assert_eq!(last.values().next().map(|(_, id)| *id), Some(id));
+        });
+    }
+
+    #[test]
+    fn test_lazy_init_starts_background_threads_on_first_get() {
+        let mut config = tcp_config();
+        config.lazy_init = true;
+        let pool = Pool::new(config).unwrap();
+        thread::sleep(Duration::from_millis(50));
+        // 未 get 前没有后台线程，也没有预热
+        assert!(!pool.inner.background_started.is_completed());
+        assert!(pool.inner.reaper_handle.lock().unwrap().is_none());
+        assert_eq!(pool.inner.connection_count.load(Ordering::SeqCst), 0);
+
+        drop(pool.get().unwrap());
+        assert!(pool.inner.background_started.is_completed());
+        assert!(pool.inner.reaper_handle.lock().unwrap().is_some());
+
+        // 懒启动失败时 get 返回 ThreadSpawnFailed，连接池随之关闭
+        let mut config = tcp_config();
+        config.lazy_init = true;
+        FAIL_SPAWN.with(|n| n.set(Some("connection-pool-reaper")));
+        let pool = Pool::new(config).unwrap();
+        let err = pool.get().err();
+        FAIL_SPAWN.with(|n| n.set(None));
+        assert!(matches!(
+            err,
+            Some(NetConnPoolError::ThreadSpawnFailed { .. })
+        ));
+        assert!(matches!(pool.get(), Err(NetConnPoolError::PoolClosed)));
+    }
+
+    #[test]
+    fn test_wait_poll_interval_recovers_from_lost_notify() {
+        let mut config = tcp_config();
+        config.min_connections = 0;
+        config.max_connections = 1;
+        config.wait_poll_interval = Some(Duration::from_millis(20));
+        let pool = Pool::new(config).unwrap();
+        let held = pool.get().unwrap();
+
+        let waiter = {
+            let pool = pool.clone();
+            thread::spawn(move || {
+                pool.get_with_timeout(Duration::from_secs(5))
+                    .map(|c| c.id())
+            })
+        };
+        while pool.inner.waiters.load(Ordering::Acquire) == 0 {
+            thread::sleep(Duration::from_millis(1));
+        }
+
+        // 绕过 return_connection 直接放回空闲队列，模拟丢失的归还通知
+        let conn = held.conn.clone();
+        std::mem::forget(held);
+        assert!(conn.try_mark_idle());
+        pool.inner.active_count.fetch_sub(1, Ordering::Relaxed);
+        let idx = PoolInner::get_bucket_index(conn.protocol(), conn.ip_version()).unwrap();
+        pool.inner.idle_counts[idx].fetch_add(1, Ordering::Relaxed);
+        pool.inner.idle_connections[idx].push(conn.clone());
+        let pushed = Instant::now();
+
+        assert_eq!(waiter.join().unwrap().unwrap(), conn.id());
+        assert!(pushed.elapsed() < Duration::from_millis(500));
+    }
+
+    #[test]
+    fn test_spurious_wakeups_do_not_spin_get_loop() {
+        let mut config = tcp_config();
+        config.min_connections = 0;
+        config.max_connections = 1;
+        let pool = Pool::new(config).unwrap();
+        let held = pool.get().unwrap();
+
+        // 不改变池状态的 notify_all 风暴，等价于频繁的虚假唤醒
+        let stop = Arc::new(AtomicBool::new(false));
+        let spammer = {
+            let inner = pool.inner.clone();
+            let stop = stop.clone();
+            thread::spawn(move || {
+                let mut notified = 0usize;
+                while !stop.load(Ordering::Relaxed) {
+                    inner.wait_cv.notify_all();
+                    notified += 1;
+                    thread::yield_now();
+                }
+                notified
+            })
+        };
+
+        POOL_WAIT_RETURNS.with(|n| n.set(0));
+        let result = pool.get_with_timeout(Duration::from_millis(200));
+        let wait_returns = POOL_WAIT_RETURNS.with(|n| n.get());
+        stop.store(true, Ordering::Relaxed);
+        assert!(spammer.join().unwrap() > 0);
+
+        assert!(matches!(
+            result,
+            Err(NetConnPoolError::PoolExhausted { .. })
+        ));
+        // 只在超时时返回一次，而不是每次唤醒都重试一轮
+        assert!(wait_returns <= 2, "wait 返回了 {} 次", wait_returns);
+        drop(held);
+    }
+
+    #[test]
+    fn test_duplicate_return_is_ignored() {
+        let mut config = tcp_config();
+        config.min_connections = 0;
+        config.max_connections = 1;
+        let pool = Pool::new(config).unwrap();
+
+        let first = pool.get().unwrap();
+        let conn = first.conn.clone();
+        let stale_epoch = first.epoch;
+        drop(first);
+
+        // 同一次借用再归还一次：不能重复扣减 active，也不能把连接二次放入空闲队列
+        pool.inner.return_connection(conn.clone(), stale_epoch);
+        assert_eq!(pool.inner.active_count.load(Ordering::Relaxed), 0);
+        assert_eq!(pool.inner.idle_connections[0].len(), 1);
+
+        // 连接被再次借出后，旧句柄的归还同样被忽略
+        let second = pool.get().unwrap();
+        assert_eq!(second.id(), conn.id());
+        pool.inner.return_connection(conn.clone(), stale_epoch);
+        assert!(conn.is_in_use());
+        assert_eq!(pool.inner.active_count.load(Ordering::Relaxed), 1);
+        assert!(pool.inner.idle_connections[0].is_empty());
+
+        drop(second);
+        assert_eq!(pool.inner.active_count.load(Ordering::Relaxed), 0);
+        assert_eq!(pool.inner.idle_connections[0].len(), 1);
+    }
+
+    #[test]
+    fn test_close_waits_for_reaper_exit() {
+        let mut config = tcp_config();
+        config.min_connections = 0;
+        config.health_check_interval = Duration::from_millis(20);
+        config.health_check_timeout = Duration::from_millis(10);
+        let checking = Arc::new(AtomicBool::new(false));
+        let flag = checking.clone();
+        config.health_checker = Some(Box::new(move |_| {
+            // 让 reaper 在一轮清理中停留较久，close 需要等它结束
+            flag.store(true, Ordering::Release);
+            thread::sleep(Duration::from_millis(200));
+            true
+        }));
+        let pool = Pool::new(config).unwrap();
+        drop(pool.get().unwrap());
+        while !checking.load(Ordering::Acquire) {
+            thread::sleep(Duration::from_millis(1));
+        }
+
+        // reaper 是唯一持有 Weak 的后台线程，线程结束后弱引用计数归零
+        assert_eq!(Arc::weak_count(&pool.inner), 1);
+        let start = Instant::now();
+        pool.close().unwrap();
+        assert_eq!(Arc::weak_count(&pool.inner), 0);
+        assert!(start.elapsed() < Duration::from_secs(2));
+        assert!(pool.inner.reaper_handle.lock().unwrap().is_none());
+    }
+
+    #[test]
+    fn test_unhealthy_connection_is_never_borrowed() {
+        let mut config = tcp_config();
+        config.min_connections = 0;
+        config.max_connections = 8;
+        let pool = Pool::new(config).unwrap();
+        let stop = Arc::new(AtomicBool::new(false));
+
+        // 模拟 reaper：不断对连接执行失败的健康检查并移除被判定不健康的连接
+        let marker = {
+            let inner = pool.inner.clone();
+            let stop = stop.clone();
+            thread::spawn(move || {
+                let mut marked = 0;
+                while !stop.load(Ordering::Relaxed) {
+                    let conns: Vec<_> = inner
+                        .all_connections
+                        .read()
+                        .unwrap()
+                        .values()
+                        .cloned()
+                        .collect();
+                    for conn in conns {
+                        inner.record_health_check(&conn, &Err("模拟检查失败".to_string()));
+                        if !conn.health_status() {
+                            let _ = inner.remove_connection(&conn);
+                            marked += 1;
+                        }
+                    }
+                }
+                marked
+            })
+        };
+
+        let getters: Vec<_> = (0..4)
+            .map(|_| {
+                let pool = pool.clone();
+                thread::spawn(move || {
+                    for _ in 0..500 {
+                        let conn = pool.get_with_timeout(Duration::from_secs(1)).unwrap();
+                        // 借出期间连接始终健康：不健康的不会被借出，借出后也不会被标记
+                        assert!(conn.health_status(), "借出了不健康的连接 {}", conn.id());
+                        thread::yield_now();
+                        assert!(
+                            conn.health_status(),
+                            "借出中的连接 {} 被标记不健康",
+                            conn.id()
+                        );
+                    }
+                })
+            })
+            .collect();
+        for getter in getters {
+            getter.join().unwrap();
+        }
+        stop.store(true, Ordering::Relaxed);
+        assert!(marker.join().unwrap() > 0);
+    }
+
+    #[test]
+    fn test_verify_on_close_reports_miscounted_active() {
+        let mut config = tcp_config();
+        config.verify_on_close = true;
+        config.shutdown_timeout = Some(Duration::ZERO);
+        let pool = Pool::new(config).unwrap();
+        // 模拟计数漏扣
+        pool.inner.active_count.fetch_add(1, Ordering::Relaxed);
+        match pool.close() {
+            Err(NetConnPoolError::CloseVerificationFailed { reason }) => {
+                assert!(reason.contains("active_count"), "{}", reason)
+            }
+            other => panic!("期望 CloseVerificationFailed，实际: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_dropping_last_pool_handle_closes_pool() {
+        let mut config = tcp_config();
+        config.shutdown_timeout = Some(Duration::ZERO);
+        let pool = Pool::new(config).unwrap();
+        drop(pool.get().unwrap());
+        let idle: Vec<Arc<Connection>> = pool
+            .inner
+            .all_connections
+            .read()
+            .unwrap()
+            .values()
+            .cloned()
+            .collect();
+        assert!(!idle.is_empty());
+        let borrowed = pool.get().unwrap();
+        let weak = Arc::downgrade(&pool.inner);
+
+        // 仍有克隆存活时不关闭
+        let clone = pool.clone();
+        drop(pool);
+        assert!(!clone.is_closed());
+
+        drop(clone);
+        // 后台线程已退出且不再持有 PoolInner，空闲连接已关闭
+        assert!(weak.upgrade().is_none());
+        assert!(idle
+            .iter()
+            .all(|c| c.is_closed() || c.id() == borrowed.id()));
+
+        // 借出的连接不受影响，归还时也不会 panic
+        assert!(!borrowed.is_closed());
+        drop(borrowed);
+    }
+
+    #[test]
+    fn test_skipped_health_checks_leave_connections_unchanged() {
+        let mut config = tcp_config();
+        config.min_connections = 0;
+        config.health_check_concurrency = 4;
+        config.health_checker = Some(Box::new(|_| true));
+        let pool = Pool::new(config).unwrap();
+        let conns: Vec<Arc<Connection>> = {
+            let held: Vec<_> = (0..3).map(|_| pool.get().unwrap()).collect();
+            let all = pool.inner.all_connections.read().unwrap();
+            held.iter().map(|c| all[&c.id()].clone()).collect()
+        };
+
+        // 连接池关闭后工作线程不再执行检查：结果为 None，而不是被当作失败
+        pool.inner.closed.store(true, Ordering::Release);
+        let results = pool.inner.run_health_checks(&conns);
+        pool.inner.closed.store(false, Ordering::Release);
+        assert!(results.iter().all(Option::is_none));
+        assert!(conns.iter().all(|c| c.health_status()));
+        assert_eq!(pool.stats().health_check_failures, 0);
+    }
+
+    #[test]
+    fn test_connection_under_health_check_is_not_borrowed() {
+        let mut config = tcp_config();
+        config.min_connections = 0;
+        let pool = Pool::new(config).unwrap();
+        let checked = pool.get().unwrap().id();
+        let conn = pool.inner.all_connections.read().unwrap()[&checked].clone();
+        assert!(conn.try_begin_check());
+
+        // 检查期间 get 不借出该连接，也不会把它当作失效连接关闭
+        let other = pool.get().unwrap();
+        assert_ne!(other.id(), checked);
+        assert!(!conn.is_in_use() && !conn.is_closed());
+        drop(other);
+
+        // 检查结束后连接照常可借
+        conn.end_check();
+        let borrowed: Vec<_> = (0..2).map(|_| pool.get().unwrap()).collect();
+        assert!(borrowed.iter().any(|c| c.id() == checked));
+    }
+
+    #[test]
+    fn test_prewarm_stops_quickly_after_pool_dropped() {
+        use std::sync::atomic::AtomicUsize;
+
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let dials = Arc::new(AtomicUsize::new(0));
+        let created = Arc::new(AtomicUsize::new(0));
+        let gate = Arc::new((Mutex::new(false), Condvar::new()));
+
+        let mut config = crate::config::default_config();
+        config.min_connections = 5;
+        config.shutdown_timeout = Some(Duration::ZERO);
+        let (d, g) = (dials.clone(), gate.clone());
+        config.dialer = Some(Box::new(move |_| {
+            let _ = &listener;
+            d.fetch_add(1, Ordering::SeqCst);
+            // 第一次建连阻塞到 Pool 被 drop 之后
+            let (lock, cvar) = &*g;
+            let _open = cvar
+                .wait_timeout_while(lock.lock().unwrap(), Duration::from_secs(5), |open| !*open)
+                .unwrap();
+            Ok(ConnectionType::Tcp(TcpStream::connect(addr)?))
+        }));
+        let c = created.clone();
+        config.on_created = Some(Box::new(move |_| {
+            c.fetch_add(1, Ordering::SeqCst);
+            Ok(())
+        }));
+
+        let pool = Pool::new(config).unwrap();
+        let weak = Arc::downgrade(&pool.inner);
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while dials.load(Ordering::SeqCst) == 0 && Instant::now() < deadline {
+            thread::sleep(Duration::from_millis(1));
+        }
+        drop(pool);
+        *gate.0.lock().unwrap() = true;
+        gate.1.notify_all();
+
+        // 预热线程完成当前建连后立即退出并释放 PoolInner
+        while weak.upgrade().is_some() && Instant::now() < deadline {
+            thread::sleep(Duration::from_millis(5));
+        }
+        assert!(weak.upgrade().is_none());
+        thread::sleep(Duration::from_millis(50));
+        assert_eq!(dials.load(Ordering::SeqCst), 1);
+        assert_eq!(created.load(Ordering::SeqCst), 0);
+    }
+}
@@ -5,8 +5,11 @@
 //!
 //! 提供自动归还的连接包装器，实现 RAII 机制。
 
-use crate::connection::Connection;
 use super::PoolInner;
+use crate::config::ConnectionType;
+use crate::connection::Connection;
+use std::io::{self, Read, Write};
+use std::net::TcpStream;
 use std::ops::Deref;
 use std::sync::{Arc, Weak};
 
@@ -16,12 +19,21 @@ use std::sync::{Arc, Weak};
 pub struct PooledConnection {
     pub(super) conn: Arc<Connection>,
     pub(super) pool: Weak<PoolInner>,
+    /// epoch 借出时领取的代际令牌，归还时用于识别重复或过期的归还
+    pub(super) epoch: u64,
 }
 
 impl PooledConnection {
     /// 创建新的 PooledConnection
     pub(crate) fn new(conn: Arc<Connection>, pool: Weak<PoolInner>) -> Self {
-        Self { conn, pool }
+        let epoch = conn.begin_borrow();
+        Self { conn, pool, epoch }
+    }
+
+    /// 与连接池解除关联，返回连接与借用令牌，之后 Drop 不再归还（交由批量归还处理）
+    pub(super) fn detach(&mut self) -> (Arc<Connection>, u64) {
+        self.pool = Weak::new();
+        (self.conn.clone(), self.epoch)
     }
 }
 
@@ -32,10 +44,146 @@ impl Deref for PooledConnection {
     }
 }
 
+impl PooledConnection {
+    /// 获取用于读写的流（TCP/TLS），UDP 连接返回 Unsupported 错误
+    fn stream(&self) -> io::Result<StreamRef<'_>> {
+        match self.conn.connection_type() {
+            ConnectionType::Tcp(stream) => Ok(StreamRef::Tcp(stream)),
+            #[cfg(feature = "tls")]
+            ConnectionType::Tls(stream) => Ok(StreamRef::Tls(stream)),
+            ConnectionType::Udp(_) => Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                "仅 TCP 连接支持 Read/Write，UDP 请使用 udp_conn()",
+            )),
+        }
+    }
+}
+
+/// 可读写流的共享引用（`&TcpStream` / `&TlsStream` 均通过共享引用实现了 Read/Write）
+#[derive(Clone, Copy)]
+enum StreamRef<'a> {
+    Tcp(&'a TcpStream),
+    #[cfg(feature = "tls")]
+    Tls(&'a crate::tls::TlsStream),
+}
+
+impl PooledConnection {
+    /// 将 TCP 连接拆分为读半和写半，可分别交给不同线程（如 `thread::scope`）并发读写
+    ///
+    /// 两个半部以可变借用绑定在 `PooledConnection` 上，不能比连接活得更久，
+    /// 因此连接归还后不可能再通过它们访问底层 socket；需要读写分离时应优先使用它，
+    /// 而不是 `tcp_conn().try_clone()`（克隆句柄脱离连接池管理，可能在归还后被继续使用）。
+    /// TLS 连接的读写共用同一个 rustls 会话，无法真正并发，与 UDP 连接一样返回 Unsupported 错误。
+    ///
+    /// ```compile_fail
+    /// # fn demo(pool: &netconnpool::Pool) -> std::io::Result<()> {
+    /// let mut conn = pool.get().unwrap();
+    /// let (reader, _writer) = conn.split()?;
+    /// drop(conn); // 归还连接：读写半仍被借用，编译失败
+    /// let _ = reader;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn split(&mut self) -> io::Result<(ReadHalf<'_>, WriteHalf<'_>)> {
+        let stream = self.stream()?;
+        #[cfg(feature = "tls")]
+        if let StreamRef::Tls(_) = stream {
+            return Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                "TLS 连接的读写共用同一会话，不支持 split",
+            ));
+        }
+        let conn = &*self.conn;
+        Ok((ReadHalf { stream, conn }, WriteHalf { stream, conn }))
+    }
+}
+
+/// ReadHalf `PooledConnection::split` 拆分出的读半
+pub struct ReadHalf<'a> {
+    stream: StreamRef<'a>,
+    conn: &'a Connection,
+}
+
+/// WriteHalf `PooledConnection::split` 拆分出的写半
+pub struct WriteHalf<'a> {
+    stream: StreamRef<'a>,
+    conn: &'a Connection,
+}
+
+impl Read for ReadHalf<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let n = self.stream.read(buf)?;
+        self.conn.add_bytes_transferred(n);
+        Ok(n)
+    }
+}
+
+impl Write for WriteHalf<'_> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let n = self.stream.write(buf)?;
+        self.conn.add_bytes_transferred(n);
+        Ok(n)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.stream.flush()
+    }
+}
+
+impl Read for StreamRef<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        match self {
+            StreamRef::Tcp(stream) => stream.read(buf),
+            #[cfg(feature = "tls")]
+            StreamRef::Tls(stream) => stream.read(buf),
+        }
+    }
+}
+
+impl Write for StreamRef<'_> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match self {
+            StreamRef::Tcp(stream) => stream.write(buf),
+            #[cfg(feature = "tls")]
+            StreamRef::Tls(stream) => stream.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match self {
+            StreamRef::Tcp(stream) => stream.flush(),
+            #[cfg(feature = "tls")]
+            StreamRef::Tls(stream) => stream.flush(),
+        }
+    }
+}
+
+/// 直接转发到底层 TcpStream / TlsStream（二者均通过共享引用实现了 Read/Write，无需内部可变性），
+/// 并把实际读写的字节数累计到连接上（用于 max_bytes_per_conn）
+impl Read for PooledConnection {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let n = self.stream()?.read(buf)?;
+        self.conn.add_bytes_transferred(n);
+        Ok(n)
+    }
+}
+
+impl Write for PooledConnection {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let n = self.stream()?.write(buf)?;
+        self.conn.add_bytes_transferred(n);
+        Ok(n)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.stream()?.flush()
+    }
+}
+
 impl Drop for PooledConnection {
     fn drop(&mut self) {
         if let Some(pool) = self.pool.upgrade() {
-            pool.return_connection(self.conn.clone());
+            pool.return_connection(self.conn.clone(), self.epoch);
         }
     }
 }
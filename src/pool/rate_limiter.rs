@@ -0,0 +1,76 @@
+// Copyright (c) 2025, vistone
+// All rights reserved.
+
+//! 新建连接速率限制模块
+//!
+//! 使用令牌桶限制 dialer 的调用频率，防止瞬时建连风暴。
+
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// TokenBucket 令牌桶
+/// 每秒补充 `rate` 个令牌，桶容量同为 `rate`（允许最多 1 秒的突发）
+#[derive(Debug)]
+pub(crate) struct TokenBucket {
+    rate: f64,
+    state: Mutex<BucketState>,
+}
+
+#[derive(Debug)]
+struct BucketState {
+    tokens: f64,
+    last_refill: Instant,
+}
+
+impl TokenBucket {
+    /// 创建每秒 `rate` 个令牌的令牌桶，初始为满桶
+    pub(crate) fn new(rate: usize) -> Self {
+        let rate = rate as f64;
+        Self {
+            rate,
+            state: Mutex::new(BucketState {
+                tokens: rate,
+                last_refill: Instant::now(),
+            }),
+        }
+    }
+
+    /// 尝试取出一个令牌
+    ///
+    /// 成功返回 `Ok(())`；令牌不足时返回距离下一个令牌可用的等待时间。
+    pub(crate) fn try_acquire(&self) -> Result<(), Duration> {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        self.refill(&mut state);
+
+        if state.tokens >= 1.0 {
+            state.tokens -= 1.0;
+            Ok(())
+        } else {
+            Err(self.wait_for_token(&state))
+        }
+    }
+
+    /// 距离下一个令牌可用的等待时间，当前已有令牌时为 0
+    pub(crate) fn next_token_in(&self) -> Duration {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        self.refill(&mut state);
+        self.wait_for_token(&state)
+    }
+
+    /// 退还一个令牌（取出令牌后未调用 dialer 时使用），不超过桶容量
+    pub(crate) fn refund(&self) {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        state.tokens = (state.tokens + 1.0).min(self.rate);
+    }
+
+    fn refill(&self, state: &mut BucketState) {
+        let now = Instant::now();
+        let elapsed = now.duration_since(state.last_refill).as_secs_f64();
+        state.tokens = (state.tokens + elapsed * self.rate).min(self.rate);
+        state.last_refill = now;
+    }
+
+    fn wait_for_token(&self, state: &BucketState) -> Duration {
+        Duration::from_secs_f64((1.0 - state.tokens).max(0.0) / self.rate)
+    }
+}
@@ -0,0 +1,84 @@
+// Copyright (c) 2025, vistone
+// All rights reserved.
+
+//! 验证查询模块
+//!
+//! 对连接执行一次"验证往返"：发送固定请求、读取并比对期望响应，
+//! 用于 test_on_borrow / test_while_idle 判定连接是否存活。
+
+use crate::config::ConnectionType;
+use std::io::{self, Read, Write};
+use std::net::{TcpStream, UdpSocket};
+use std::time::Duration;
+
+/// 在连接上执行验证往返，失败时返回原因
+///
+/// 执行期间临时把读写超时设为 `timeout`，结束后恢复原超时设置。
+pub(crate) fn validate(
+    conn: &ConnectionType,
+    request: &[u8],
+    expected: &[u8],
+    timeout: Duration,
+) -> std::result::Result<(), String> {
+    let matched = match conn {
+        ConnectionType::Tcp(stream) => {
+            with_timeouts(stream, timeout, || exchange(stream, request, expected))
+        }
+        ConnectionType::Udp(socket) => validate_udp(socket, request, expected, timeout),
+        #[cfg(feature = "tls")]
+        ConnectionType::Tls(tls) => {
+            // 克隆出的句柄与 TLS 底层共享同一 socket，超时设置对两者同时生效
+            tls.with_tcp(TcpStream::try_clone).and_then(|stream| {
+                with_timeouts(&stream, timeout, || exchange(tls, request, expected))
+            })
+        }
+    };
+    match matched {
+        Ok(true) => Ok(()),
+        Ok(false) => Err("validation_query 响应不匹配".to_string()),
+        Err(e) => Err(format!("validation_query 往返失败: {}", e)),
+    }
+}
+
+/// 发送请求并读取与期望响应等长的数据进行比对
+fn exchange(mut stream: impl Read + Write, request: &[u8], expected: &[u8]) -> io::Result<bool> {
+    stream.write_all(request)?;
+    stream.flush()?;
+    let mut buf = vec![0u8; expected.len()];
+    stream.read_exact(&mut buf)?;
+    Ok(buf == expected)
+}
+
+/// 临时设置 TCP 读写超时执行 `f`，结束后恢复原设置
+fn with_timeouts<R>(
+    stream: &TcpStream,
+    timeout: Duration,
+    f: impl FnOnce() -> io::Result<R>,
+) -> io::Result<R> {
+    let prev_read = stream.read_timeout()?;
+    let prev_write = stream.write_timeout()?;
+    stream.set_read_timeout(Some(timeout))?;
+    stream.set_write_timeout(Some(timeout))?;
+    let result = f();
+    stream.set_read_timeout(prev_read)?;
+    stream.set_write_timeout(prev_write)?;
+    result
+}
+
+fn validate_udp(
+    socket: &UdpSocket,
+    request: &[u8],
+    expected: &[u8],
+    timeout: Duration,
+) -> io::Result<bool> {
+    let prev_read = socket.read_timeout()?;
+    socket.set_read_timeout(Some(timeout))?;
+    let result = socket.send(request).and_then(|_| {
+        // UDP 按数据报读取，缓冲区需容纳完整响应
+        let mut buf = vec![0u8; expected.len().max(1) + 1];
+        let n = socket.recv(&mut buf)?;
+        Ok(&buf[..n] == expected)
+    });
+    socket.set_read_timeout(prev_read)?;
+    result
+}
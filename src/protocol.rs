@@ -30,6 +30,8 @@ pub fn detect_protocol(conn: &ConnectionType) -> Protocol {
     match conn {
         ConnectionType::Tcp(_) => Protocol::TCP,
         ConnectionType::Udp(_) => Protocol::UDP,
+        #[cfg(feature = "tls")]
+        ConnectionType::Tls(_) => Protocol::TCP,
     }
 }
 
@@ -0,0 +1,187 @@
+// Copyright (c) 2025, vistone
+// All rights reserved.
+
+//! r2d2 适配
+//!
+//! `R2d2Manager` 实现 `r2d2::ManageConnection`，让已有的 r2d2 代码复用本 crate 的
+//! Dialer 与健康检查语义。两套生命周期的映射：
+//!
+//! - 建连：`connect` 调用 `dialer`（或 `labeled_dialer`），成功后执行 `on_created`；
+//! - 借出校验：`is_valid`（r2d2 `test_on_check_out` 时调用）执行 `on_acquire`、
+//!   `health_checker_with_reason` / `health_checker` 与 `validation_query`；
+//! - 归还：`has_broken` 非阻塞探测 TCP/TLS 对端是否已关闭，已关闭的连接由 r2d2 丢弃；
+//! - 容量、超时、空闲回收与最长生命周期由 `r2d2::Builder`（`max_size`、`connection_timeout`、
+//!   `idle_timeout`、`max_lifetime`）管理，`Config` 中对应的字段以及 reaper、统计、事件等
+//!   连接池自身的机制不生效。
+//!
+//! 连接以 `ConnectionType` 交给 r2d2 管理，被 r2d2 丢弃时随 Drop 关闭。
+
+use crate::config::{Config, ConnectionType};
+use crate::errors::{NetConnPoolError, Result};
+use crate::mode::PoolMode;
+use crate::pool::peer_closed;
+use std::fmt;
+use std::time::Duration;
+
+/// R2d2Manager 以 `Config` 的 Dialer 与健康检查实现 `r2d2::ManageConnection`
+pub struct R2d2Manager {
+    config: Config,
+}
+
+impl R2d2Manager {
+    /// 由客户端模式配置创建，配置中必须设置 `dialer` 或 `labeled_dialer`
+    pub fn new(config: Config) -> Result<Self> {
+        if config.mode != PoolMode::Client {
+            return Err(NetConnPoolError::InvalidConfig {
+                reason: "R2d2Manager 仅支持客户端模式".to_string(),
+            });
+        }
+        if config.dialer.is_none() && config.labeled_dialer.is_none() {
+            return Err(NetConnPoolError::InvalidConfig {
+                reason: "R2d2Manager 需要 Dialer".to_string(),
+            });
+        }
+        Ok(Self { config })
+    }
+}
+
+impl fmt::Debug for R2d2Manager {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("R2d2Manager")
+            .field("config", &self.config)
+            .finish()
+    }
+}
+
+impl ::r2d2::ManageConnection for R2d2Manager {
+    type Connection = ConnectionType;
+    type Error = NetConnPoolError;
+
+    fn connect(&self) -> Result<ConnectionType> {
+        let dialed = match (&self.config.labeled_dialer, &self.config.dialer) {
+            (Some(dialer), _) => dialer(None).map(|(conn, _label)| conn),
+            (None, Some(dialer)) => dialer(None),
+            (None, None) => unreachable!("R2d2Manager::new 已校验 Dialer"),
+        };
+        let conn = dialed.map_err(NetConnPoolError::dial_failed)?;
+        if let Some(on_created) = &self.config.on_created {
+            on_created(&conn)
+                .map_err(|e| NetConnPoolError::IoError(std::io::Error::other(e.to_string())))?;
+        }
+        Ok(conn)
+    }
+
+    fn is_valid(&self, conn: &mut ConnectionType) -> Result<()> {
+        let invalid = |reason: String| NetConnPoolError::HealthCheckFailed { reason };
+        if let Some(on_acquire) = &self.config.on_acquire {
+            on_acquire(conn).map_err(|e| invalid(format!("on_acquire 失败: {}", e)))?;
+        }
+        if let Some(checker) = &self.config.health_checker_with_reason {
+            checker(conn).map_err(invalid)?;
+        } else if let Some(checker) = &self.config.health_checker {
+            if !checker(conn) {
+                return Err(invalid("health_checker returned false".to_string()));
+            }
+        }
+        if let Some((request, expected)) = &self.config.validation_query {
+            let timeout = if self.config.health_check_timeout.is_zero() {
+                Duration::from_secs(3)
+            } else {
+                self.config.health_check_timeout
+            };
+            crate::pool::validation::validate(conn, request, expected, timeout).map_err(invalid)?;
+        }
+        Ok(())
+    }
+
+    fn has_broken(&self, conn: &mut ConnectionType) -> bool {
+        peer_closed(conn)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::default_config;
+    use ::r2d2::ManageConnection;
+    use std::net::{TcpListener, TcpStream};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    fn tcp_config(listener: &TcpListener) -> Config {
+        let addr = listener.local_addr().unwrap();
+        let mut config = default_config();
+        config.dialer = Some(Box::new(move |_| {
+            Ok(ConnectionType::Tcp(TcpStream::connect(addr)?))
+        }));
+        config
+    }
+
+    #[test]
+    fn test_new_requires_client_mode_and_dialer() {
+        assert!(R2d2Manager::new(default_config()).is_err());
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let mut config = tcp_config(&listener);
+        config.mode = PoolMode::Server;
+        assert!(R2d2Manager::new(config).is_err());
+    }
+
+    #[test]
+    fn test_connect_runs_on_created() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let created = Arc::new(AtomicUsize::new(0));
+        let mut config = tcp_config(&listener);
+        config.on_created = Some(Box::new({
+            let created = created.clone();
+            move |_| {
+                if created.fetch_add(1, Ordering::SeqCst) == 0 {
+                    Ok(())
+                } else {
+                    Err("rejected".into())
+                }
+            }
+        }));
+        let manager = R2d2Manager::new(config).unwrap();
+
+        assert!(matches!(manager.connect(), Ok(ConnectionType::Tcp(_))));
+        // on_created 失败时建连失败
+        assert!(matches!(
+            manager.connect(),
+            Err(NetConnPoolError::IoError(_))
+        ));
+        assert_eq!(created.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn test_is_valid_runs_health_checker() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let healthy = Arc::new(AtomicUsize::new(1));
+        let mut config = tcp_config(&listener);
+        config.health_checker = Some(Box::new({
+            let healthy = healthy.clone();
+            move |_| healthy.load(Ordering::SeqCst) == 1
+        }));
+        let manager = R2d2Manager::new(config).unwrap();
+        let mut conn = manager.connect().unwrap();
+
+        assert!(manager.is_valid(&mut conn).is_ok());
+        healthy.store(0, Ordering::SeqCst);
+        assert!(matches!(
+            manager.is_valid(&mut conn),
+            Err(NetConnPoolError::HealthCheckFailed { .. })
+        ));
+    }
+
+    #[test]
+    fn test_has_broken_detects_peer_close() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let manager = R2d2Manager::new(tcp_config(&listener)).unwrap();
+        let mut conn = manager.connect().unwrap();
+        let (peer, _) = listener.accept().unwrap();
+        assert!(!manager.has_broken(&mut conn));
+
+        drop(peer);
+        std::thread::sleep(Duration::from_millis(50));
+        assert!(manager.has_broken(&mut conn));
+    }
+}
@@ -0,0 +1,117 @@
+// Copyright (c) 2025, vistone
+// All rights reserved.
+
+//! 命名连接池注册表
+//!
+//! 大型应用往往同时持有多个连接池（数据库、缓存、不同上游），`PoolRegistry`
+//! 按名字集中管理它们：首次使用时创建、按名取用、统一关闭与汇总统计。
+
+use crate::config::Config;
+use crate::errors::Result;
+use crate::pool::Pool;
+use crate::stats::Stats;
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+use std::sync::RwLock;
+
+/// PoolRegistry 按名字管理多个连接池
+#[derive(Debug, Default)]
+pub struct PoolRegistry {
+    pools: RwLock<HashMap<String, Pool>>,
+}
+
+impl PoolRegistry {
+    /// 创建空的注册表
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 获取名为 `name` 的连接池，不存在时用 `config_fn` 生成的配置创建并注册
+    ///
+    /// `Pool::new` 可能预热建连、启动后台线程，因此在注册表锁之外创建，不阻塞其他查询；
+    /// 并发创建同名池时只注册先完成的一个，其余新建的池被关闭并返回已注册的池。
+    /// 创建失败时不注册。
+    pub fn get_or_create<F>(&self, name: &str, config_fn: F) -> Result<Pool>
+    where
+        F: FnOnce() -> Config,
+    {
+        if let Some(pool) = self.get(name) {
+            return Ok(pool);
+        }
+        let pool = Pool::new(config_fn())?;
+        let mut pools = self.pools.write().unwrap_or_else(|e| e.into_inner());
+        match pools.entry(name.to_string()) {
+            Entry::Occupied(entry) => {
+                let existing = entry.get().clone();
+                drop(pools);
+                let _ = pool.close();
+                Ok(existing)
+            }
+            Entry::Vacant(entry) => Ok(entry.insert(pool).clone()),
+        }
+    }
+
+    /// 按名字获取已注册的连接池
+    pub fn get(&self, name: &str) -> Option<Pool> {
+        self.pools
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(name)
+            .cloned()
+    }
+
+    /// 从注册表移除连接池（不会关闭它）
+    pub fn remove(&self, name: &str) -> Option<Pool> {
+        self.pools
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+            .remove(name)
+    }
+
+    /// 已注册的连接池名字（按字典序）
+    pub fn names(&self) -> Vec<String> {
+        let mut names: Vec<String> = self
+            .pools
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .keys()
+            .cloned()
+            .collect();
+        names.sort();
+        names
+    }
+
+    /// 关闭并注销所有连接池
+    ///
+    /// 每个池都会尝试关闭，返回遇到的第一个错误；之后同名的 `get_or_create` 会创建新池。
+    pub fn close_all(&self) -> Result<()> {
+        let pools: Vec<Pool> = self
+            .pools
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+            .drain()
+            .map(|(_, pool)| pool)
+            .collect();
+        let mut first_err = None;
+        for pool in pools {
+            if let Err(e) = pool.close() {
+                first_err.get_or_insert(e);
+            }
+        }
+        first_err.map_or(Ok(()), Err)
+    }
+
+    /// 各连接池统计的汇总（见 `Stats::merge`）
+    pub fn aggregate_stats(&self) -> Stats {
+        let mut total = Stats::default();
+        for pool in self
+            .pools
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .values()
+        {
+            total.merge(&pool.stats());
+        }
+        total
+    }
+}
@@ -0,0 +1,84 @@
+// Copyright (c) 2025, vistone
+// All rights reserved.
+
+//! UDP 共享 socket 模式
+//!
+//! UDP 无连接，多个请求可以共用同一个未 connect 的 socket，发送时用 `send_to`
+//! 指定目标。`SharedUdpPool` 只维护少量 socket，借用时轮询返回一个轻量句柄，
+//! 适用于只需发送短包（如指标上报、日志投递）的场景，避免为每个并发请求占用一个 socket。
+//!
+//! 注意：共享 socket 上的 `recv_from` 可能收到发给其他借用者的回包，
+//! 需要请求/响应配对的场景请继续使用 `Pool` 的一连接一 socket 模式。
+
+use crate::errors::{NetConnPoolError, Result};
+use std::io;
+use std::net::{SocketAddr, ToSocketAddrs, UdpSocket};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+/// SharedUdpPool 共享 UDP socket 池
+#[derive(Debug)]
+pub struct SharedUdpPool {
+    sockets: Vec<Arc<UdpSocket>>,
+    next: AtomicUsize,
+}
+
+impl SharedUdpPool {
+    /// 在 `bind_addr` 上绑定 `socket_count` 个 socket（端口为0时各自分配临时端口）
+    pub fn new(bind_addr: impl ToSocketAddrs, socket_count: usize) -> Result<Self> {
+        if socket_count == 0 {
+            return Err(NetConnPoolError::InvalidConfig {
+                reason: "socket_count 必须大于0".to_string(),
+            });
+        }
+        let addrs: Vec<SocketAddr> = bind_addr.to_socket_addrs()?.collect();
+        let sockets = (0..socket_count)
+            .map(|_| UdpSocket::bind(&addrs[..]).map(Arc::new))
+            .collect::<io::Result<Vec<_>>>()?;
+        Ok(Self {
+            sockets,
+            next: AtomicUsize::new(0),
+        })
+    }
+
+    /// 借用一个共享 socket 句柄（轮询分配，不会阻塞，也不受并发数限制）
+    pub fn get(&self) -> SharedUdpSocket {
+        let idx = self.next.fetch_add(1, Ordering::Relaxed) % self.sockets.len();
+        SharedUdpSocket {
+            socket: self.sockets[idx].clone(),
+        }
+    }
+
+    /// 池中实际持有的 socket 数
+    pub fn socket_count(&self) -> usize {
+        self.sockets.len()
+    }
+}
+
+/// SharedUdpSocket 共享 socket 的轻量句柄，可在线程间传递，drop 时无需归还
+#[derive(Debug, Clone)]
+pub struct SharedUdpSocket {
+    socket: Arc<UdpSocket>,
+}
+
+impl SharedUdpSocket {
+    /// 向 `target` 发送一个数据包
+    pub fn send_to(&self, buf: &[u8], target: impl ToSocketAddrs) -> io::Result<usize> {
+        self.socket.send_to(buf, target)
+    }
+
+    /// 接收一个数据包（可能是发给其他借用者的回包）
+    pub fn recv_from(&self, buf: &mut [u8]) -> io::Result<(usize, SocketAddr)> {
+        self.socket.recv_from(buf)
+    }
+
+    /// 本地地址
+    pub fn local_addr(&self) -> io::Result<SocketAddr> {
+        self.socket.local_addr()
+    }
+
+    /// 底层 socket（用于设置超时等选项，设置对所有共享者生效）
+    pub fn socket(&self) -> &UdpSocket {
+        &self.socket
+    }
+}
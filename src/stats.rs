@@ -1,8 +1,11 @@
 // Copyright (c) 2025, vistone
 // All rights reserved.
 
+use crate::ipversion::IPVersion;
+use crate::protocol::Protocol;
+use std::collections::HashMap;
 use std::sync::atomic::{AtomicI64, AtomicU64, Ordering};
-use std::sync::RwLock;
+use std::sync::Mutex;
 use std::time::{Duration, Instant};
 
 /// Stats 连接池统计信息
@@ -52,23 +55,46 @@ pub struct Stats {
     pub health_check_failures: i64,
     /// UnhealthyConnections 不健康连接数
     pub unhealthy_connections: i64,
+    /// HealthCheckFailureReasons 按失败原因分类的健康检查失败次数
+    ///
+    /// 最多记录 `MAX_FAILURE_REASONS` 种原因，之后出现的新原因统一计入 `"other"`，
+    /// 避免原因中带地址、端口等可变内容时无限增长。
+    pub health_check_failure_reasons: HashMap<String, i64>,
 
     /// ConnectionErrors 连接错误数
     pub connection_errors: i64,
-    /// LeakedConnections 泄漏的连接数
+    /// CloseFailures 关闭连接失败次数（默认关闭逻辑返回错误）
+    pub close_failures: i64,
+    /// CloseCallbackFailures close_conn 回调返回错误的次数（默认关闭逻辑仍会执行）
+    pub close_callback_failures: i64,
+    /// LeakedConnections 累计被标记为疑似泄漏的连接数（只增不减）
     pub leaked_connections: i64,
+    /// CurrentLeakedConnections 当前疑似泄漏的连接数
+    /// 疑似泄漏的连接归还或被强制回收后回落，适合用于告警
+    pub current_leaked_connections: i64,
 
     /// TotalConnectionsReused 累计连接复用次数（从空闲池获取的次数）
     pub total_connections_reused: i64,
-    /// AverageReuseCount 平均每个连接的复用次数
+    /// AverageReuseCount 平均每个连接的复用次数（与 avg_reuse_per_conn 相同，保留以兼容）
     pub average_reuse_count: f64,
+    /// AvgReusePerConn 平均每个连接的复用次数 = total_connections_reused / total_connections_created
+    pub avg_reuse_per_conn: f64,
+    /// ReuseRatio 复用率（0.0~1.0）= total_connections_reused / successful_gets，
+    /// 即成功获取中命中空闲连接的比例
+    pub reuse_ratio: f64,
 
     /// AverageGetTime 平均获取连接时间
     pub average_get_time: Duration,
     /// TotalGetTime 总获取连接时间
     pub total_get_time: Duration,
+    /// ReusedGetTime 命中空闲连接的成功获取耗时
+    pub reused_get_time: GetTimeStats,
+    /// CreatedGetTime 现场新建连接的成功获取耗时
+    pub created_get_time: GetTimeStats,
+    /// Smoothed EWMA 平滑后的速率、成功率与建连耗时
+    pub smoothed: SmoothedStats,
 
-    /// LastUpdateTime 最后更新时间
+    /// LastUpdateTime 计数器最后一次发生变化的时间（不是快照生成时间）
     pub last_update_time: Instant,
 }
 
@@ -95,21 +121,637 @@ impl Default for Stats {
             health_check_attempts: 0,
             health_check_failures: 0,
             unhealthy_connections: 0,
+            health_check_failure_reasons: HashMap::new(),
             connection_errors: 0,
+            close_failures: 0,
+            close_callback_failures: 0,
             leaked_connections: 0,
+            current_leaked_connections: 0,
             total_connections_reused: 0,
             average_reuse_count: 0.0,
+            avg_reuse_per_conn: 0.0,
+            reuse_ratio: 0.0,
             average_get_time: Duration::ZERO,
             total_get_time: Duration::ZERO,
+            reused_get_time: GetTimeStats::default(),
+            created_get_time: GetTimeStats::default(),
+            smoothed: SmoothedStats::default(),
             last_update_time: Instant::now(),
         }
     }
 }
 
+impl Stats {
+    /// 将另一份统计累加到当前统计（用于多个池的汇总）
+    ///
+    /// 计数类字段直接相加，失败原因按键合并；平均值与比率按合并后的总量重新计算，
+    /// last_update_time 取两者中较新的一个。
+    pub fn merge(&mut self, other: &Stats) {
+        self.total_connections_created += other.total_connections_created;
+        self.total_connections_closed += other.total_connections_closed;
+        self.current_connections += other.current_connections;
+        self.current_idle_connections += other.current_idle_connections;
+        self.current_active_connections += other.current_active_connections;
+        self.current_ipv4_connections += other.current_ipv4_connections;
+        self.current_ipv6_connections += other.current_ipv6_connections;
+        self.current_ipv4_idle_connections += other.current_ipv4_idle_connections;
+        self.current_ipv6_idle_connections += other.current_ipv6_idle_connections;
+        self.current_tcp_connections += other.current_tcp_connections;
+        self.current_udp_connections += other.current_udp_connections;
+        self.current_tcp_idle_connections += other.current_tcp_idle_connections;
+        self.current_udp_idle_connections += other.current_udp_idle_connections;
+        self.total_get_requests += other.total_get_requests;
+        self.successful_gets += other.successful_gets;
+        self.failed_gets += other.failed_gets;
+        self.timeout_gets += other.timeout_gets;
+        self.health_check_attempts += other.health_check_attempts;
+        self.health_check_failures += other.health_check_failures;
+        self.unhealthy_connections += other.unhealthy_connections;
+        for (reason, count) in &other.health_check_failure_reasons {
+            add_failure_reason(&mut self.health_check_failure_reasons, reason, *count);
+        }
+        self.connection_errors += other.connection_errors;
+        self.close_failures += other.close_failures;
+        self.close_callback_failures += other.close_callback_failures;
+        self.leaked_connections += other.leaked_connections;
+        self.current_leaked_connections += other.current_leaked_connections;
+        self.total_connections_reused += other.total_connections_reused;
+        self.total_get_time += other.total_get_time;
+        self.reused_get_time.merge(&other.reused_get_time);
+        self.created_get_time.merge(&other.created_get_time);
+        self.smoothed.merge(&other.smoothed);
+        self.last_update_time = self.last_update_time.max(other.last_update_time);
+
+        let reused = self.total_connections_reused.max(0) as f64;
+        self.avg_reuse_per_conn = if self.total_connections_created > 0 {
+            reused / self.total_connections_created as f64
+        } else {
+            0.0
+        };
+        self.average_reuse_count = self.avg_reuse_per_conn;
+        self.reuse_ratio = if self.successful_gets > 0 {
+            (reused / self.successful_gets as f64).min(1.0)
+        } else {
+            0.0
+        };
+        self.average_get_time = average_duration(self.total_get_time, self.successful_gets);
+    }
+
+    /// 以 OpenMetrics 文本格式导出统计，可直接作为 `/metrics` 的响应体
+    ///
+    /// 每个指标名以 `prefix_` 开头（prefix 为空时不加前缀）：累计量导出为 counter（样本带 `_total` 后缀），
+    /// 即时量与比率导出为 gauge，按 IP 版本/协议拆分的连接数与健康检查失败原因以标签区分，
+    /// 命中空闲与现场建连的获取耗时导出为带 `source` 标签的 summary（单位秒）。输出以 `# EOF` 结尾。
+    pub fn to_openmetrics(&self, prefix: &str) -> String {
+        let mut out = MetricsWriter {
+            prefix,
+            out: String::new(),
+        };
+
+        for (metric, help, value) in [
+            (
+                "connections_created",
+                "累计创建的连接数",
+                self.total_connections_created,
+            ),
+            (
+                "connections_closed",
+                "累计关闭的连接数",
+                self.total_connections_closed,
+            ),
+            (
+                "connections_reused",
+                "累计从空闲池复用连接的次数",
+                self.total_connections_reused,
+            ),
+            (
+                "get_requests",
+                "累计获取连接请求数",
+                self.total_get_requests,
+            ),
+            ("successful_gets", "成功获取连接数", self.successful_gets),
+            ("failed_gets", "失败获取连接数", self.failed_gets),
+            ("timeout_gets", "超时获取连接数", self.timeout_gets),
+            (
+                "health_check_attempts",
+                "健康检查尝试次数",
+                self.health_check_attempts,
+            ),
+            (
+                "health_check_failures",
+                "健康检查失败次数",
+                self.health_check_failures,
+            ),
+            (
+                "unhealthy_connections",
+                "被判定不健康的连接数",
+                self.unhealthy_connections,
+            ),
+            ("connection_errors", "连接错误数", self.connection_errors),
+            ("close_failures", "关闭连接失败次数", self.close_failures),
+            (
+                "close_callback_failures",
+                "close_conn 回调失败次数",
+                self.close_callback_failures,
+            ),
+            (
+                "leaked_connections",
+                "累计疑似泄漏的连接数",
+                self.leaked_connections,
+            ),
+        ] {
+            out.family(metric, "counter", help, &[("", value.to_string())]);
+        }
+        out.family(
+            "get_time_seconds",
+            "counter",
+            "成功获取连接的累计耗时（秒）",
+            &[("", self.total_get_time.as_secs_f64().to_string())],
+        );
+        let mut reasons: Vec<_> = self
+            .health_check_failure_reasons
+            .iter()
+            .map(|(reason, count)| {
+                (
+                    format!("reason=\"{}\"", escape_label_value(reason)),
+                    count.to_string(),
+                )
+            })
+            .collect();
+        reasons.sort();
+        out.family(
+            "health_check_failure_reasons",
+            "counter",
+            "按原因分类的健康检查失败次数",
+            &reasons,
+        );
+
+        for (metric, help, value) in [
+            (
+                "current_connections",
+                "当前连接数",
+                self.current_connections,
+            ),
+            (
+                "current_idle_connections",
+                "当前空闲连接数",
+                self.current_idle_connections,
+            ),
+            (
+                "current_active_connections",
+                "当前活跃连接数",
+                self.current_active_connections,
+            ),
+            (
+                "current_leaked_connections",
+                "当前疑似泄漏的连接数",
+                self.current_leaked_connections,
+            ),
+        ] {
+            out.family(metric, "gauge", help, &[("", value.to_string())]);
+        }
+        for (metric, help, samples) in [
+            (
+                "current_connections_by_ip_version",
+                "按 IP 版本统计的当前连接数",
+                [
+                    ("ip_version=\"ipv4\"", self.current_ipv4_connections),
+                    ("ip_version=\"ipv6\"", self.current_ipv6_connections),
+                ],
+            ),
+            (
+                "current_idle_connections_by_ip_version",
+                "按 IP 版本统计的当前空闲连接数",
+                [
+                    ("ip_version=\"ipv4\"", self.current_ipv4_idle_connections),
+                    ("ip_version=\"ipv6\"", self.current_ipv6_idle_connections),
+                ],
+            ),
+            (
+                "current_connections_by_protocol",
+                "按协议统计的当前连接数",
+                [
+                    ("protocol=\"tcp\"", self.current_tcp_connections),
+                    ("protocol=\"udp\"", self.current_udp_connections),
+                ],
+            ),
+            (
+                "current_idle_connections_by_protocol",
+                "按协议统计的当前空闲连接数",
+                [
+                    ("protocol=\"tcp\"", self.current_tcp_idle_connections),
+                    ("protocol=\"udp\"", self.current_udp_idle_connections),
+                ],
+            ),
+        ] {
+            let samples = samples.map(|(labels, value)| (labels, value.to_string()));
+            out.family(metric, "gauge", help, &samples);
+        }
+        out.family(
+            "reuse_ratio",
+            "gauge",
+            "成功获取中命中空闲连接的比例",
+            &[("", self.reuse_ratio.to_string())],
+        );
+        out.family(
+            "avg_reuse_per_conn",
+            "gauge",
+            "平均每个连接的复用次数",
+            &[("", self.avg_reuse_per_conn.to_string())],
+        );
+        for (metric, help, value) in [
+            (
+                "get_rate_ewma",
+                "EWMA 平滑后的获取请求速率（次/秒）",
+                self.smoothed.get_rate,
+            ),
+            (
+                "success_ratio_ewma",
+                "EWMA 平滑后的获取成功率",
+                self.smoothed.success_ratio,
+            ),
+            (
+                "created_get_time_ewma_seconds",
+                "EWMA 平滑后的现场建连获取耗时（秒）",
+                self.smoothed.created_get_time.as_secs_f64(),
+            ),
+        ] {
+            out.family(metric, "gauge", help, &[("", value.to_string())]);
+        }
+
+        // summary：每个来源依次输出分位样本与 _sum/_count
+        let mut labels = Vec::new();
+        let mut values = Vec::new();
+        for (source, stats) in [
+            ("reused", &self.reused_get_time),
+            ("created", &self.created_get_time),
+        ] {
+            for (quantile, value) in [("0.5", stats.p50), ("0.9", stats.p90), ("0.99", stats.p99)] {
+                labels.push(format!("source=\"{}\",quantile=\"{}\"", source, quantile));
+                values.push(("", value.as_secs_f64().to_string()));
+            }
+            labels.push(format!("source=\"{}\"", source));
+            values.push(("_sum", stats.total.as_secs_f64().to_string()));
+            labels.push(format!("source=\"{}\"", source));
+            values.push(("_count", stats.count.to_string()));
+        }
+        out.header(
+            "get_duration_seconds",
+            "summary",
+            "按来源（命中空闲/现场建连）统计的成功获取耗时（秒）",
+        );
+        for (labels, (suffix, value)) in labels.iter().zip(values) {
+            out.sample("get_duration_seconds", suffix, labels, &value);
+        }
+
+        out.out.push_str("# EOF\n");
+        out.out
+    }
+}
+
+/// OpenMetrics 文本拼接辅助
+struct MetricsWriter<'a> {
+    prefix: &'a str,
+    out: String,
+}
+
+impl MetricsWriter<'_> {
+    fn name(&self, metric: &str) -> String {
+        if self.prefix.is_empty() {
+            metric.to_string()
+        } else {
+            format!("{}_{}", self.prefix, metric)
+        }
+    }
+
+    fn header(&mut self, metric: &str, kind: &str, help: &str) {
+        let name = self.name(metric);
+        self.out.push_str(&format!(
+            "# TYPE {} {}\n# HELP {} {}\n",
+            name, kind, name, help
+        ));
+    }
+
+    fn sample(&mut self, metric: &str, suffix: &str, labels: &str, value: &str) {
+        let name = self.name(metric);
+        if labels.is_empty() {
+            self.out
+                .push_str(&format!("{}{} {}\n", name, suffix, value));
+        } else {
+            self.out
+                .push_str(&format!("{}{}{{{}}} {}\n", name, suffix, labels, value));
+        }
+    }
+
+    /// 输出一个 counter/gauge 指标族；counter 样本名追加 `_total`
+    fn family<L: AsRef<str>>(
+        &mut self,
+        metric: &str,
+        kind: &str,
+        help: &str,
+        samples: &[(L, String)],
+    ) {
+        self.header(metric, kind, help);
+        let suffix = if kind == "counter" { "_total" } else { "" };
+        for (labels, value) in samples {
+            self.sample(metric, suffix, labels.as_ref(), value);
+        }
+    }
+}
+
+/// 转义 OpenMetrics 标签值中的反斜杠、双引号与换行
+fn escape_label_value(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+/// SmoothedStats 指数加权移动平均（EWMA）平滑后的指标
+///
+/// 每次生成统计快照时，把距上次快照这段时间内的区间值以权重 `1 - e^(-dt/window)` 并入，
+/// 因此结果与快照频率无关；稳定输入下约经过 3 个 window 收敛到该值的 95%。
+/// window 由 `Config::stats_ewma_window` 指定。
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct SmoothedStats {
+    /// GetRate 获取请求速率（次/秒）
+    pub get_rate: f64,
+    /// SuccessRatio 获取成功率（0.0~1.0），尚无获取请求时为0
+    pub success_ratio: f64,
+    /// CreatedGetTime 现场新建连接的获取耗时，尚无样本时为0
+    pub created_get_time: Duration,
+}
+
+impl SmoothedStats {
+    /// 合并另一个池的平滑值：速率相加，成功率与建连耗时按速率加权
+    pub fn merge(&mut self, other: &SmoothedStats) {
+        let total = self.get_rate + other.get_rate;
+        if total > 0.0 {
+            let (w1, w2) = (self.get_rate / total, other.get_rate / total);
+            self.success_ratio = self.success_ratio * w1 + other.success_ratio * w2;
+            self.created_get_time =
+                self.created_get_time.mul_f64(w1) + other.created_get_time.mul_f64(w2);
+        } else {
+            self.success_ratio = self.success_ratio.max(other.success_ratio);
+            self.created_get_time = self.created_get_time.max(other.created_get_time);
+        }
+        self.get_rate = total;
+    }
+}
+
+/// EWMA 状态：上次快照时的累计量基线与当前平滑值
+struct EwmaState {
+    last_tick: Instant,
+    total_get_requests: i64,
+    successful_gets: i64,
+    created_count: i64,
+    created_total: Duration,
+    smoothed: SmoothedStats,
+    // 成功率是否已有首个样本（成功率可以合法地为0，不能用0判断）
+    ratio_seeded: bool,
+}
+
+impl EwmaState {
+    fn new(now: Instant) -> Self {
+        Self {
+            last_tick: now,
+            total_get_requests: 0,
+            successful_gets: 0,
+            created_count: 0,
+            created_total: Duration::ZERO,
+            smoothed: SmoothedStats::default(),
+            ratio_seeded: false,
+        }
+    }
+
+    /// 以当前累计量推进一次 EWMA 并返回平滑值
+    fn tick(
+        &mut self,
+        now: Instant,
+        window: Duration,
+        total_get_requests: i64,
+        successful_gets: i64,
+        created: &GetTimeStats,
+    ) -> SmoothedStats {
+        let dt = now.saturating_duration_since(self.last_tick);
+        if dt.is_zero() {
+            return self.smoothed;
+        }
+        let alpha = 1.0 - (-dt.as_secs_f64() / window.as_secs_f64()).exp();
+        let smoothed = &mut self.smoothed;
+
+        let gets = (total_get_requests - self.total_get_requests).max(0);
+        smoothed.get_rate += alpha * (gets as f64 / dt.as_secs_f64() - smoothed.get_rate);
+
+        // 成功率与建连耗时只在区间内有样本时更新，首个样本直接作为初值
+        if gets > 0 {
+            let succeeded = (successful_gets - self.successful_gets).clamp(0, gets);
+            let ratio = succeeded as f64 / gets as f64;
+            smoothed.success_ratio = if self.ratio_seeded {
+                smoothed.success_ratio + alpha * (ratio - smoothed.success_ratio)
+            } else {
+                ratio
+            };
+            self.ratio_seeded = true;
+        }
+        let created_count = created.count - self.created_count;
+        if created_count > 0 {
+            let sample = average_duration(
+                created.total.saturating_sub(self.created_total),
+                created_count,
+            );
+            smoothed.created_get_time = if smoothed.created_get_time.is_zero() {
+                sample
+            } else {
+                smoothed.created_get_time.mul_f64(1.0 - alpha) + sample.mul_f64(alpha)
+            };
+        }
+
+        self.last_tick = now;
+        self.total_get_requests = total_get_requests;
+        self.successful_gets = successful_gets;
+        self.created_count = created.count;
+        self.created_total = created.total;
+        self.smoothed
+    }
+
+    /// 累计量被 reset 清零后同步清零基线，平滑值保留
+    fn reset_baseline(&mut self) {
+        self.total_get_requests = 0;
+        self.successful_gets = 0;
+        self.created_count = 0;
+        self.created_total = Duration::ZERO;
+    }
+}
+
+/// health_check_failure_reasons 最多记录的不同原因数，超出后计入 `FAILURE_REASON_OTHER`
+pub const MAX_FAILURE_REASONS: usize = 32;
+
+/// 超出 `MAX_FAILURE_REASONS` 的失败原因统一归入的键
+pub const FAILURE_REASON_OTHER: &str = "other";
+
+/// 按原因累加失败次数：已有原因直接累加，原因种类达到上限后新原因归入 "other"
+fn add_failure_reason(reasons: &mut HashMap<String, i64>, reason: &str, count: i64) {
+    let key = if reasons.contains_key(reason) || reasons.len() < MAX_FAILURE_REASONS {
+        reason
+    } else {
+        FAILURE_REASON_OTHER
+    };
+    *reasons.entry(key.to_string()).or_insert(0) += count;
+}
+
+/// 总耗时按样本数求平均，样本数不为正时为 0
+///
+/// 以 u128 纳秒相除：样本数超过 u32::MAX 时不会截断（`Duration / u32` 会截断甚至除零）。
+fn average_duration(total: Duration, count: i64) -> Duration {
+    u128::try_from(count)
+        .ok()
+        .and_then(|count| total.as_nanos().checked_div(count))
+        .map_or(Duration::ZERO, |nanos| {
+            Duration::from_nanos(u64::try_from(nanos).unwrap_or(u64::MAX))
+        })
+}
+
+/// GetTimeStats 一类成功获取（命中空闲 / 现场建连）的耗时统计
+///
+/// 分位数由对数分桶直方图估算，每个分桶宽度不超过其下界的 25%，取分桶上界作为估计值。
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct GetTimeStats {
+    /// Count 样本数
+    pub count: i64,
+    /// Total 总耗时
+    pub total: Duration,
+    /// Average 平均耗时
+    pub average: Duration,
+    /// P50 耗时中位数
+    pub p50: Duration,
+    /// P90 90 分位耗时
+    pub p90: Duration,
+    /// P99 99 分位耗时
+    pub p99: Duration,
+}
+
+impl GetTimeStats {
+    /// 累加另一份耗时统计（用于多个池的汇总）
+    ///
+    /// 样本数与总耗时相加并重新计算平均值；合并后无法还原直方图，分位数取两者中较大者作为上界近似。
+    pub fn merge(&mut self, other: &GetTimeStats) {
+        self.count += other.count;
+        self.total += other.total;
+        self.average = average_duration(self.total, self.count);
+        self.p50 = self.p50.max(other.p50);
+        self.p90 = self.p90.max(other.p90);
+        self.p99 = self.p99.max(other.p99);
+    }
+}
+
+/// 获取耗时直方图：按纳秒值的最高位分组，每组再按次高两位细分为 4 个分桶
+struct GetTimeHistogram {
+    buckets: Vec<AtomicU64>,
+    total_nanos: AtomicU64,
+}
+
+impl GetTimeHistogram {
+    const BUCKETS: usize = 252;
+
+    fn new() -> Self {
+        Self {
+            buckets: (0..Self::BUCKETS).map(|_| AtomicU64::new(0)).collect(),
+            total_nanos: AtomicU64::new(0),
+        }
+    }
+
+    fn bucket_of(nanos: u64) -> usize {
+        if nanos < 4 {
+            return nanos as usize;
+        }
+        let msb = 63 - nanos.leading_zeros() as usize;
+        let sub = ((nanos >> (msb - 2)) & 3) as usize;
+        (msb - 1) * 4 + sub
+    }
+
+    /// 分桶内的最大纳秒值
+    fn upper_bound(bucket: usize) -> u64 {
+        if bucket < 4 {
+            return bucket as u64;
+        }
+        let msb = bucket / 4 + 1;
+        let lower = (4 + (bucket % 4) as u64) << (msb - 2);
+        lower + ((1u64 << (msb - 2)) - 1)
+    }
+
+    fn record(&self, nanos: u64) {
+        self.buckets[Self::bucket_of(nanos)].fetch_add(1, Ordering::Relaxed);
+        StatsCollector::safe_increment_u64(&self.total_nanos, nanos, "get_time_histogram");
+    }
+
+    fn snapshot(&self) -> GetTimeStats {
+        let counts: Vec<u64> = self
+            .buckets
+            .iter()
+            .map(|b| b.load(Ordering::Relaxed))
+            .collect();
+        let count: u64 = counts.iter().sum();
+        if count == 0 {
+            return GetTimeStats::default();
+        }
+        let total = self.total_nanos.load(Ordering::Relaxed);
+        let percentile = |q: f64| {
+            let rank = ((count as f64 * q).ceil() as u64).max(1);
+            let mut seen = 0;
+            for (bucket, n) in counts.iter().enumerate() {
+                seen += n;
+                if seen >= rank {
+                    return Duration::from_nanos(Self::upper_bound(bucket));
+                }
+            }
+            Duration::ZERO
+        };
+        GetTimeStats {
+            count: count as i64,
+            total: Duration::from_nanos(total),
+            average: Duration::from_nanos(total / count),
+            p50: percentile(0.5),
+            p90: percentile(0.9),
+            p99: percentile(0.99),
+        }
+    }
+
+    fn reset(&self) {
+        for bucket in &self.buckets {
+            bucket.swap(0, Ordering::Relaxed);
+        }
+        self.total_nanos.swap(0, Ordering::Relaxed);
+    }
+}
+
+/// BucketStat 单个空闲桶（协议×IP 版本）的连接分布
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BucketStat {
+    /// Protocol 桶对应的协议
+    pub protocol: Protocol,
+    /// IPVersion 桶对应的 IP 版本
+    pub ip_version: IPVersion,
+    /// Idle 当前空闲连接数
+    pub idle: usize,
+    /// Active 当前借出中的连接数
+    pub active: usize,
+}
+
 /// StatsCollector 统计收集器
 pub struct StatsCollector {
     stats: StatsInternal,
-    last_update_time: RwLock<Instant>,
+    // 健康检查失败原因分类计数（仅在失败路径上更新，使用锁即可）
+    health_check_failure_reasons: Mutex<HashMap<String, i64>>,
+    // 按获取来源（命中空闲 / 现场建连）分开的耗时直方图
+    reused_get_time: GetTimeHistogram,
+    created_get_time: GetTimeHistogram,
+    // EWMA 平滑状态，只在生成快照时推进
+    ewma: Mutex<EwmaState>,
+    ewma_window: Duration,
+    // 最后一次计数变化相对 created_at 的纳秒偏移，用原子量避免热路径加锁
+    created_at: Instant,
+    last_update_nanos: AtomicU64,
 }
 
 struct StatsInternal {
@@ -134,7 +776,10 @@ struct StatsInternal {
     health_check_failures: AtomicI64,
     unhealthy_connections: AtomicI64,
     connection_errors: AtomicI64,
+    close_failures: AtomicI64,
+    close_callback_failures: AtomicI64,
     leaked_connections: AtomicI64,
+    current_leaked_connections: AtomicI64,
     total_connections_reused: AtomicI64,
     average_get_time: AtomicU64, // Duration as nanoseconds
     total_get_time: AtomicU64,   // Duration as nanoseconds
@@ -200,8 +845,14 @@ impl StatsCollector {
         }
     }
 
-    /// NewStatsCollector 创建统计收集器
+    /// NewStatsCollector 创建统计收集器（EWMA 窗口为默认的10秒）
     pub fn new() -> Self {
+        Self::with_ewma_window(Duration::from_secs(10))
+    }
+
+    /// 创建统计收集器，并指定 EWMA 平滑指标的时间窗口
+    pub fn with_ewma_window(ewma_window: Duration) -> Self {
+        let now = Instant::now();
         Self {
             stats: StatsInternal {
                 total_connections_created: AtomicI64::new(0),
@@ -225,12 +876,21 @@ impl StatsCollector {
                 health_check_failures: AtomicI64::new(0),
                 unhealthy_connections: AtomicI64::new(0),
                 connection_errors: AtomicI64::new(0),
+                close_failures: AtomicI64::new(0),
+                close_callback_failures: AtomicI64::new(0),
                 leaked_connections: AtomicI64::new(0),
+                current_leaked_connections: AtomicI64::new(0),
                 total_connections_reused: AtomicI64::new(0),
                 average_get_time: AtomicU64::new(0),
                 total_get_time: AtomicU64::new(0),
             },
-            last_update_time: RwLock::new(Instant::now()),
+            health_check_failure_reasons: Mutex::new(HashMap::new()),
+            reused_get_time: GetTimeHistogram::new(),
+            created_get_time: GetTimeHistogram::new(),
+            ewma: Mutex::new(EwmaState::new(now)),
+            ewma_window,
+            created_at: now,
+            last_update_nanos: AtomicU64::new(0),
         }
     }
 
@@ -282,6 +942,14 @@ impl StatsCollector {
         self.update_time();
     }
 
+    /// get_request_counts 当前的 (total_get_requests, failed_gets)，不触发 EWMA 更新
+    pub(crate) fn get_request_counts(&self) -> (i64, i64) {
+        (
+            self.stats.total_get_requests.load(Ordering::Relaxed),
+            self.stats.failed_gets.load(Ordering::Relaxed),
+        )
+    }
+
     /// IncrementSuccessfulGets 增加成功获取计数
     pub fn increment_successful_gets(&self) {
         Self::safe_increment_i64(&self.stats.successful_gets, 1, "successful_gets");
@@ -330,18 +998,54 @@ impl StatsCollector {
         self.update_time();
     }
 
+    /// RecordHealthCheckFailureReason 按原因记录一次健康检查失败
+    pub fn record_health_check_failure_reason(&self, reason: &str) {
+        let mut reasons = self
+            .health_check_failure_reasons
+            .lock()
+            .unwrap_or_else(|e| e.into_inner());
+        add_failure_reason(&mut reasons, reason, 1);
+        self.update_time();
+    }
+
     /// IncrementConnectionErrors 增加连接错误计数
     pub fn increment_connection_errors(&self) {
         Self::safe_increment_i64(&self.stats.connection_errors, 1, "connection_errors");
         self.update_time();
     }
 
+    /// IncrementCloseFailures 增加关闭连接失败计数
+    pub fn increment_close_failures(&self) {
+        Self::safe_increment_i64(&self.stats.close_failures, 1, "close_failures");
+        self.update_time();
+    }
+
+    /// IncrementCloseCallbackFailures 增加 close_conn 回调失败计数
+    pub fn increment_close_callback_failures(&self) {
+        Self::safe_increment_i64(
+            &self.stats.close_callback_failures,
+            1,
+            "close_callback_failures",
+        );
+        self.update_time();
+    }
+
     /// IncrementLeakedConnections 增加泄漏连接计数
     pub fn increment_leaked_connections(&self) {
         Self::safe_increment_i64(&self.stats.leaked_connections, 1, "leaked_connections");
         self.update_time();
     }
 
+    /// IncrementCurrentLeakedConnections 增加当前疑似泄漏连接计数
+    pub fn increment_current_leaked_connections(&self, delta: i64) {
+        Self::safe_increment_i64(
+            &self.stats.current_leaked_connections,
+            delta,
+            "current_leaked_connections",
+        );
+        self.update_time();
+    }
+
     /// RecordGetTime 记录获取连接的时间
     pub fn record_get_time(&self, duration: Duration) {
         // 安全转换，避免溢出（Duration的纳秒值通常不会超过u64::MAX）
@@ -350,16 +1054,25 @@ impl StatsCollector {
         self.update_time();
     }
 
+    /// RecordGetTimeBySource 按获取来源分别记录耗时（`reused` 为 true 表示命中空闲连接）
+    ///
+    /// 只写入分来源的耗时统计，总耗时仍由 `record_get_time` 记录。
+    pub fn record_get_time_by_source(&self, duration: Duration, reused: bool) {
+        let nanos = duration.as_nanos().min(u64::MAX as u128) as u64;
+        if reused {
+            self.reused_get_time.record(nanos);
+        } else {
+            self.created_get_time.record(nanos);
+        }
+    }
+
     /// GetStats 获取当前统计信息快照
     pub fn get_stats(&self) -> Stats {
         // 动态计算平均值，避免在快速路径上计算
         let total_gets = self.stats.successful_gets.load(Ordering::Relaxed).max(0) as u64;
+        let now = Instant::now();
         let total_time = self.stats.total_get_time.load(Ordering::Relaxed);
-        let avg_time = if total_gets > 0 {
-            total_time / total_gets
-        } else {
-            0
-        };
+        let avg_time = total_time.checked_div(total_gets).unwrap_or(0);
         self.stats
             .average_get_time
             .store(avg_time, Ordering::Relaxed);
@@ -371,6 +1084,22 @@ impl StatsCollector {
         } else {
             0.0
         };
+        let successful_gets = self.stats.successful_gets.load(Ordering::Relaxed);
+        let reuse_ratio = if successful_gets > 0 {
+            (total_reused.max(0) as f64 / successful_gets as f64).min(1.0)
+        } else {
+            0.0
+        };
+
+        let total_get_requests = self.stats.total_get_requests.load(Ordering::Relaxed);
+        let created_get_time = self.created_get_time.snapshot();
+        let smoothed = self.ewma.lock().unwrap_or_else(|e| e.into_inner()).tick(
+            now,
+            self.ewma_window,
+            total_get_requests,
+            successful_gets,
+            &created_get_time,
+        );
 
         Stats {
             total_connections_created: self.stats.total_connections_created.load(Ordering::Relaxed),
@@ -401,33 +1130,81 @@ impl StatsCollector {
                 .stats
                 .current_udp_idle_connections
                 .load(Ordering::Relaxed),
-            total_get_requests: self.stats.total_get_requests.load(Ordering::Relaxed),
-            successful_gets: self.stats.successful_gets.load(Ordering::Relaxed),
+            total_get_requests,
+            successful_gets,
             failed_gets: self.stats.failed_gets.load(Ordering::Relaxed),
             timeout_gets: self.stats.timeout_gets.load(Ordering::Relaxed),
             health_check_attempts: self.stats.health_check_attempts.load(Ordering::Relaxed),
             health_check_failures: self.stats.health_check_failures.load(Ordering::Relaxed),
             unhealthy_connections: self.stats.unhealthy_connections.load(Ordering::Relaxed),
+            health_check_failure_reasons: self
+                .health_check_failure_reasons
+                .lock()
+                .map(|r| r.clone())
+                .unwrap_or_default(),
             connection_errors: self.stats.connection_errors.load(Ordering::Relaxed),
+            close_failures: self.stats.close_failures.load(Ordering::Relaxed),
+            close_callback_failures: self.stats.close_callback_failures.load(Ordering::Relaxed),
             leaked_connections: self.stats.leaked_connections.load(Ordering::Relaxed),
+            current_leaked_connections: self
+                .stats
+                .current_leaked_connections
+                .load(Ordering::Relaxed),
             total_connections_reused: total_reused,
             average_reuse_count: avg_reuse,
+            avg_reuse_per_conn: avg_reuse,
+            reuse_ratio,
             average_get_time: Duration::from_nanos(
                 self.stats.average_get_time.load(Ordering::Relaxed),
             ),
             total_get_time: Duration::from_nanos(self.stats.total_get_time.load(Ordering::Relaxed)),
-            last_update_time: {
-                // 更新 last_update_time：记录最后一次获取统计快照的时间
-                // 注意：update_time() 已优化为空操作，此处是唯一更新点
-                let now = Instant::now();
-                if let Ok(mut last_time) = self.last_update_time.write() {
-                    *last_time = now;
-                }
-                now
-            },
+            reused_get_time: self.reused_get_time.snapshot(),
+            created_get_time,
+            smoothed,
+            last_update_time: self.created_at
+                + Duration::from_nanos(self.last_update_nanos.load(Ordering::Relaxed)),
         }
     }
 
+    /// Reset 清零累计型计数器，用于区间统计
+    ///
+    /// 仅清零 total_* / *_gets / 健康检查 / 连接错误等只增不减的计数器，
+    /// current_* 即时量与泄漏连接数保持不变。每个计数器以 swap 单独清零，
+    /// 单个计数器上并发的增量要么计入旧区间、要么计入新区间；但各计数器并非同一时刻清零，
+    /// reset 期间的统计快照可能出现部分计数器已清零、部分尚未清零的情况。
+    pub fn reset(&self) {
+        let cumulative = [
+            &self.stats.total_connections_created,
+            &self.stats.total_connections_closed,
+            &self.stats.total_get_requests,
+            &self.stats.successful_gets,
+            &self.stats.failed_gets,
+            &self.stats.timeout_gets,
+            &self.stats.health_check_attempts,
+            &self.stats.health_check_failures,
+            &self.stats.unhealthy_connections,
+            &self.stats.connection_errors,
+            &self.stats.close_failures,
+            &self.stats.close_callback_failures,
+            &self.stats.total_connections_reused,
+        ];
+        for counter in cumulative {
+            counter.swap(0, Ordering::Relaxed);
+        }
+        self.stats.total_get_time.swap(0, Ordering::Relaxed);
+        self.stats.average_get_time.swap(0, Ordering::Relaxed);
+        self.reused_get_time.reset();
+        self.created_get_time.reset();
+        self.ewma
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .reset_baseline();
+        if let Ok(mut reasons) = self.health_check_failure_reasons.lock() {
+            reasons.clear();
+        }
+        self.update_time();
+    }
+
     /// IncrementCurrentIPv4Connections 增加IPv4连接计数
     pub fn increment_current_ipv4_connections(&self, delta: i64) {
         Self::safe_increment_i64(
@@ -518,10 +1295,69 @@ impl StatsCollector {
         self.update_time();
     }
 
+    /// ReconcileCurrent 将 current_* 即时计数校正为 `actual` 中的真实值
+    ///
+    /// 仅改写 current_* 字段，累计型计数器不受影响。返回被校正（存在漂移）的字段数。
+    /// 与校正并发发生的增减可能再次引入偏差，建议在静止态调用。
+    pub fn reconcile_current(&self, actual: &Stats) -> usize {
+        let fields = [
+            (&self.stats.current_connections, actual.current_connections),
+            (
+                &self.stats.current_idle_connections,
+                actual.current_idle_connections,
+            ),
+            (
+                &self.stats.current_active_connections,
+                actual.current_active_connections,
+            ),
+            (
+                &self.stats.current_ipv4_connections,
+                actual.current_ipv4_connections,
+            ),
+            (
+                &self.stats.current_ipv6_connections,
+                actual.current_ipv6_connections,
+            ),
+            (
+                &self.stats.current_ipv4_idle_connections,
+                actual.current_ipv4_idle_connections,
+            ),
+            (
+                &self.stats.current_ipv6_idle_connections,
+                actual.current_ipv6_idle_connections,
+            ),
+            (
+                &self.stats.current_tcp_connections,
+                actual.current_tcp_connections,
+            ),
+            (
+                &self.stats.current_udp_connections,
+                actual.current_udp_connections,
+            ),
+            (
+                &self.stats.current_tcp_idle_connections,
+                actual.current_tcp_idle_connections,
+            ),
+            (
+                &self.stats.current_udp_idle_connections,
+                actual.current_udp_idle_connections,
+            ),
+        ];
+        let fixed = fields
+            .into_iter()
+            .filter(|(counter, value)| counter.swap(*value, Ordering::Relaxed) != *value)
+            .count();
+        if fixed > 0 {
+            self.update_time();
+        }
+        fixed
+    }
+
     #[inline]
     fn update_time(&self) {
-        // 优化：不再需要频繁更新，只在 get_stats 时更新
-        // 保留空方法以保持 API 兼容性
+        // fetch_max 保证并发更新时时间戳不会回退
+        let nanos = self.created_at.elapsed().as_nanos().min(u64::MAX as u128) as u64;
+        self.last_update_nanos.fetch_max(nanos, Ordering::Relaxed);
     }
 }
 
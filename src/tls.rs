@@ -0,0 +1,143 @@
+// Copyright (c) 2025, vistone
+// All rights reserved.
+
+//! TLS 连接支持（`tls` feature，基于 rustls）
+//!
+//! 握手完成后的 TLS 流以 `ConnectionType::Tls` 纳入连接池管理，按 TCP 分桶；
+//! 连接关闭时先发送 close_notify 再关闭底层 TCP。
+
+use crate::config::{ConnectionType, Dialer};
+use crate::protocol::Protocol;
+use rustls::pki_types::ServerName;
+use rustls::{ClientConfig, ClientConnection, StreamOwned};
+use std::fmt;
+use std::io::{self, Read, Write};
+use std::net::{SocketAddr, TcpStream, ToSocketAddrs};
+use std::sync::{Arc, Mutex, MutexGuard, TryLockError};
+
+/// TlsStream 已完成握手的客户端 TLS 流
+///
+/// 内部以 Mutex 保护 rustls 状态，因此可像 `&TcpStream` 一样通过共享引用读写；
+/// 同一时刻只有一个读或写操作持有锁，阻塞读期间其他线程的写会等待。
+/// socket 选项、地址查询与关闭走独立克隆的 TCP 句柄，不会被阻塞读卡住。
+pub struct TlsStream {
+    // rustls 连接状态较大，装箱以免撑大 ConnectionType 的其他变体
+    stream: Box<Mutex<StreamOwned<ClientConnection, TcpStream>>>,
+    // 与 stream 共享同一 socket 的克隆句柄，无需获取会话锁
+    sock: TcpStream,
+}
+
+impl fmt::Debug for TlsStream {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TlsStream")
+            .field("peer_addr", &self.peer_addr().ok())
+            .finish()
+    }
+}
+
+impl TlsStream {
+    /// 在已建立的 TCP 连接上完成 TLS 握手
+    pub fn from_tcp(
+        mut sock: TcpStream,
+        server_name: &str,
+        config: Arc<ClientConfig>,
+    ) -> io::Result<Self> {
+        let name = ServerName::try_from(server_name.to_string())
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+        let mut conn = ClientConnection::new(config, name).map_err(io::Error::other)?;
+        while conn.is_handshaking() {
+            conn.complete_io(&mut sock)?;
+        }
+        Ok(Self {
+            sock: sock.try_clone()?,
+            stream: Box::new(Mutex::new(StreamOwned::new(conn, sock))),
+        })
+    }
+
+    /// 建立 TCP 连接并完成 TLS 握手
+    pub fn connect(
+        addr: impl ToSocketAddrs,
+        server_name: &str,
+        config: Arc<ClientConfig>,
+    ) -> io::Result<Self> {
+        Self::from_tcp(TcpStream::connect(addr)?, server_name, config)
+    }
+
+    /// 获取底层 rustls 流（可访问协商结果，如 ALPN、对端证书）
+    pub fn lock(&self) -> MutexGuard<'_, StreamOwned<ClientConnection, TcpStream>> {
+        self.stream.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// 对底层 TCP 连接执行操作（如设置 socket 选项），不获取会话锁
+    ///
+    /// 不要通过该句柄直接读写数据，否则会破坏 TLS 记录流。
+    pub fn with_tcp<R>(&self, f: impl FnOnce(&TcpStream) -> R) -> R {
+        f(&self.sock)
+    }
+
+    /// 对端地址
+    pub fn peer_addr(&self) -> io::Result<SocketAddr> {
+        self.with_tcp(TcpStream::peer_addr)
+    }
+
+    /// 本地地址
+    pub fn local_addr(&self) -> io::Result<SocketAddr> {
+        self.with_tcp(TcpStream::local_addr)
+    }
+
+    /// 发送 close_notify 并关闭底层 TCP 连接
+    ///
+    /// 会话锁被阻塞读占用时跳过 close_notify，直接关闭 socket 以唤醒阻塞的读。
+    pub(crate) fn shutdown(&self) {
+        let stream = match self.stream.try_lock() {
+            Ok(stream) => Some(stream),
+            Err(TryLockError::Poisoned(e)) => Some(e.into_inner()),
+            Err(TryLockError::WouldBlock) => None,
+        };
+        if let Some(mut stream) = stream {
+            stream.conn.send_close_notify();
+            let _ = stream.flush();
+        }
+        let _ = self.sock.shutdown(std::net::Shutdown::Both);
+    }
+}
+
+impl Read for &TlsStream {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.lock().read(buf)
+    }
+}
+
+impl Write for &TlsStream {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.lock().write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.lock().flush()
+    }
+}
+
+/// tls_dialer 创建一个 TLS Dialer
+///
+/// 每次建连时连接 `host:port` 并以 `server_name` 完成 TLS 握手。
+/// 该 Dialer 仅支持 TCP，调用方请求 UDP 时返回错误。
+pub fn tls_dialer(
+    host: impl Into<String>,
+    port: u16,
+    server_name: impl Into<String>,
+    config: Arc<ClientConfig>,
+) -> Dialer {
+    let host = host.into();
+    let server_name = server_name.into();
+    Box::new(move |protocol| {
+        if protocol == Some(Protocol::UDP) {
+            return Err(Box::new(io::Error::new(
+                io::ErrorKind::Unsupported,
+                "tls dialer 仅支持 TCP",
+            )) as Box<dyn std::error::Error + Send + Sync>);
+        }
+        let stream = TlsStream::connect((host.as_str(), port), &server_name, config.clone())?;
+        Ok(ConnectionType::Tls(stream))
+    })
+}
@@ -0,0 +1,146 @@
+// Copyright (c) 2025, vistone
+// All rights reserved.
+
+// 异步连接池（async feature）行为测试
+
+use netconnpool::config::default_config;
+use netconnpool::*;
+use std::net::SocketAddr;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+
+/// 启动一个异步回声服务器
+async fn start_echo_server() -> SocketAddr {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        while let Ok((mut stream, _)) = listener.accept().await {
+            tokio::spawn(async move {
+                let mut buf = [0u8; 1024];
+                while let Ok(n) = stream.read(&mut buf).await {
+                    if n == 0 || stream.write_all(&buf[..n]).await.is_err() {
+                        break;
+                    }
+                }
+            });
+        }
+    });
+    addr
+}
+
+fn async_pool(addr: SocketAddr, max_connections: usize) -> AsyncPool {
+    let mut config = default_config();
+    config.min_connections = 0;
+    config.max_connections = max_connections;
+    config.max_idle_connections = max_connections;
+    AsyncPool::new(config, Box::new(move || Box::pin(TcpStream::connect(addr)))).unwrap()
+}
+
+#[tokio::test]
+async fn test_async_get_reuses_connection() {
+    let addr = start_echo_server().await;
+    let pool = async_pool(addr, 2);
+
+    let id = {
+        let mut conn = pool.get().await.unwrap();
+        conn.write_all(b"ping").await.unwrap();
+        let mut buf = [0u8; 4];
+        conn.read_exact(&mut buf).await.unwrap();
+        assert_eq!(&buf, b"ping");
+        conn.id()
+    };
+    let conn = pool.try_get().await.unwrap();
+    assert_eq!(conn.id(), id);
+    assert_eq!(conn.reuse_count(), 1);
+    assert_eq!(pool.stats().total_connections_created, 1);
+}
+
+#[tokio::test]
+async fn test_async_get_waits_for_return() {
+    let addr = start_echo_server().await;
+    let pool = Arc::new(async_pool(addr, 1));
+
+    let held = pool.get().await.unwrap();
+    let err = pool.try_get().await.unwrap_err();
+    assert_eq!(err.wait_reason(), Some(WaitReason::WaitingForIdle));
+
+    let waiter = tokio::spawn({
+        let pool = pool.clone();
+        async move { pool.get_with_timeout(Duration::from_secs(5)).await }
+    });
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    let held_id = held.id();
+    drop(held);
+    assert_eq!(waiter.await.unwrap().unwrap().id(), held_id);
+}
+
+#[tokio::test]
+async fn test_async_cancelled_dial_releases_slot() {
+    let addr = start_echo_server().await;
+    let calls = Arc::new(AtomicUsize::new(0));
+    let mut config = default_config();
+    config.min_connections = 0;
+    config.max_connections = 1;
+    config.max_idle_connections = 1;
+    let pool = AsyncPool::new(
+        config,
+        Box::new({
+            let calls = calls.clone();
+            move || {
+                let first = calls.fetch_add(1, Ordering::SeqCst) == 0;
+                Box::pin(async move {
+                    if first {
+                        tokio::time::sleep(Duration::from_secs(3600)).await;
+                    }
+                    TcpStream::connect(addr).await
+                })
+            }
+        }),
+    )
+    .unwrap();
+
+    // 第一次建连被取消，名额必须归还，否则后续 get 会因池满失败
+    assert!(tokio::time::timeout(Duration::from_millis(50), pool.get())
+        .await
+        .is_err());
+    assert!(pool.try_get().await.is_ok());
+}
+
+#[tokio::test]
+async fn test_async_close_wakes_waiters() {
+    let addr = start_echo_server().await;
+    let pool = Arc::new(async_pool(addr, 1));
+
+    let _held = pool.get().await.unwrap();
+    let waiter = tokio::spawn({
+        let pool = pool.clone();
+        async move { pool.get_with_timeout(Duration::from_secs(5)).await }
+    });
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    pool.close();
+    assert!(matches!(
+        waiter.await.unwrap(),
+        Err(NetConnPoolError::PoolClosed)
+    ));
+}
+
+#[tokio::test]
+async fn test_async_ipv6_connection_reused() {
+    let listener = TcpListener::bind("[::1]:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        let mut held = Vec::new();
+        while let Ok((stream, _)) = listener.accept().await {
+            held.push(stream);
+        }
+    });
+    let pool = async_pool(addr, 1);
+
+    let id = pool.get().await.unwrap().id();
+    let conn = pool.try_get().await.unwrap();
+    assert_eq!(conn.id(), id);
+    assert_eq!(conn.ip_version(), IPVersion::IPv6);
+}
@@ -439,10 +439,7 @@ fn test_comprehensive_client_stress() {
     println!("    创建连接: {}", final_stats.total_connections_created);
     println!("    关闭连接: {}", final_stats.total_connections_closed);
     println!("    连接复用: {}", final_stats.total_connections_reused);
-    println!(
-        "    复用率: {:.2}%",
-        final_stats.average_reuse_count * 100.0
-    );
+    println!("    复用率: {:.2}%", final_stats.reuse_ratio * 100.0);
     println!("    TCP连接: {}", final_stats.current_tcp_connections);
     println!("    UDP连接: {}", final_stats.current_udp_connections);
     println!();
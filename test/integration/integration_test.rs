@@ -73,15 +73,9 @@ fn test_full_lifecycle() {
 
     let stats2 = pool.stats();
     println!("  总操作数: {}", stats2.successful_gets);
-    // average_reuse_count 是平均每个连接的复用次数，不是复用率
-    // 复用率 = total_connections_reused / successful_gets * 100%
-    let reuse_rate = if stats2.successful_gets > 0 {
-        stats2.total_connections_reused as f64 / stats2.successful_gets as f64 * 100.0
-    } else {
-        0.0
-    };
+    let reuse_rate = stats2.reuse_ratio * 100.0;
     println!("  连接复用率: {:.2}%", reuse_rate);
-    println!("  平均复用次数: {:.2}", stats2.average_reuse_count);
+    println!("  平均复用次数: {:.2}", stats2.avg_reuse_per_conn);
 
     // 阶段3: 高负载阶段
     println!("阶段3: 高负载阶段");
@@ -0,0 +1,3413 @@
+// Copyright (c) 2025, vistone
+// All rights reserved.
+
+// 连接池公开 API 行为测试
+// 覆盖获取语义、错误类型等需要真实连接参与的场景
+
+use netconnpool::config::default_config;
+use netconnpool::fd::{fd_limit, open_fd_count};
+use netconnpool::*;
+use std::io::{Read, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream, UdpSocket};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// 启动一个只负责 accept 并持有连接的 TCP 服务器
+fn start_tcp_server() -> SocketAddr {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    thread::spawn(move || {
+        let mut held = Vec::new();
+        for stream in listener.incoming().flatten() {
+            held.push(stream);
+        }
+    });
+    addr
+}
+
+fn tcp_config(addr: SocketAddr) -> Config {
+    let mut config = default_config();
+    config.min_connections = 0;
+    config.dialer = Some(Box::new(move |_| {
+        TcpStream::connect(addr)
+            .map(ConnectionType::Tcp)
+            .map_err(|e| Box::new(e) as Box<dyn std::error::Error + Send + Sync>)
+    }));
+    config
+}
+
+#[test]
+fn test_pool_full_zero_timeout_returns_exhausted() {
+    let addr = start_tcp_server();
+    let mut config = tcp_config(addr);
+    config.max_connections = 1;
+    config.max_idle_connections = 1;
+    let pool = Pool::new(config).unwrap();
+
+    let _held = pool.get().unwrap();
+    match pool.try_get() {
+        Err(NetConnPoolError::PoolExhausted {
+            current,
+            max,
+            waited,
+            wait_reason,
+            last_dial_error,
+        }) => {
+            assert!(last_dial_error.is_none());
+            assert_eq!(current, 1);
+            assert_eq!(wait_reason, WaitReason::WaitingForIdle);
+            assert_eq!(max, 1);
+            assert!(waited < Duration::from_millis(100));
+        }
+        other => panic!("期望 PoolExhausted，实际: {:?}", other.map(|c| c.id())),
+    }
+
+    let stats = pool.stats();
+    assert_eq!(stats.failed_gets, 1);
+    assert_eq!(stats.timeout_gets, 0);
+}
+
+#[test]
+fn test_pool_full_small_timeout_returns_exhausted() {
+    let addr = start_tcp_server();
+    let mut config = tcp_config(addr);
+    config.max_connections = 1;
+    config.max_idle_connections = 1;
+    let pool = Pool::new(config).unwrap();
+
+    let _held = pool.get().unwrap();
+    let start = Instant::now();
+    match pool.get_with_timeout(Duration::from_millis(50)) {
+        Err(NetConnPoolError::PoolExhausted { max, waited, .. }) => {
+            assert_eq!(max, 1);
+            assert!(waited >= Duration::from_millis(50));
+        }
+        other => panic!("期望 PoolExhausted，实际: {:?}", other.map(|c| c.id())),
+    }
+    assert!(start.elapsed() < Duration::from_secs(1));
+
+    let stats = pool.stats();
+    assert_eq!(stats.failed_gets, 1);
+    assert_eq!(stats.timeout_gets, 1);
+}
+
+#[test]
+fn test_get_times_out_while_discarding_idle_connections() {
+    let addr = start_tcp_server();
+    let dirty = Arc::new(AtomicBool::new(false));
+    let mut config = tcp_config(addr);
+    config.on_acquire = Some(Box::new({
+        let dirty = dirty.clone();
+        move |_| {
+            if dirty.load(Ordering::SeqCst) {
+                thread::sleep(Duration::from_millis(30));
+                return Err("连接残留上次的数据".into());
+            }
+            Ok(())
+        }
+    }));
+    let pool = Pool::new(config).unwrap();
+    drop((0..3).map(|_| pool.get().unwrap()).collect::<Vec<_>>());
+    dirty.store(true, Ordering::SeqCst);
+
+    // 每轮丢弃一条空闲连接都不等待，超过 timeout 后不再继续重试
+    match pool.get_with_timeout(Duration::from_millis(20)) {
+        Err(NetConnPoolError::GetConnectionTimeout { timeout, waited }) => {
+            assert_eq!(timeout, Duration::from_millis(20));
+            assert!(waited > timeout);
+        }
+        other => panic!(
+            "期望 GetConnectionTimeout，实际: {:?}",
+            other.map(|c| c.id())
+        ),
+    }
+    assert_eq!(pool.idle_count(), 2);
+
+    let stats = pool.stats();
+    assert_eq!(stats.failed_gets, 1);
+    assert_eq!(stats.timeout_gets, 1);
+}
+
+#[test]
+fn test_try_get_reuses_idle_connection() {
+    let addr = start_tcp_server();
+    let pool = Pool::new(tcp_config(addr)).unwrap();
+
+    let id = pool.get().unwrap().id();
+    let conn = pool.try_get().unwrap();
+    assert_eq!(conn.id(), id);
+}
+
+#[test]
+fn test_get_filtered_hits_exact_bucket() {
+    let tcp_v6 = TcpListener::bind("[::1]:0").unwrap();
+    let tcp_v6_addr = tcp_v6.local_addr().unwrap();
+    thread::spawn(move || {
+        let mut held = Vec::new();
+        for stream in tcp_v6.incoming().flatten() {
+            held.push(stream);
+        }
+    });
+    let udp_v4 = UdpSocket::bind("127.0.0.1:0").unwrap();
+    let udp_v4_addr = udp_v4.local_addr().unwrap();
+
+    let mut config = default_config();
+    config.min_connections = 0;
+    config.dialer = Some(Box::new(move |protocol| match protocol {
+        Some(Protocol::UDP) => {
+            let socket = UdpSocket::bind("127.0.0.1:0")?;
+            socket.connect(udp_v4_addr)?;
+            Ok(ConnectionType::Udp(socket))
+        }
+        _ => Ok(ConnectionType::Tcp(TcpStream::connect(tcp_v6_addr)?)),
+    }));
+    let pool = Pool::new(config).unwrap();
+    let timeout = Duration::from_secs(1);
+
+    let tcp = pool
+        .get_filtered(Some(Protocol::TCP), Some(IPVersion::IPv6), timeout)
+        .unwrap();
+    assert_eq!(tcp.protocol(), Protocol::TCP);
+    assert_eq!(tcp.ip_version(), IPVersion::IPv6);
+    let udp = pool
+        .get_filtered(Some(Protocol::UDP), Some(IPVersion::IPv4), timeout)
+        .unwrap();
+    assert_eq!(udp.protocol(), Protocol::UDP);
+    assert_eq!(udp.ip_version(), IPVersion::IPv4);
+    let (tcp_id, udp_id) = (tcp.id(), udp.id());
+    drop(tcp);
+    drop(udp);
+
+    // 归还后再次精确获取应命中对应桶中的同一条连接
+    let udp = pool
+        .get_filtered(Some(Protocol::UDP), Some(IPVersion::IPv4), timeout)
+        .unwrap();
+    assert_eq!(udp.id(), udp_id);
+    let tcp = pool
+        .get_filtered(Some(Protocol::TCP), Some(IPVersion::IPv6), timeout)
+        .unwrap();
+    assert_eq!(tcp.id(), tcp_id);
+
+    // 桶中没有 TCP/IPv4，新建的连接又是 IPv6，应报告 IP 版本不匹配
+    let err = pool
+        .get_filtered(Some(Protocol::TCP), Some(IPVersion::IPv4), timeout)
+        .unwrap_err();
+    assert!(matches!(
+        err,
+        NetConnPoolError::NoConnectionForIPVersion { .. }
+    ));
+}
+
+#[test]
+fn test_happy_eyeballs_dialer_in_pool() {
+    let addr = start_tcp_server();
+    let mut config = default_config();
+    config.min_connections = 0;
+    config.dialer = Some(happy_eyeballs_dialer(
+        "127.0.0.1",
+        addr.port(),
+        Duration::from_millis(50),
+        Duration::from_secs(5),
+    ));
+    let pool = Pool::new(config).unwrap();
+
+    let conn = pool.get().unwrap();
+    assert_eq!(conn.protocol(), Protocol::TCP);
+    assert_eq!(conn.ip_version(), IPVersion::IPv4);
+    let stats = pool.stats();
+    assert_eq!(stats.current_ipv4_connections, 1);
+    assert_eq!(stats.current_tcp_connections, 1);
+
+    // 该 Dialer 只支持 TCP
+    assert!(pool
+        .get_with_protocol(Protocol::UDP, Duration::from_millis(100))
+        .is_err());
+}
+
+#[test]
+fn test_tcp_nodelay_and_keepalive_applied() {
+    let addr = start_tcp_server();
+    let mut config = tcp_config(addr);
+    config.tcp_nodelay = true;
+    config.tcp_keepalive = Some(Duration::from_secs(30));
+    let pool = Pool::new(config).unwrap();
+
+    let conn = pool.get().unwrap();
+    let stream = conn.tcp_conn().unwrap();
+    assert!(stream.nodelay().unwrap());
+    assert!(socket2::SockRef::from(stream).keepalive().unwrap());
+
+    // 未开启时保持系统默认（Nagle 启用）
+    let pool = Pool::new(tcp_config(addr)).unwrap();
+    let conn = pool.get().unwrap();
+    assert!(!conn.tcp_conn().unwrap().nodelay().unwrap());
+}
+
+#[test]
+fn test_health_check_failure_reason_recorded() {
+    let addr = start_tcp_server();
+    let reasons = Arc::new(Mutex::new(Vec::new()));
+    let mut config = tcp_config(addr);
+    config.health_check_interval = Duration::from_millis(50);
+    config.health_checker_with_reason = Some(Box::new(|_| Err("ping failed".to_string())));
+    config.on_health_check_failure = Some(Box::new({
+        let reasons = reasons.clone();
+        move |_, reason| reasons.lock().unwrap().push(reason.to_string())
+    }));
+    let pool = Pool::new(config).unwrap();
+
+    drop(pool.get().unwrap());
+    thread::sleep(Duration::from_millis(300));
+
+    let stats = pool.stats();
+    assert!(stats.health_check_failures >= 1);
+    assert_eq!(
+        stats
+            .health_check_failure_reasons
+            .get("ping failed")
+            .copied(),
+        Some(stats.health_check_failures)
+    );
+    assert!(reasons.lock().unwrap().iter().all(|r| r == "ping failed"));
+    assert_eq!(stats.current_connections, 0);
+}
+
+#[test]
+fn test_parallel_health_check_respects_concurrency() {
+    let addr = start_tcp_server();
+    let in_flight = Arc::new(AtomicUsize::new(0));
+    let max_in_flight = Arc::new(AtomicUsize::new(0));
+    let mut config = tcp_config(addr);
+    config.max_connections = 16;
+    config.max_idle_connections = 16;
+    config.health_check_interval = Duration::from_millis(100);
+    config.health_check_concurrency = 4;
+    config.health_checker = Some(Box::new({
+        let in_flight = in_flight.clone();
+        let max_in_flight = max_in_flight.clone();
+        move |_| {
+            let now = in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+            max_in_flight.fetch_max(now, Ordering::SeqCst);
+            thread::sleep(Duration::from_millis(10));
+            in_flight.fetch_sub(1, Ordering::SeqCst);
+            true
+        }
+    }));
+    let pool = Pool::new(config).unwrap();
+
+    let conns: Vec<_> = (0..16).map(|_| pool.get().unwrap()).collect();
+    drop(conns);
+    thread::sleep(Duration::from_millis(350));
+
+    let stats = pool.stats();
+    assert!(stats.health_check_attempts >= 16);
+    assert_eq!(stats.health_check_failures, 0);
+    let max = max_in_flight.load(Ordering::SeqCst);
+    assert!(max > 1 && max <= 4, "并行度异常: {}", max);
+}
+
+#[test]
+fn test_lifetime_jitter_spreads_expiry() {
+    let addr = start_tcp_server();
+    let max_lifetime = Duration::from_secs(1000);
+    let mut config = tcp_config(addr);
+    config.max_connections = 20;
+    config.max_idle_connections = 20;
+    config.max_lifetime = max_lifetime;
+    config.lifetime_jitter = 0.2;
+    let pool = Pool::new(config).unwrap();
+
+    // 同一时刻建立的一批连接
+    let conns: Vec<_> = (0..20).map(|_| pool.get().unwrap()).collect();
+    let lifetimes: Vec<Duration> = conns
+        .iter()
+        .map(|c| c.effective_max_lifetime(max_lifetime))
+        .collect();
+    let min = *lifetimes.iter().min().unwrap();
+    let max = *lifetimes.iter().max().unwrap();
+    assert!(min >= Duration::from_secs(800), "最小寿命越界: {:?}", min);
+    assert!(max <= Duration::from_secs(1200), "最大寿命越界: {:?}", max);
+    assert!(
+        max - min >= Duration::from_secs(100),
+        "过期时间过于集中: {:?}..{:?}",
+        min,
+        max
+    );
+
+    // 抖动比例非法时拒绝配置
+    let mut config = tcp_config(addr);
+    config.lifetime_jitter = 1.5;
+    assert!(Pool::new(config).is_err());
+}
+
+#[test]
+fn test_set_max_idle_connections_shrinks_idle() {
+    let addr = start_tcp_server();
+    let mut config = tcp_config(addr);
+    config.max_connections = 10;
+    config.max_idle_connections = 10;
+    let pool = Pool::new(config).unwrap();
+
+    let conns: Vec<_> = (0..8).map(|_| pool.get().unwrap()).collect();
+    drop(conns);
+    assert_eq!(pool.stats().current_idle_connections, 8);
+
+    pool.set_max_idle_connections(3).unwrap();
+    assert_eq!(pool.max_idle_connections(), 3);
+    assert_eq!(pool.stats().current_idle_connections, 3);
+    assert_eq!(pool.idle_count(), 3);
+    assert_eq!(pool.stats().current_connections, 3);
+
+    // 新上限同样作用于后续归还
+    let conns: Vec<_> = (0..6).map(|_| pool.get().unwrap()).collect();
+    drop(conns);
+    assert_eq!(pool.stats().current_idle_connections, 3);
+
+    assert!(pool.set_max_idle_connections(0).is_err());
+    assert!(pool.set_max_idle_connections(11).is_err());
+}
+
+#[test]
+fn test_get_where_filters_idle_connections() {
+    let addr = start_tcp_server();
+    let pool = Pool::new(tcp_config(addr)).unwrap();
+    let even_reuse = |c: &Connection| c.reuse_count() % 2 == 0;
+    let timeout = Duration::from_secs(1);
+
+    let a = pool.get().unwrap().id();
+    // a 的 reuse_count 为 0，满足断言
+    let conn = pool.get_where(even_reuse, timeout).unwrap();
+    assert_eq!(conn.id(), a);
+    assert_eq!(conn.reuse_count(), 1);
+    drop(conn);
+
+    // a 的 reuse_count 为 1，被拒绝并放回，改为新建连接 b
+    let conn = pool.get_where(even_reuse, timeout).unwrap();
+    let b = conn.id();
+    assert_ne!(b, a);
+    drop(conn);
+    assert_eq!(pool.idle_count(), 2);
+
+    // 空闲池中只有 b 满足断言
+    let conn = pool.get_where(even_reuse, timeout).unwrap();
+    assert_eq!(conn.id(), b);
+    assert_eq!(pool.idle_count(), 1);
+    assert_eq!(pool.stats().current_connections, 2);
+}
+
+#[test]
+fn test_get_where_does_not_filter_new_connections() {
+    let addr = start_tcp_server();
+    let pool = Pool::new(tcp_config(addr)).unwrap();
+    let a = pool.get().unwrap().id();
+
+    // 空闲连接被拒绝后现场建连，新连接不经过断言
+    let conn = pool.get_where(|_| false, Duration::from_secs(1)).unwrap();
+    assert_ne!(conn.id(), a);
+    assert_eq!(conn.reuse_count(), 0);
+    assert_eq!(pool.idle_count(), 1);
+}
+
+#[test]
+fn test_replace_evicted_connection_in_same_cycle() {
+    let addr = start_tcp_server();
+    let failed_once = Arc::new(AtomicBool::new(false));
+    let mut config = tcp_config(addr);
+    config.health_check_interval = Duration::from_millis(100);
+    config.replace_evicted_connections = true;
+    config.health_checker = Some(Box::new({
+        let failed_once = failed_once.clone();
+        move |_| failed_once.swap(true, Ordering::SeqCst)
+    }));
+    let pool = Pool::new(config).unwrap();
+
+    let first_id = {
+        let conn = pool.get().unwrap();
+        conn.id()
+    };
+    thread::sleep(Duration::from_millis(250));
+
+    let stats = pool.stats();
+    assert_eq!(stats.health_check_failures, 1);
+    assert_eq!(stats.total_connections_created, 2);
+    assert_eq!(stats.current_connections, 1);
+
+    // 补建的连接已在空闲队列中，直接复用而无需现场建连
+    let conn = pool.try_get().unwrap();
+    assert_ne!(conn.id(), first_id);
+    assert_eq!(pool.stats().total_connections_created, 2);
+}
+
+#[test]
+fn test_slow_replacement_dial_does_not_block_reaper() {
+    let addr = start_tcp_server();
+    let dials = Arc::new(AtomicUsize::new(0));
+    let checks = Arc::new(AtomicUsize::new(0));
+    let mut config = tcp_config(addr);
+    config.min_connections = 2;
+    config.health_check_interval = Duration::from_millis(50);
+    config.replace_evicted_connections = true;
+    config.dialer = Some(Box::new({
+        let dials = dials.clone();
+        move |_| {
+            // 预热之后的补建拨号很慢
+            if dials.fetch_add(1, Ordering::SeqCst) >= 2 {
+                thread::sleep(Duration::from_secs(1));
+            }
+            Ok(ConnectionType::Tcp(TcpStream::connect(addr)?))
+        }
+    }));
+    config.health_checker = Some(Box::new({
+        let checks = checks.clone();
+        move |_| checks.fetch_add(1, Ordering::SeqCst) != 0
+    }));
+    let pool = Pool::new(config).unwrap();
+
+    // 补建拨号进行期间，reaper 仍按周期继续做健康检查
+    thread::sleep(Duration::from_millis(500));
+    assert!(dials.load(Ordering::SeqCst) >= 3);
+    assert!(checks.load(Ordering::SeqCst) >= 4);
+    assert_eq!(pool.stats().health_check_failures, 1);
+}
+
+#[test]
+fn test_dial_error_retryable_classification() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let closed_addr = listener.local_addr().unwrap();
+    drop(listener);
+
+    let refused = Pool::new(tcp_config(closed_addr)).unwrap();
+    match refused.try_get() {
+        Err(err @ NetConnPoolError::DialFailed { .. }) => assert!(err.is_retryable()),
+        other => panic!("期望 DialFailed，实际: {:?}", other.map(|c| c.id())),
+    }
+
+    let mut config = tcp_config(closed_addr);
+    config.dialer = Some(Box::new(|_| Err("证书配置无效".into())));
+    let permanent = Pool::new(config).unwrap();
+    match permanent.try_get() {
+        Err(err @ NetConnPoolError::DialFailed { .. }) => {
+            assert!(!err.is_retryable());
+            assert!(err.to_string().contains("证书配置无效"));
+        }
+        other => panic!("期望 DialFailed，实际: {:?}", other.map(|c| c.id())),
+    }
+}
+
+#[test]
+fn test_per_protocol_limit_keeps_tcp_available() {
+    let tcp_addr = start_tcp_server();
+    let udp_peer = UdpSocket::bind("127.0.0.1:0").unwrap();
+    let udp_addr = udp_peer.local_addr().unwrap();
+
+    let mut config = tcp_config(tcp_addr);
+    config.max_connections = 6;
+    config.max_idle_connections = 6;
+    config.max_udp_connections = 4;
+    config.dialer = Some(Box::new(move |protocol| match protocol {
+        Some(Protocol::UDP) => {
+            let socket = UdpSocket::bind("127.0.0.1:0")?;
+            socket.connect(udp_addr)?;
+            Ok(ConnectionType::Udp(socket))
+        }
+        _ => Ok(ConnectionType::Tcp(TcpStream::connect(tcp_addr)?)),
+    }));
+    let pool = Pool::new(config).unwrap();
+    let timeout = Duration::from_millis(50);
+
+    let udp_conns: Vec<_> = (0..4)
+        .map(|_| pool.get_with_protocol(Protocol::UDP, timeout).unwrap())
+        .collect();
+    assert!(matches!(
+        pool.get_with_protocol(Protocol::UDP, timeout),
+        Err(NetConnPoolError::PoolExhausted {
+            current: 4,
+            max: 4,
+            ..
+        })
+    ));
+
+    // UDP 打满后 TCP 仍可使用剩余的全局额度
+    let tcp_conns: Vec<_> = (0..2)
+        .map(|_| pool.get_with_protocol(Protocol::TCP, timeout).unwrap())
+        .collect();
+    assert!(tcp_conns.iter().all(|c| c.protocol() == Protocol::TCP));
+    assert_eq!(udp_conns.len() + tcp_conns.len(), 6);
+}
+
+#[test]
+fn test_create_rate_limit_bounds_new_connections() {
+    let addr = start_tcp_server();
+    let mut config = tcp_config(addr);
+    config.max_connections = 100;
+    config.max_idle_connections = 100;
+    config.max_new_connections_per_sec = 10;
+    let pool = Arc::new(Pool::new(config).unwrap());
+
+    let start = Instant::now();
+    let handles: Vec<_> = (0..50)
+        .map(|_| {
+            let pool = pool.clone();
+            thread::spawn(move || {
+                let conn = pool.get_with_timeout(Duration::from_secs(5)).unwrap();
+                thread::sleep(Duration::from_millis(20));
+                drop(conn);
+            })
+        })
+        .collect();
+    for handle in handles {
+        handle.join().unwrap();
+    }
+    let elapsed = start.elapsed().as_secs_f64();
+
+    // 令牌桶初始满桶（10 个）+ 每秒补充 10 个
+    let created = pool.stats().total_connections_created;
+    assert!(created >= 1);
+    assert!(
+        created as f64 <= 10.0 + 10.0 * elapsed + 1.0,
+        "{} 秒内新建了 {} 条连接",
+        elapsed,
+        created
+    );
+    assert_eq!(pool.stats().successful_gets, 50);
+}
+
+#[test]
+fn test_exhausted_error_carries_wait_reason() {
+    let tcp_addr = start_tcp_server();
+    let udp_peer = UdpSocket::bind("127.0.0.1:0").unwrap();
+    let udp_addr = udp_peer.local_addr().unwrap();
+    let mut config = tcp_config(tcp_addr);
+    config.max_connections = 1;
+    config.max_idle_connections = 1;
+    config.dialer = Some(Box::new(move |protocol| match protocol {
+        Some(Protocol::UDP) => {
+            let socket = UdpSocket::bind("127.0.0.1:0")?;
+            socket.connect(udp_addr)?;
+            Ok(ConnectionType::Udp(socket))
+        }
+        _ => Ok(ConnectionType::Tcp(TcpStream::connect(tcp_addr)?)),
+    }));
+    let pool = Pool::new(config).unwrap();
+    let timeout = Duration::from_millis(20);
+
+    // 唯一的连接被借出：等待归还
+    let held = pool.get_tcp().unwrap();
+    let err = pool.get_with_protocol(Protocol::TCP, timeout).unwrap_err();
+    assert_eq!(err.wait_reason(), Some(WaitReason::WaitingForIdle));
+
+    // 空闲的是 TCP 连接，但请求 UDP：等待建连额度
+    drop(held);
+    let err = pool.get_with_protocol(Protocol::UDP, timeout).unwrap_err();
+    assert_eq!(err.wait_reason(), Some(WaitReason::WaitingForCapacity));
+}
+
+#[test]
+fn test_rate_limited_error_carries_dialer_backoff() {
+    let addr = start_tcp_server();
+    let mut config = tcp_config(addr);
+    config.max_new_connections_per_sec = 1;
+    let pool = Pool::new(config).unwrap();
+
+    let _held = pool.get().unwrap();
+    let err = pool
+        .get_with_timeout(Duration::from_millis(20))
+        .unwrap_err();
+    assert!(matches!(err, NetConnPoolError::CreateRateLimited { .. }));
+    assert_eq!(err.wait_reason(), Some(WaitReason::DialerBackoff));
+}
+
+#[test]
+fn test_pool_full_gets_do_not_consume_rate_limit_tokens() {
+    let addr = start_tcp_server();
+    let mut config = tcp_config(addr);
+    config.max_connections = 1;
+    config.max_new_connections_per_sec = 2;
+    let pool = Pool::new(config).unwrap();
+
+    // 唯一的额度被占用：池满的 get 不调用 dialer，也不应消耗令牌
+    let held = pool.get().unwrap();
+    for _ in 0..3 {
+        assert!(matches!(
+            pool.try_get(),
+            Err(NetConnPoolError::PoolExhausted { .. })
+        ));
+    }
+
+    // 连接作废后需要新建：桶里仍剩一个令牌
+    held.mark_unhealthy();
+    drop(held);
+    let conn = pool.try_get().unwrap();
+    assert_eq!(conn.reuse_count(), 0);
+    assert_eq!(pool.stats().total_connections_created, 2);
+}
+
+#[test]
+fn test_prewarm_is_rate_limited() {
+    let addr = start_tcp_server();
+    let mut config = tcp_config(addr);
+    config.min_connections = 6;
+    config.max_new_connections_per_sec = 2;
+    let pool = Pool::new(config).unwrap();
+
+    // 满桶只够 2 次建连，之后每 500ms 补充一个令牌
+    thread::sleep(Duration::from_millis(200));
+    assert!(pool.stats().total_connections_created <= 2);
+
+    // 预热等待令牌补充而不是放弃，最终补足 min_connections
+    let deadline = Instant::now() + Duration::from_secs(10);
+    while pool.idle_count() < 6 && Instant::now() < deadline {
+        thread::sleep(Duration::from_millis(50));
+    }
+    assert_eq!(pool.idle_count(), 6);
+}
+
+#[test]
+fn test_for_each_idle_visits_idle_connections() {
+    let addr = start_tcp_server();
+    let pool = Pool::new(tcp_config(addr)).unwrap();
+
+    let conns: Vec<_> = (0..3).map(|_| pool.get().unwrap()).collect();
+    let held = pool.get().unwrap();
+    drop(conns);
+
+    let mut visited = Vec::new();
+    pool.for_each_idle(|conn| visited.push(conn.id()));
+    assert_eq!(visited.len(), pool.idle_count());
+    assert_eq!(visited.len(), 3);
+    assert!(!visited.contains(&held.id()));
+    // 遍历不借出连接
+    assert_eq!(pool.active_count(), 1);
+}
+
+#[test]
+fn test_reset_stats_starts_new_interval() {
+    let addr = start_tcp_server();
+    let pool = Pool::new(tcp_config(addr)).unwrap();
+
+    for _ in 0..5 {
+        drop(pool.get().unwrap());
+    }
+    let held = pool.get().unwrap();
+    assert_eq!(pool.stats().successful_gets, 6);
+
+    pool.reset_stats();
+    for _ in 0..3 {
+        drop(pool.try_get().unwrap());
+    }
+
+    let stats = pool.stats();
+    assert_eq!(stats.total_get_requests, 3);
+    assert_eq!(stats.successful_gets, 3);
+    // held 占用了唯一的空闲连接：第一次新建，其余两次复用
+    assert_eq!(stats.total_connections_created, 1);
+    assert_eq!(stats.total_connections_reused, 2);
+    // 即时量不受 reset 影响
+    assert_eq!(stats.current_connections, 2);
+    assert_eq!(stats.current_active_connections, 1);
+    drop(held);
+}
+
+#[test]
+fn test_health_reports_capacity_exhaustion() {
+    let addr = start_tcp_server();
+    let mut config = tcp_config(addr);
+    config.max_connections = 1;
+    config.max_idle_connections = 1;
+    config.health_window = Duration::from_millis(100);
+    config.reaper_interval = Some(Duration::from_millis(10));
+    let pool = Pool::new(config).unwrap();
+    assert!(pool.health().is_healthy());
+
+    let held = pool.get().unwrap();
+    assert!(pool.try_get().is_err());
+    // health() 只读：多个调用方看到同一窗口
+    for _ in 0..2 {
+        let health = pool.health();
+        assert_eq!(health.status, HealthStatus::Degraded);
+        assert!(health
+            .issues
+            .contains(&HealthIssue::CapacityExhausted { count: 1 }));
+    }
+
+    // reaper 推进两个周期后窗口内不再有耗尽，恢复健康
+    drop(held);
+    drop(pool.get().unwrap());
+    thread::sleep(Duration::from_millis(350));
+    assert!(pool.health().is_healthy());
+
+    pool.close().unwrap();
+    assert_eq!(pool.health().status, HealthStatus::Unhealthy);
+}
+
+#[test]
+fn test_pooled_connection_read_write_passthrough() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    thread::spawn(move || {
+        for mut stream in listener.incoming().flatten() {
+            thread::spawn(move || {
+                let mut buf = [0u8; 1024];
+                while let Ok(n) = stream.read(&mut buf) {
+                    if n == 0 || stream.write_all(&buf[..n]).is_err() {
+                        break;
+                    }
+                }
+            });
+        }
+    });
+    let pool = Pool::new(tcp_config(addr)).unwrap();
+
+    let mut conn = pool.get().unwrap();
+    conn.write_all(b"hello pool").unwrap();
+    conn.flush().unwrap();
+    let mut buf = [0u8; 10];
+    conn.read_exact(&mut buf).unwrap();
+    assert_eq!(&buf, b"hello pool");
+}
+
+#[test]
+fn test_pooled_connection_read_write_rejects_udp() {
+    let peer = UdpSocket::bind("127.0.0.1:0").unwrap();
+    let peer_addr = peer.local_addr().unwrap();
+    let mut config = default_config();
+    config.min_connections = 0;
+    config.dialer = Some(Box::new(move |_| {
+        let socket = UdpSocket::bind("127.0.0.1:0")?;
+        socket.connect(peer_addr)?;
+        Ok(ConnectionType::Udp(socket))
+    }));
+    let pool = Pool::new(config).unwrap();
+
+    let mut conn = pool.get().unwrap();
+    let err = conn.write(b"x").unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::Unsupported);
+}
+
+#[test]
+fn test_on_acquire_discards_dirty_connections() {
+    let addr = start_tcp_server();
+    let dirty = Arc::new(Mutex::new(Vec::<SocketAddr>::new()));
+    let mut config = tcp_config(addr);
+    config.on_acquire = Some(Box::new({
+        let dirty = dirty.clone();
+        move |conn| {
+            let local = conn.local_addr()?;
+            if dirty.lock().unwrap().contains(&local) {
+                return Err("连接残留上次的数据".into());
+            }
+            Ok(())
+        }
+    }));
+    let pool = Pool::new(config).unwrap();
+
+    let conns: Vec<_> = (0..3).map(|_| pool.get().unwrap()).collect();
+    for conn in &conns[..2] {
+        let local = conn.tcp_conn().unwrap().local_addr().unwrap();
+        dirty.lock().unwrap().push(local);
+    }
+    drop(conns);
+
+    let clean: Vec<_> = (0..3).map(|_| pool.get().unwrap()).collect();
+    for conn in &clean {
+        let local = conn.tcp_conn().unwrap().local_addr().unwrap();
+        assert!(!dirty.lock().unwrap().contains(&local));
+    }
+    let stats = pool.stats();
+    assert_eq!(stats.total_connections_created, 5);
+    assert_eq!(stats.total_connections_closed, 2);
+}
+
+#[test]
+fn test_zero_lifetime_and_idle_timeout_mean_unlimited() {
+    let addr = start_tcp_server();
+    let mut config = tcp_config(addr);
+    config.max_lifetime = Duration::ZERO;
+    config.idle_timeout = Duration::from_secs(60);
+    assert!(config.validate().is_ok());
+    config.idle_timeout = Duration::ZERO;
+    config.health_check_interval = Duration::from_millis(20);
+    config.health_check_timeout = Duration::from_millis(10);
+    let pool = Pool::new(config).unwrap();
+
+    let id = pool.get().unwrap().id();
+    thread::sleep(Duration::from_millis(100));
+    assert_eq!(pool.try_get().unwrap().id(), id);
+    assert_eq!(pool.stats().total_connections_closed, 0);
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn test_socket_options_applied_on_create() {
+    let addr = start_tcp_server();
+    let mut config = tcp_config(addr);
+    config.socket_options = SocketOptions {
+        recv_buffer_size: Some(32 * 1024),
+        linger: Some(Duration::from_secs(1)),
+        ..SocketOptions::default()
+    };
+    let pool = Pool::new(config).unwrap();
+
+    let conn = pool.get().unwrap();
+    let socket = socket2::SockRef::from(conn.tcp_conn().unwrap());
+    // Linux 会把设置的 SO_RCVBUF 翻倍以容纳内核簿记开销
+    assert_eq!(socket.recv_buffer_size().unwrap(), 2 * 32 * 1024);
+    assert_eq!(socket.linger().unwrap(), Some(Duration::from_secs(1)));
+}
+
+#[test]
+fn test_fail_fast_on_new_validates_dialer() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let closed_addr = listener.local_addr().unwrap();
+    drop(listener);
+
+    let mut config = tcp_config(closed_addr);
+    config.fail_fast_on_new = true;
+    match Pool::new(config) {
+        Err(err @ NetConnPoolError::DialFailed { .. }) => assert!(err.is_retryable()),
+        other => panic!("期望 DialFailed，实际: {:?}", other.map(|p| p.stats())),
+    }
+
+    // 默认关闭时仍然允许创建
+    assert!(Pool::new(tcp_config(closed_addr)).is_ok());
+
+    let mut config = tcp_config(start_tcp_server());
+    config.fail_fast_on_new = true;
+    let pool = Pool::new(config).unwrap();
+    let stats = pool.stats();
+    assert_eq!(stats.total_connections_created, 1);
+    assert_eq!(stats.current_idle_connections, 1);
+}
+
+#[test]
+fn test_force_reclaim_on_leak_returns_capacity() {
+    let mut config = tcp_config(start_tcp_server());
+    config.max_connections = 1;
+    config.max_idle_connections = 1;
+    config.connection_leak_timeout = Duration::from_millis(200);
+    config.health_check_interval = Duration::from_millis(50);
+    config.force_reclaim_on_leak = true;
+    let pool = Pool::new(config).unwrap();
+
+    let mut held = pool.get().unwrap();
+    let held_id = held.id();
+
+    // 持有者不归还：严格模式下 reaper 超过 leak_timeout 后强制回收，容量归还给等待者
+    let conn = pool.get_with_timeout(Duration::from_secs(5)).unwrap();
+    assert_ne!(conn.id(), held_id);
+
+    // 底层 socket 已关闭，持有者的 IO 报错
+    assert!(held.write_all(b"ping").is_err());
+
+    drop(held);
+    drop(conn);
+    let stats = pool.stats();
+    assert_eq!(stats.total_connections_closed, 1);
+    assert_eq!(stats.current_connections, 1);
+    assert_eq!(stats.leaked_connections, 1);
+}
+
+#[test]
+fn test_bucket_stats_match_connection_distribution() {
+    let tcp_v4 = start_tcp_server();
+    let listener_v6 = TcpListener::bind("[::1]:0").unwrap();
+    let tcp_v6 = listener_v6.local_addr().unwrap();
+    thread::spawn(move || {
+        let mut held = Vec::new();
+        for stream in listener_v6.incoming().flatten() {
+            held.push(stream);
+        }
+    });
+    let udp_peer = UdpSocket::bind("127.0.0.1:0").unwrap();
+    let udp_addr = udp_peer.local_addr().unwrap();
+
+    // TCP 依次拨 IPv4、IPv6
+    let tcp_dials = Arc::new(AtomicUsize::new(0));
+    let mut config = tcp_config(tcp_v4);
+    config.dialer = Some(Box::new(move |protocol| match protocol {
+        Some(Protocol::UDP) => {
+            let socket = UdpSocket::bind("127.0.0.1:0")?;
+            socket.connect(udp_addr)?;
+            Ok(ConnectionType::Udp(socket))
+        }
+        _ => {
+            let addr = if tcp_dials.fetch_add(1, Ordering::SeqCst) == 0 {
+                tcp_v4
+            } else {
+                tcp_v6
+            };
+            Ok(ConnectionType::Tcp(TcpStream::connect(addr)?))
+        }
+    }));
+    let pool = Pool::new(config).unwrap();
+
+    let tcp4_conn = pool.get_tcp().unwrap();
+    let tcp6 = pool.get_tcp().unwrap();
+    assert_eq!(tcp6.ip_version(), IPVersion::IPv6);
+    drop(tcp6);
+    let udp4_conn = pool.get_udp().unwrap();
+
+    let buckets = pool.bucket_stats();
+    let find = |protocol, ip_version| {
+        *buckets
+            .iter()
+            .find(|b| b.protocol == protocol && b.ip_version == ip_version)
+            .unwrap()
+    };
+    let tcp4 = find(Protocol::TCP, IPVersion::IPv4);
+    assert_eq!((tcp4.idle, tcp4.active), (0, 1));
+    let tcp6 = find(Protocol::TCP, IPVersion::IPv6);
+    assert_eq!((tcp6.idle, tcp6.active), (1, 0));
+    let udp4 = find(Protocol::UDP, IPVersion::IPv4);
+    assert_eq!((udp4.idle, udp4.active), (0, 1));
+    let udp6 = find(Protocol::UDP, IPVersion::IPv6);
+    assert_eq!((udp6.idle, udp6.active), (0, 0));
+
+    // 归还后借出计数随之回落
+    drop((tcp4_conn, udp4_conn));
+    let buckets = pool.bucket_stats();
+    assert!(buckets.iter().all(|b| b.active == 0));
+    assert_eq!(buckets.iter().map(|b| b.idle).sum::<usize>(), 3);
+}
+
+#[test]
+fn test_concurrent_creation_never_exceeds_max_connections() {
+    let peer = UdpSocket::bind("127.0.0.1:0").unwrap();
+    let peer_addr = peer.local_addr().unwrap();
+
+    let mut config = default_config();
+    config.min_connections = 0;
+    config.max_connections = 50;
+    config.max_idle_connections = 50;
+    config.get_connection_timeout = Duration::ZERO;
+    config.dialer = Some(Box::new(move |_| {
+        let socket = UdpSocket::bind("127.0.0.1:0")?;
+        socket.connect(peer_addr)?;
+        Ok(ConnectionType::Udp(socket))
+    }));
+    let pool = Arc::new(Pool::new(config).unwrap());
+
+    let handles: Vec<_> = (0..8)
+        .map(|_| {
+            let pool = pool.clone();
+            thread::spawn(move || (0..20).filter_map(|_| pool.get().ok()).collect::<Vec<_>>())
+        })
+        .collect();
+    let held: Vec<_> = handles
+        .into_iter()
+        .flat_map(|h| h.join().unwrap())
+        .collect();
+
+    assert_eq!(held.len(), 50);
+    assert_eq!(pool.stats().current_connections, 50);
+    drop(held);
+    assert_eq!(pool.idle_count(), 50);
+}
+
+#[test]
+fn test_pool_debug_shows_runtime_state() {
+    let mut config = tcp_config(start_tcp_server());
+    config.max_connections = 3;
+    config.enable_stats = false;
+    let pool = Pool::new(config).unwrap();
+    let _held = pool.get().unwrap();
+    drop(pool.get().unwrap());
+
+    let output = format!("{:?}", pool);
+    for field in [
+        "mode: Client",
+        "max_connections: 3",
+        "min_connections: 0",
+        "total_count: 2",
+        "active_count: 1",
+        "idle_count: 1",
+        "closed: false",
+        "enable_stats: false",
+    ] {
+        assert!(output.contains(field), "缺少 {}: {}", field, output);
+    }
+    assert!(!output.contains("dialer"));
+}
+
+#[test]
+fn test_get_retry_recovers_from_transient_dial_failures() {
+    let addr = start_tcp_server();
+    let dials = Arc::new(AtomicUsize::new(0));
+    let mut config = tcp_config(addr);
+    config.dialer = Some(Box::new({
+        let dials = dials.clone();
+        move |_| {
+            if dials.fetch_add(1, Ordering::SeqCst) < 2 {
+                return Err(std::io::Error::from(std::io::ErrorKind::ConnectionRefused).into());
+            }
+            Ok(ConnectionType::Tcp(TcpStream::connect(addr)?))
+        }
+    }));
+    let pool = Pool::new(config).unwrap();
+
+    let conn = pool
+        .get_retry(Duration::from_secs(5), 5, Duration::from_millis(10))
+        .unwrap();
+    assert_eq!(dials.load(Ordering::SeqCst), 3);
+    drop(conn);
+
+    // 尝试次数用尽时返回最后一次错误
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let closed_addr = listener.local_addr().unwrap();
+    drop(listener);
+    let refused_dials = Arc::new(AtomicUsize::new(0));
+    let mut config = tcp_config(closed_addr);
+    config.dialer = Some(Box::new({
+        let refused_dials = refused_dials.clone();
+        move |_| {
+            refused_dials.fetch_add(1, Ordering::SeqCst);
+            Ok(ConnectionType::Tcp(TcpStream::connect(closed_addr)?))
+        }
+    }));
+    let refused = Pool::new(config).unwrap();
+    match refused.get_retry(Duration::from_secs(5), 3, Duration::from_millis(10)) {
+        Err(err @ NetConnPoolError::DialFailed { .. }) => assert!(err.is_retryable()),
+        other => panic!("期望 DialFailed，实际: {:?}", other.map(|c| c.id())),
+    }
+    assert_eq!(refused_dials.load(Ordering::SeqCst), 3);
+
+    // 不可重试错误立即返回
+    pool.close().unwrap();
+    let start = Instant::now();
+    assert!(matches!(
+        pool.get_retry(Duration::from_secs(5), 5, Duration::from_secs(1)),
+        Err(NetConnPoolError::PoolClosed)
+    ));
+    assert!(start.elapsed() < Duration::from_secs(1));
+}
+
+#[test]
+fn test_labeled_dialer_label_survives_reuse() {
+    let addr = start_tcp_server();
+    let mut config = tcp_config(addr);
+    config.dialer = None;
+    config.labeled_dialer = Some(Box::new(move |_| {
+        let stream = TcpStream::connect(addr)?;
+        Ok((
+            ConnectionType::Tcp(stream),
+            Some(format!("backend-{}", addr)),
+        ))
+    }));
+    let pool = Pool::new(config).unwrap();
+    let expected = format!("backend-{}", addr);
+
+    let conn = pool.get().unwrap();
+    let id = conn.id();
+    assert_eq!(conn.label(), Some(expected.as_str()));
+    assert!(format!("{:?}", *conn).contains(&expected));
+    drop(conn);
+
+    let mut idle_labels = Vec::new();
+    pool.for_each_idle(|c| idle_labels.push(c.label().map(str::to_string)));
+    assert_eq!(idle_labels, vec![Some(expected.clone())]);
+
+    let reused = pool.get().unwrap();
+    assert_eq!(reused.id(), id);
+    assert_eq!(reused.label(), Some(expected.as_str()));
+}
+
+#[test]
+fn test_failing_close_conn_still_closes_socket() {
+    let mut config = tcp_config(start_tcp_server());
+    config.close_conn = Some(Box::new(|_| Err("close 回调失败".into())));
+    let pool = Pool::new(config).unwrap();
+
+    let conn = pool.get().unwrap();
+    let mut raw = conn.tcp_conn().unwrap().try_clone().unwrap();
+    drop(conn);
+    pool.close().unwrap();
+
+    // close_conn 失败后仍执行了默认 shutdown，后续写入失败
+    assert!(raw.write_all(b"ping").is_err());
+    let stats = pool.stats();
+    assert_eq!(stats.close_callback_failures, 1);
+    assert_eq!(stats.close_failures, 0);
+}
+
+#[test]
+fn test_default_read_timeout_applied_and_restored() {
+    let peer = UdpSocket::bind("127.0.0.1:0").unwrap();
+    let peer_addr = peer.local_addr().unwrap();
+    let mut config = default_config();
+    config.min_connections = 0;
+    config.default_read_timeout = Some(Duration::from_millis(100));
+    config.dialer = Some(Box::new(move |_| {
+        let socket = UdpSocket::bind("127.0.0.1:0")?;
+        socket.connect(peer_addr)?;
+        Ok(ConnectionType::Udp(socket))
+    }));
+    let pool = Pool::new(config).unwrap();
+
+    // 未手动设置超时，recv 在默认读超时后返回
+    let conn = pool.get().unwrap();
+    let mut buf = [0u8; 16];
+    let start = Instant::now();
+    assert!(conn.udp_conn().unwrap().recv(&mut buf).is_err());
+    assert!(start.elapsed() < Duration::from_secs(5));
+
+    // 借用期间清除超时，再次借出时恢复默认值
+    conn.udp_conn().unwrap().set_read_timeout(None).unwrap();
+    drop(conn);
+    let conn = pool.get().unwrap();
+    assert_eq!(
+        conn.udp_conn().unwrap().read_timeout().unwrap(),
+        Some(Duration::from_millis(100))
+    );
+
+    let mut config = default_config();
+    config.dialer = Some(Box::new(|_| Err("unused".into())));
+    config.default_write_timeout = Some(Duration::ZERO);
+    assert!(matches!(
+        Pool::new(config),
+        Err(NetConnPoolError::InvalidConfig { .. })
+    ));
+}
+
+#[test]
+fn test_with_connection_returns_even_on_panic() {
+    let mut config = tcp_config(start_tcp_server());
+    config.max_connections = 1;
+    config.max_idle_connections = 1;
+    let pool = Pool::new(config).unwrap();
+
+    let id = pool
+        .with_connection(|conn| conn.id(), Duration::from_secs(1))
+        .unwrap();
+    assert_eq!(pool.active_count(), 0);
+
+    let written = pool
+        .with_tcp(|mut stream| stream.write(b"ping"), Duration::from_secs(1))
+        .unwrap()
+        .unwrap();
+    assert_eq!(written, 4);
+
+    let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        pool.with_connection(|_| panic!("闭包内 panic"), Duration::from_secs(1))
+    }));
+    assert!(result.is_err());
+    assert_eq!(pool.active_count(), 0);
+    assert_eq!(pool.idle_count(), 1);
+
+    // 容量为 1：若 panic 时未归还，这里会因池耗尽而失败
+    let reused = pool
+        .with_connection(|conn| conn.id(), Duration::ZERO)
+        .unwrap();
+    assert_eq!(reused, id);
+}
+
+#[test]
+fn test_reaper_reclaims_idle_expired_without_health_check() {
+    let mut config = tcp_config(start_tcp_server());
+    config.enable_health_check = false;
+    config.idle_timeout = Duration::from_millis(200);
+    config.health_check_interval = Duration::from_millis(50);
+    let pool = Pool::new(config).unwrap();
+
+    let a = pool.get().unwrap();
+    let b = pool.get().unwrap();
+    drop(a);
+    drop(b);
+    assert_eq!(pool.idle_count(), 2);
+
+    let deadline = Instant::now() + Duration::from_secs(5);
+    while pool.idle_count() > 0 && Instant::now() < deadline {
+        thread::sleep(Duration::from_millis(20));
+    }
+    // 空闲计数与连接表同时归零，说明连接已从空闲队列中释放而非等待下次 get
+    assert_eq!(pool.idle_count(), 0);
+    let stats = pool.stats();
+    assert_eq!(stats.current_connections, 0);
+    assert_eq!(stats.current_idle_connections, 0);
+    assert_eq!(stats.total_connections_closed, 2);
+}
+
+#[test]
+fn test_reaper_leaves_fresh_idle_queue_untouched() {
+    let mut config = tcp_config(start_tcp_server());
+    config.enable_health_check = false;
+    config.idle_timeout = Duration::from_secs(30);
+    config.reaper_interval = Some(Duration::from_millis(1));
+    let pool = Pool::new(config).unwrap();
+
+    let conns: Vec<_> = (0..5).map(|_| pool.get().unwrap()).collect();
+    let order: Vec<_> = conns.iter().map(|c| c.id()).collect();
+    drop(conns);
+
+    // 没有连接空闲到阈值：reaper 多轮之后队列未被弹出重排，空闲连接按放回顺序借出
+    thread::sleep(Duration::from_millis(100));
+    let reborrowed: Vec<_> = (0..5).map(|_| pool.get().unwrap()).collect();
+    assert_eq!(reborrowed.iter().map(|c| c.id()).collect::<Vec<_>>(), order);
+    assert_eq!(pool.stats().total_connections_created, 5);
+}
+
+#[test]
+fn test_close_wakes_blocked_getters_immediately() {
+    let addr = start_tcp_server();
+    for round in 0..20 {
+        let mut config = tcp_config(addr);
+        config.max_connections = 1;
+        config.max_idle_connections = 1;
+        let pool = Arc::new(Pool::new(config).unwrap());
+        let held = pool.get().unwrap();
+
+        let waiter = {
+            let pool = pool.clone();
+            thread::spawn(move || {
+                let start = Instant::now();
+                let result = pool.get_with_timeout(Duration::from_secs(10));
+                (result.map(|c| c.id()), start.elapsed())
+            })
+        };
+        // 偶数轮让等待者先进入 wait，奇数轮直接关闭以覆盖检查与 wait 之间的窗口
+        if round % 2 == 0 {
+            thread::sleep(Duration::from_millis(20));
+        }
+        // close 会等待借出的连接归还，放到独立线程执行
+        let closer = {
+            let pool = pool.clone();
+            thread::spawn(move || pool.close())
+        };
+
+        let (result, elapsed) = waiter.join().unwrap();
+        assert!(matches!(result, Err(NetConnPoolError::PoolClosed)));
+        assert!(
+            elapsed < Duration::from_secs(2),
+            "等待者未及时唤醒: {:?}",
+            elapsed
+        );
+        drop(held);
+        closer.join().unwrap().unwrap();
+    }
+}
+
+#[test]
+fn test_on_stats_reports_periodically() {
+    let samples: Arc<Mutex<Vec<Stats>>> = Arc::new(Mutex::new(Vec::new()));
+    let mut config = tcp_config(start_tcp_server());
+    config.min_connections = 0;
+    config.stats_interval = Duration::from_millis(50);
+    let sink = samples.clone();
+    config.on_stats = Some(Box::new(move |stats| {
+        sink.lock().unwrap().push(stats.clone());
+    }));
+    let pool = Pool::new(config).unwrap();
+
+    thread::sleep(Duration::from_millis(180));
+    let before = samples.lock().unwrap().len();
+    assert!(before >= 2, "期望至少两次采样，实际 {}", before);
+    assert!(samples
+        .lock()
+        .unwrap()
+        .iter()
+        .all(|s| s.successful_gets == 0));
+
+    // 之后的采样反映新的操作
+    drop(pool.get().unwrap());
+    drop(pool.get().unwrap());
+    thread::sleep(Duration::from_millis(180));
+    let last = samples.lock().unwrap().last().cloned().unwrap();
+    assert!(samples.lock().unwrap().len() > before);
+    assert_eq!(last.successful_gets, 2);
+    assert_eq!(last.current_idle_connections, 1);
+
+    // 关闭后不再上报
+    pool.close().unwrap();
+    thread::sleep(Duration::from_millis(20));
+    let after_close = samples.lock().unwrap().len();
+    thread::sleep(Duration::from_millis(150));
+    assert_eq!(samples.lock().unwrap().len(), after_close);
+}
+
+#[test]
+fn test_on_stats_requires_enable_stats() {
+    let mut config = tcp_config(start_tcp_server());
+    config.enable_stats = false;
+    config.on_stats = Some(Box::new(|_| {}));
+    assert!(matches!(
+        Pool::new(config),
+        Err(NetConnPoolError::InvalidConfig { .. })
+    ));
+}
+
+#[test]
+fn test_capacity_reserved_before_dial() {
+    let addr = start_tcp_server();
+    let dials = Arc::new(AtomicUsize::new(0));
+    let mut config = tcp_config(addr);
+    config.min_connections = 0;
+    config.max_connections = 10;
+    config.max_idle_connections = 10;
+    config.get_connection_timeout = Duration::from_millis(200);
+    let counter = dials.clone();
+    config.dialer = Some(Box::new(move |_| {
+        counter.fetch_add(1, Ordering::SeqCst);
+        // 放大建连耗时，让并发请求都在建连窗口内到达
+        thread::sleep(Duration::from_millis(20));
+        Ok(ConnectionType::Tcp(TcpStream::connect(addr)?))
+    }));
+    let pool = Arc::new(Pool::new(config).unwrap());
+
+    let handles: Vec<_> = (0..50)
+        .map(|_| {
+            let pool = pool.clone();
+            thread::spawn(move || {
+                let conn = pool.get().ok();
+                thread::sleep(Duration::from_millis(50));
+                conn.is_some()
+            })
+        })
+        .collect();
+    let acquired = handles
+        .into_iter()
+        .map(|h| h.join().unwrap())
+        .filter(|ok| *ok)
+        .count();
+
+    assert!(acquired >= 10);
+    assert_eq!(dials.load(Ordering::SeqCst), 10);
+    assert_eq!(pool.stats().total_connections_closed, 0);
+}
+
+#[test]
+fn test_consistent_stats_is_self_consistent_at_rest() {
+    let tcp_addr = start_tcp_server();
+    let peer = UdpSocket::bind("127.0.0.1:0").unwrap();
+    let udp_addr = peer.local_addr().unwrap();
+
+    let mut config = default_config();
+    config.min_connections = 0;
+    config.dialer = Some(Box::new(move |protocol| {
+        if protocol == Some(Protocol::UDP) {
+            let socket = UdpSocket::bind("127.0.0.1:0")?;
+            socket.connect(udp_addr)?;
+            Ok(ConnectionType::Udp(socket))
+        } else {
+            Ok(ConnectionType::Tcp(TcpStream::connect(tcp_addr)?))
+        }
+    }));
+    let pool = Pool::new(config).unwrap();
+
+    let held_tcp = pool.get_tcp().unwrap();
+    let held_udp = pool.get_udp().unwrap();
+    drop(pool.get_tcp().unwrap());
+    drop(pool.get_udp().unwrap());
+    drop(pool.get_tcp().unwrap());
+
+    let stats = pool.consistent_stats();
+    assert_eq!(stats.current_connections, 4);
+    assert_eq!(
+        stats.current_connections,
+        stats.current_active_connections + stats.current_idle_connections
+    );
+    assert_eq!(stats.current_active_connections, 2);
+    assert_eq!(
+        stats.current_connections,
+        stats.current_ipv4_connections + stats.current_ipv6_connections
+    );
+    assert_eq!(
+        stats.current_connections,
+        stats.current_tcp_connections + stats.current_udp_connections
+    );
+    assert_eq!(
+        stats.current_idle_connections,
+        stats.current_tcp_idle_connections + stats.current_udp_idle_connections
+    );
+    assert_eq!(stats.total_connections_created, 4);
+
+    // 静止态下收集器计数与真实值一致，无需校正
+    assert_eq!(pool.reconcile_stats(), 0);
+    let plain = pool.stats();
+    assert_eq!(plain.current_connections, stats.current_connections);
+    assert_eq!(
+        plain.current_idle_connections,
+        stats.current_idle_connections
+    );
+    drop((held_tcp, held_udp));
+}
+
+#[test]
+fn test_shared_udp_pool_multiplexes_few_sockets() {
+    let server = UdpSocket::bind("127.0.0.1:0").unwrap();
+    server
+        .set_read_timeout(Some(Duration::from_secs(2)))
+        .unwrap();
+    let target = server.local_addr().unwrap();
+
+    let pool = Arc::new(SharedUdpPool::new("127.0.0.1:0", 2).unwrap());
+    let handles: Vec<_> = (0..32)
+        .map(|_| {
+            let pool = pool.clone();
+            thread::spawn(move || pool.get().send_to(b"ping", target).unwrap())
+        })
+        .collect();
+    for handle in handles {
+        handle.join().unwrap();
+    }
+
+    let mut sources = std::collections::HashSet::new();
+    let mut buf = [0u8; 16];
+    for _ in 0..32 {
+        let (n, from) = server.recv_from(&mut buf).unwrap();
+        assert_eq!(&buf[..n], b"ping");
+        sources.insert(from);
+    }
+    assert_eq!(pool.socket_count(), 2);
+    assert_eq!(sources.len(), 2);
+
+    assert!(matches!(
+        SharedUdpPool::new("127.0.0.1:0", 0),
+        Err(NetConnPoolError::InvalidConfig { .. })
+    ));
+}
+
+#[test]
+fn test_shutdown_timeout_decoupled_from_leak_timeout() {
+    let mut config = tcp_config(start_tcp_server());
+    config.connection_leak_timeout = Duration::from_secs(60);
+    config.shutdown_timeout = Some(Duration::from_millis(100));
+    let pool = Pool::new(config).unwrap();
+    let held = pool.get().unwrap();
+
+    let start = Instant::now();
+    pool.close().unwrap();
+    let elapsed = start.elapsed();
+    assert!(elapsed >= Duration::from_millis(100), "{:?}", elapsed);
+    assert!(elapsed < Duration::from_secs(5), "{:?}", elapsed);
+    // 超时后仍在使用的连接被强制关闭
+    assert!(held.is_closed());
+
+    // 未设置时回退 connection_leak_timeout
+    let mut config = tcp_config(start_tcp_server());
+    config.connection_leak_timeout = Duration::from_millis(100);
+    let pool = Pool::new(config).unwrap();
+    let _held = pool.get().unwrap();
+    let start = Instant::now();
+    pool.close().unwrap();
+    assert!(start.elapsed() >= Duration::from_millis(100));
+}
+
+#[test]
+fn test_max_wait_queue_rejects_excess_waiters() {
+    let mut config = tcp_config(start_tcp_server());
+    config.max_connections = 1;
+    config.max_wait_queue = 4;
+    config.get_connection_timeout = Duration::from_secs(2);
+    let pool = Arc::new(Pool::new(config).unwrap());
+    let held = pool.get().unwrap();
+
+    let waiters: Vec<_> = (0..4)
+        .map(|_| {
+            let pool = pool.clone();
+            thread::spawn(move || pool.get().map(|c| c.id()))
+        })
+        .collect();
+    // 等 4 个等待者进入排队
+    thread::sleep(Duration::from_millis(200));
+
+    let start = Instant::now();
+    match pool.get() {
+        Err(NetConnPoolError::QueueFull { waiters, max }) => {
+            assert_eq!((waiters, max), (4, 4));
+        }
+        other => panic!("期望 QueueFull，实际: {:?}", other.map(|c| c.id())),
+    }
+    assert!(start.elapsed() < Duration::from_millis(500));
+
+    // 归还后排队者依次拿到连接
+    drop(held);
+    for handle in waiters {
+        assert!(handle.join().unwrap().is_ok());
+    }
+}
+
+#[test]
+fn test_trim_idle_keeps_most_recently_used() {
+    let mut config = tcp_config(start_tcp_server());
+    config.max_connections = 4;
+    config.max_idle_connections = 4;
+    let pool = Pool::new(config).unwrap();
+
+    let conns: Vec<_> = (0..4).map(|_| pool.get().unwrap()).collect();
+    let ids: Vec<u64> = conns.iter().map(|c| c.id()).collect();
+    for conn in conns {
+        drop(conn);
+        thread::sleep(Duration::from_millis(5));
+    }
+
+    // 借出第二条：扫描时跳过的第一条被放回队尾，队列顺序不再等于最近使用顺序
+    let second = ids[1];
+    let conn = pool
+        .get_where(|c| c.id() == second, Duration::from_secs(1))
+        .unwrap();
+    assert_eq!(conn.id(), second);
+    drop(conn);
+
+    pool.set_max_idle_connections(2).unwrap();
+    let mut kept = Vec::new();
+    pool.for_each_idle(|c| kept.push(c.id()));
+    kept.sort_unstable();
+    let mut expected = vec![ids[1], ids[3]];
+    expected.sort_unstable();
+    assert_eq!(kept, expected);
+}
+
+#[test]
+fn test_insert_existing_connections_are_reused() {
+    let addr = start_tcp_server();
+    let mut config = tcp_config(addr);
+    config.min_connections = 0;
+    config.max_connections = 2;
+    let pool = Pool::new(config).unwrap();
+
+    for _ in 0..2 {
+        let stream = TcpStream::connect(addr).unwrap();
+        stream.set_nonblocking(true).unwrap();
+        pool.insert(ConnectionType::Tcp(stream)).unwrap();
+    }
+    assert_eq!(pool.idle_count(), 2);
+
+    // 受 max_connections 约束
+    let extra = ConnectionType::Tcp(TcpStream::connect(addr).unwrap());
+    assert!(matches!(
+        pool.insert(extra),
+        Err(NetConnPoolError::MaxConnectionsReached { .. })
+    ));
+
+    let mut a = pool.get().unwrap();
+    let b = pool.get().unwrap();
+    assert_ne!(a.id(), b.id());
+    assert_eq!(a.reuse_count(), 1);
+    assert_eq!(b.reuse_count(), 1);
+    // 注入时已统一切换为阻塞模式：无数据时读操作等到超时才返回
+    a.tcp_conn()
+        .unwrap()
+        .set_read_timeout(Some(Duration::from_millis(50)))
+        .unwrap();
+    let start = Instant::now();
+    assert!(a.read(&mut [0u8; 4]).is_err());
+    assert!(start.elapsed() >= Duration::from_millis(40));
+
+    let stats = pool.stats();
+    assert_eq!(stats.total_connections_reused, 2);
+    assert_eq!(stats.current_connections, 2);
+}
+
+#[test]
+fn test_current_leaked_drops_after_return() {
+    let mut config = tcp_config(start_tcp_server());
+    config.connection_leak_timeout = Duration::from_millis(200);
+    config.health_check_interval = Duration::from_millis(50);
+    config.health_check_timeout = Duration::from_millis(10);
+    let pool = Pool::new(config).unwrap();
+
+    let conn = pool.get().unwrap();
+    thread::sleep(Duration::from_millis(320));
+    let stats = pool.stats();
+    assert_eq!(stats.leaked_connections, 1);
+    assert_eq!(stats.current_leaked_connections, 1);
+    assert!(pool
+        .health()
+        .issues
+        .contains(&HealthIssue::LeakedConnections { count: 1 }));
+
+    // 用得久但正常归还：当前疑似泄漏回落，累计计数保留
+    drop(conn);
+    let stats = pool.stats();
+    assert_eq!(stats.current_leaked_connections, 0);
+    assert_eq!(stats.leaked_connections, 1);
+    assert!(pool
+        .health()
+        .issues
+        .iter()
+        .all(|i| !matches!(i, HealthIssue::LeakedConnections { .. })));
+}
+
+/// 回声服务器，返回已接受连接的服务端句柄，便于测试中单独断开某条连接
+fn start_echo_server() -> (SocketAddr, Arc<Mutex<Vec<TcpStream>>>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    let accepted = Arc::new(Mutex::new(Vec::new()));
+    let handles = accepted.clone();
+    thread::spawn(move || {
+        for mut stream in listener.incoming().flatten() {
+            handles.lock().unwrap().push(stream.try_clone().unwrap());
+            thread::spawn(move || {
+                let mut buf = [0u8; 64];
+                while let Ok(n) = stream.read(&mut buf) {
+                    if n == 0 || stream.write_all(&buf[..n]).is_err() {
+                        break;
+                    }
+                }
+            });
+        }
+    });
+    (addr, accepted)
+}
+
+#[test]
+fn test_validation_query_on_borrow_and_while_idle() {
+    let (addr, accepted) = start_echo_server();
+    let mut config = tcp_config(addr);
+    config.min_connections = 0;
+    config.validation_query = Some((b"PING".to_vec(), b"PING".to_vec()));
+    config.test_on_borrow = true;
+    config.health_check_timeout = Duration::from_millis(200);
+    let pool = Pool::new(config).unwrap();
+
+    // 好连接通过验证并被复用
+    let first = pool.get().unwrap().id();
+    assert_eq!(pool.get().unwrap().id(), first);
+    assert_eq!(pool.stats().health_check_failures, 0);
+
+    // 服务端断开后借出时验证失败，坏连接被淘汰并新建
+    accepted.lock().unwrap()[0]
+        .shutdown(std::net::Shutdown::Both)
+        .unwrap();
+    let conn = pool.get().unwrap();
+    assert_ne!(conn.id(), first);
+    drop(conn);
+    let stats = pool.stats();
+    assert_eq!(stats.health_check_failures, 1);
+    assert_eq!(stats.current_connections, 1);
+
+    // test_while_idle：reaper 对空闲连接执行验证，坏连接被移除
+    let (addr, accepted) = start_echo_server();
+    let mut config = tcp_config(addr);
+    config.min_connections = 0;
+    config.validation_query = Some((b"PING".to_vec(), b"PING".to_vec()));
+    config.test_while_idle = true;
+    config.health_check_interval = Duration::from_millis(50);
+    config.health_check_timeout = Duration::from_millis(20);
+    let pool = Pool::new(config).unwrap();
+    drop(pool.get().unwrap());
+    thread::sleep(Duration::from_millis(120));
+    assert_eq!(pool.stats().current_connections, 1);
+
+    accepted.lock().unwrap()[0]
+        .shutdown(std::net::Shutdown::Both)
+        .unwrap();
+    thread::sleep(Duration::from_millis(200));
+    let stats = pool.stats();
+    assert_eq!(stats.current_connections, 0);
+    assert!(stats.health_check_failures >= 1);
+
+    // 启用验证开关但未设置 validation_query 时配置无效
+    let mut config = tcp_config(addr);
+    config.test_on_borrow = true;
+    assert!(matches!(
+        Pool::new(config),
+        Err(NetConnPoolError::InvalidConfig { .. })
+    ));
+}
+
+#[test]
+fn test_split_reads_and_writes_concurrently() {
+    let (addr, _accepted) = start_echo_server();
+    let pool = Pool::new(tcp_config(addr)).unwrap();
+    let mut conn = pool.get().unwrap();
+    let id = conn.id();
+
+    let (mut reader, mut writer) = conn.split().unwrap();
+    let echoed = thread::scope(|s| {
+        s.spawn(move || {
+            for _ in 0..3 {
+                writer.write_all(b"ping").unwrap();
+            }
+        });
+        let mut buf = [0u8; 12];
+        reader.read_exact(&mut buf).unwrap();
+        buf
+    });
+    assert_eq!(&echoed, b"pingpingping");
+
+    // 读写半随借用结束失效，连接照常归还复用
+    drop(conn);
+    assert_eq!(pool.get().unwrap().id(), id);
+
+    let peer = UdpSocket::bind("127.0.0.1:0").unwrap();
+    let peer_addr = peer.local_addr().unwrap();
+    let mut config = default_config();
+    config.min_connections = 0;
+    config.dialer = Some(Box::new(move |_| {
+        let socket = UdpSocket::bind("127.0.0.1:0")?;
+        socket.connect(peer_addr)?;
+        Ok(ConnectionType::Udp(socket))
+    }));
+    let pool = Pool::new(config).unwrap();
+    let mut udp = pool.get().unwrap();
+    assert_eq!(
+        udp.split().err().map(|e| e.kind()),
+        Some(std::io::ErrorKind::Unsupported)
+    );
+}
+
+#[test]
+fn test_get_with_priority_prefers_high_priority_waiters() {
+    let addr = start_tcp_server();
+    let mut config = tcp_config(addr);
+    config.max_connections = 2;
+    config.priority_aging = Duration::from_secs(10);
+    let pool = Arc::new(Pool::new(config).unwrap());
+
+    let barrier = Arc::new(std::sync::Barrier::new(8));
+    let run_group = |priority: u8| {
+        (0..4)
+            .map(|_| {
+                let pool = pool.clone();
+                let barrier = barrier.clone();
+                thread::spawn(move || {
+                    barrier.wait();
+                    let mut total = Duration::ZERO;
+                    for _ in 0..5 {
+                        let start = Instant::now();
+                        let conn = pool
+                            .get_with_priority(priority, Duration::from_secs(10))
+                            .unwrap();
+                        total += start.elapsed();
+                        thread::sleep(Duration::from_millis(10));
+                        drop(conn);
+                    }
+                    total
+                })
+            })
+            .collect::<Vec<_>>()
+    };
+    let low = run_group(0);
+    let high = run_group(10);
+
+    let avg = |handles: Vec<thread::JoinHandle<Duration>>| {
+        let total: Duration = handles.into_iter().map(|h| h.join().unwrap()).sum();
+        total / 20
+    };
+    let high_avg = avg(high);
+    let low_avg = avg(low);
+    assert!(
+        high_avg * 2 < low_avg,
+        "high {:?} low {:?}",
+        high_avg,
+        low_avg
+    );
+
+    // 老化周期足够短时低优先级请求不会被饿死
+    let mut config = tcp_config(addr);
+    config.max_connections = 1;
+    config.priority_aging = Duration::from_millis(20);
+    let pool = Arc::new(Pool::new(config).unwrap());
+    let stop = Arc::new(AtomicBool::new(false));
+    let hogs: Vec<_> = (0..3)
+        .map(|_| {
+            let pool = pool.clone();
+            let stop = stop.clone();
+            thread::spawn(move || {
+                while !stop.load(Ordering::Relaxed) {
+                    if let Ok(conn) = pool.get_with_priority(1, Duration::from_millis(50)) {
+                        thread::sleep(Duration::from_millis(5));
+                        drop(conn);
+                    }
+                }
+            })
+        })
+        .collect();
+    thread::sleep(Duration::from_millis(20));
+    let got = pool.get_with_timeout(Duration::from_secs(2));
+    stop.store(true, Ordering::Relaxed);
+    assert!(got.is_ok());
+    drop(got);
+    for h in hogs {
+        h.join().unwrap();
+    }
+}
+
+#[test]
+fn test_max_bytes_per_conn_retires_connection() {
+    let (addr, _accepted) = start_echo_server();
+    let mut config = tcp_config(addr);
+    config.max_bytes_per_conn = 16;
+    let pool = Pool::new(config).unwrap();
+
+    let mut conn = pool.get().unwrap();
+    let id = conn.id();
+    conn.write_all(b"ping").unwrap();
+    let mut buf = [0u8; 4];
+    conn.read_exact(&mut buf).unwrap();
+    assert_eq!(conn.bytes_transferred(), 8);
+    drop(conn);
+
+    // 未达上限时照常复用；拆分出的读写半同样计入
+    let mut conn = pool.get().unwrap();
+    assert_eq!(conn.id(), id);
+    {
+        let (mut reader, mut writer) = conn.split().unwrap();
+        writer.write_all(b"ping").unwrap();
+        reader.read_exact(&mut buf).unwrap();
+    }
+    assert_eq!(conn.bytes_transferred(), 16);
+    drop(conn);
+
+    // 累计达到上限后该连接不再借出，被新连接替换
+    let conn = pool.get().unwrap();
+    assert_ne!(conn.id(), id);
+    assert_eq!(conn.bytes_transferred(), 0);
+    assert_eq!(pool.stats().current_connections, 1);
+}
+
+#[test]
+fn test_pool_registry_aggregates_and_closes_all() {
+    let addr = start_tcp_server();
+    let registry = PoolRegistry::new();
+
+    let mut pools = Vec::new();
+    let mut held = Vec::new();
+    for (name, count) in [("db", 1), ("cache", 2), ("upstream", 3)] {
+        let pool = registry.get_or_create(name, || tcp_config(addr)).unwrap();
+        held.extend((0..count).map(|_| pool.get().unwrap()));
+        pools.push(pool);
+    }
+    // 同名池只创建一次，config_fn 不会再被调用
+    let db = registry
+        .get_or_create("db", || unreachable!("db 已注册"))
+        .unwrap();
+    assert_eq!(db.stats().current_connections, 1);
+    assert!(registry.get("missing").is_none());
+    assert_eq!(registry.names(), ["cache", "db", "upstream"]);
+
+    let stats = registry.aggregate_stats();
+    assert_eq!(stats.current_connections, 6);
+    assert_eq!(stats.current_active_connections, 6);
+
+    drop(held);
+    registry.close_all().unwrap();
+    assert!(pools.iter().all(|p| p.is_closed()));
+    assert!(registry.names().is_empty());
+    assert_eq!(registry.aggregate_stats().current_connections, 0);
+}
+
+#[test]
+fn test_pool_registry_creates_pools_outside_the_lock() {
+    let addr = start_tcp_server();
+    let registry = Arc::new(PoolRegistry::new());
+    registry.get_or_create("fast", || tcp_config(addr)).unwrap();
+
+    // Pool::new 同步建连很慢时，创建期间不阻塞对注册表的其他访问
+    let slow_config = move || {
+        let mut config = tcp_config(addr);
+        config.fail_fast_on_new = true;
+        config.dialer = Some(Box::new(move |_| {
+            thread::sleep(Duration::from_millis(300));
+            Ok(ConnectionType::Tcp(TcpStream::connect(addr)?))
+        }));
+        config
+    };
+    let creators: Vec<_> = (0..2)
+        .map(|_| {
+            let registry = registry.clone();
+            thread::spawn(move || registry.get_or_create("slow", slow_config).unwrap())
+        })
+        .collect();
+    thread::sleep(Duration::from_millis(50));
+    let start = Instant::now();
+    assert!(registry.get("fast").is_some());
+    assert!(start.elapsed() < Duration::from_millis(200));
+
+    // 并发创建同名池时只注册一个，两个调用方拿到的都是已注册的池
+    let pools: Vec<Pool> = creators.into_iter().map(|h| h.join().unwrap()).collect();
+    assert_eq!(registry.names(), ["fast", "slow"]);
+    assert!(pools.iter().all(|p| !p.is_closed()));
+    let _held = registry.get("slow").unwrap().get().unwrap();
+    assert!(pools.iter().all(|p| p.active_count() == 1));
+}
+
+#[test]
+fn test_exhausted_error_carries_last_dial_error() {
+    let mut config = default_config();
+    config.min_connections = 0;
+    config.max_connections = 1;
+    let calls = Arc::new(AtomicUsize::new(0));
+    let dial_calls = calls.clone();
+    config.dialer = Some(Box::new(move |_| {
+        // 第一次立即被拒，之后每次建连都先卡住一段时间再被拒
+        if dial_calls.fetch_add(1, Ordering::SeqCst) > 0 {
+            thread::sleep(Duration::from_millis(500));
+        }
+        Err(Box::new(std::io::Error::from(
+            std::io::ErrorKind::ConnectionRefused,
+        )))
+    }));
+    let pool = Arc::new(Pool::new(config).unwrap());
+
+    assert!(matches!(
+        pool.get(),
+        Err(NetConnPoolError::DialFailed { .. })
+    ));
+
+    // 另一线程建连期间占住唯一的额度，本次 get 池满超时
+    let dialing = {
+        let pool = pool.clone();
+        thread::spawn(move || pool.get().map(|_| ()))
+    };
+    while calls.load(Ordering::SeqCst) < 2 {
+        thread::sleep(Duration::from_millis(5));
+    }
+    let err = pool
+        .get_with_timeout(Duration::from_millis(50))
+        .unwrap_err();
+    assert!(matches!(err, NetConnPoolError::PoolExhausted { .. }));
+    let cause = err.last_dial_error().expect("应附带最近一次建连失败");
+    assert_eq!(cause.kind, Some(std::io::ErrorKind::ConnectionRefused));
+    assert!(err.to_string().contains("last_dial_error"));
+    assert!(dialing.join().unwrap().is_err());
+}
+
+#[test]
+fn test_reaper_interval_independent_of_health_check_interval() {
+    let addr = start_tcp_server();
+    let checks = Arc::new(AtomicUsize::new(0));
+    let counting_checker =
+        |checks: &Arc<AtomicUsize>| -> Box<dyn Fn(&ConnectionType) -> bool + Send + Sync> {
+            let checks = checks.clone();
+            Box::new(move |_| {
+                checks.fetch_add(1, Ordering::SeqCst);
+                true
+            })
+        };
+
+    // 健康检查很稀疏，但 idle 回收按 reaper_interval 及时进行
+    let mut config = tcp_config(addr);
+    config.health_check_interval = Duration::from_secs(10);
+    config.reaper_interval = Some(Duration::from_millis(20));
+    config.idle_timeout = Duration::from_millis(100);
+    config.health_checker = Some(counting_checker(&checks));
+    let pool = Pool::new(config).unwrap();
+    drop(pool.get().unwrap());
+    thread::sleep(Duration::from_millis(400));
+    assert_eq!(pool.stats().current_connections, 0);
+    assert_eq!(checks.load(Ordering::SeqCst), 0);
+
+    // reaper 频繁运行时，单个连接仍按 health_check_interval 检查
+    let mut config = tcp_config(addr);
+    config.health_check_interval = Duration::from_millis(100);
+    config.health_check_timeout = Duration::from_millis(50);
+    config.reaper_interval = Some(Duration::from_millis(20));
+    config.health_checker = Some(counting_checker(&checks));
+    let pool = Pool::new(config).unwrap();
+    drop(pool.get().unwrap());
+    thread::sleep(Duration::from_millis(450));
+    let performed = checks.load(Ordering::SeqCst);
+    assert!((2..=5).contains(&performed), "checks: {}", performed);
+
+    let mut config = tcp_config(addr);
+    config.reaper_interval = Some(Duration::ZERO);
+    assert!(matches!(
+        Pool::new(config),
+        Err(NetConnPoolError::InvalidConfig { .. })
+    ));
+}
+
+#[test]
+fn test_health_check_runs_about_once_per_interval_per_connection() {
+    let addr = start_tcp_server();
+    let checked_at = Arc::new(Mutex::new(Vec::new()));
+    let interval = Duration::from_millis(200);
+    let mut config = tcp_config(addr);
+    config.health_check_interval = interval;
+    config.health_check_timeout = Duration::from_millis(100);
+    config.health_checker = Some(Box::new({
+        let checked_at = checked_at.clone();
+        move |_| {
+            checked_at.lock().unwrap().push(Instant::now());
+            true
+        }
+    }));
+    let pool = Pool::new(config).unwrap();
+
+    // 连接与 reaper 周期错开创建：每次检查都应在上次检查（或创建）后约一个 interval 发生，
+    // 既不会每轮 reaper 都查，也不会拖到下一个 interval 才查
+    thread::sleep(Duration::from_millis(30));
+    let created = Instant::now();
+    drop(pool.get().unwrap());
+    thread::sleep(Duration::from_millis(1050));
+
+    let checked_at = checked_at.lock().unwrap().clone();
+    assert!(checked_at.len() >= 4, "checks: {}", checked_at.len());
+    let mut prev = created;
+    for at in checked_at {
+        let gap = at - prev;
+        assert!(gap >= interval && gap < interval * 3 / 2, "gap: {:?}", gap);
+        prev = at;
+    }
+}
+
+#[test]
+fn test_iter_yields_connections_until_pool_closed() {
+    let addr = start_tcp_server();
+    let pool = Pool::new(tcp_config(addr)).unwrap();
+
+    // 每次取出的连接在下一轮前归还，迭代持续复用同一连接
+    let ids: Vec<u64> = pool
+        .iter(Duration::from_millis(100))
+        .take(3)
+        .map(|conn| conn.unwrap().id())
+        .collect();
+    assert_eq!(ids.len(), 3);
+    assert!(ids.iter().all(|&id| id == ids[0]));
+
+    let closer = {
+        let pool = pool.clone();
+        thread::spawn(move || {
+            thread::sleep(Duration::from_millis(100));
+            pool.close().unwrap();
+        })
+    };
+    let mut iter = pool.iter(Duration::from_millis(100));
+    let mut yielded = 0;
+    for conn in iter.by_ref() {
+        assert!(conn.is_ok());
+        yielded += 1;
+        thread::sleep(Duration::from_millis(10));
+    }
+    closer.join().unwrap();
+    assert!(yielded > 0);
+    assert!(iter.next().is_none());
+}
+
+#[test]
+fn test_idle_probe_reclaims_connection_closed_by_peer() {
+    let (addr, accepted) = start_echo_server();
+    let mut config = tcp_config(addr);
+    config.idle_probe_after = Duration::from_millis(50);
+    config.reaper_interval = Some(Duration::from_millis(20));
+    config.enable_health_check = false;
+    let pool = Pool::new(config).unwrap();
+
+    let id = pool.get().unwrap().id();
+    thread::sleep(Duration::from_millis(20));
+    assert_eq!(pool.stats().current_connections, 1);
+
+    // 服务端主动关闭：reaper 探测到对端 EOF 后在下次借用前回收
+    accepted.lock().unwrap()[0]
+        .shutdown(std::net::Shutdown::Both)
+        .unwrap();
+    thread::sleep(Duration::from_millis(300));
+    assert_eq!(pool.stats().current_connections, 0);
+
+    let mut conn = pool.get().unwrap();
+    assert_ne!(conn.id(), id);
+    conn.write_all(b"ping").unwrap();
+    let mut buf = [0u8; 4];
+    conn.read_exact(&mut buf).unwrap();
+    assert_eq!(&buf, b"ping");
+
+    // 对端存活的空闲连接经探测后仍保留
+    drop(conn);
+    thread::sleep(Duration::from_millis(200));
+    assert_eq!(pool.stats().current_connections, 1);
+}
+
+#[test]
+fn test_dump_reports_connections_and_buckets() {
+    let addr = start_tcp_server();
+    let pool = Pool::new(tcp_config(addr)).unwrap();
+
+    let held: Vec<_> = (0..3).map(|_| pool.get().unwrap()).collect();
+    let held_ids: Vec<u64> = held.iter().map(|c| c.id()).collect();
+    let mut held = held.into_iter();
+    drop(held.next());
+
+    let dump = pool.dump();
+    assert_eq!(dump.connections.len(), 3);
+    assert_eq!(dump.connections.iter().filter(|c| c.in_use).count(), 2);
+    assert!(dump.connections.windows(2).all(|w| w[0].id < w[1].id));
+    let tcp_v4 = &dump.buckets[0];
+    assert_eq!((tcp_v4.idle, tcp_v4.active), (1, 2));
+    assert!(dump.buckets[1..].iter().all(|b| b.idle + b.active == 0));
+    assert_eq!(dump.stats.current_connections, 3);
+
+    let report = dump.to_string();
+    assert!(report.contains("total=3 active=2 idle=1"));
+    assert!(report.contains("TCP/IPv4: idle=1 active=2"));
+    for id in held_ids {
+        assert!(report.contains(&format!("#{} TCP/IPv4", id)));
+    }
+}
+
+#[test]
+fn test_warm_ratio_prewarms_fraction_of_max_connections() {
+    let addr = start_tcp_server();
+    let mut config = tcp_config(addr);
+    config.max_connections = 100;
+    config.max_idle_connections = 100;
+    config.min_connections = 5;
+    config.warm_ratio = Some(0.2);
+    assert_eq!(config.warm_target(), 20);
+    let pool = Pool::new(config).unwrap();
+
+    let deadline = Instant::now() + Duration::from_secs(5);
+    while pool.idle_count() < 20 && Instant::now() < deadline {
+        thread::sleep(Duration::from_millis(10));
+    }
+    thread::sleep(Duration::from_millis(50));
+    assert_eq!(pool.idle_count(), 20);
+
+    // 比例低于 min_connections 时以 min_connections 为准；越界比例被拒绝
+    let mut config = tcp_config(addr);
+    config.min_connections = 5;
+    config.warm_ratio = Some(0.1);
+    assert_eq!(config.warm_target(), 5);
+    config.warm_ratio = Some(1.5);
+    assert!(matches!(
+        Pool::new(config),
+        Err(NetConnPoolError::InvalidConfig { .. })
+    ));
+}
+
+#[test]
+fn test_get_role_matches_connection_role_exactly() {
+    let addr = start_tcp_server();
+    let mut config = tcp_config(addr);
+    config.role_dialer = Some(Box::new(move |_, _| {
+        TcpStream::connect(addr)
+            .map(ConnectionType::Tcp)
+            .map_err(|e| Box::new(e) as Box<dyn std::error::Error + Send + Sync>)
+    }));
+    let pool = Pool::new(config).unwrap();
+    let timeout = Duration::from_secs(1);
+
+    let producer = pool.get_role(ConnectionRole::WriteOnly, timeout).unwrap();
+    let consumer = pool.get_role(ConnectionRole::ReadOnly, timeout).unwrap();
+    assert_eq!(producer.role(), Some(ConnectionRole::WriteOnly));
+    assert_eq!(consumer.role(), Some(ConnectionRole::ReadOnly));
+    let (producer_id, consumer_id) = (producer.id(), consumer.id());
+    drop(producer);
+    drop(consumer);
+
+    // 两类连接都在空闲池中，按角色借出时各自精确命中
+    for _ in 0..3 {
+        let consumer = pool.get_role(ConnectionRole::ReadOnly, timeout).unwrap();
+        let producer = pool.get_role(ConnectionRole::WriteOnly, timeout).unwrap();
+        assert_eq!(consumer.id(), consumer_id);
+        assert_eq!(producer.id(), producer_id);
+    }
+
+    // 普通 get 不会借出带角色的连接
+    let plain = pool.get().unwrap();
+    assert_eq!(plain.role(), None);
+    assert!(plain.id() != producer_id && plain.id() != consumer_id);
+
+    // 未配置 role_dialer 时按角色新建连接报配置错误
+    let pool = Pool::new(tcp_config(addr)).unwrap();
+    assert!(matches!(
+        pool.get_role(ConnectionRole::ReadOnly, timeout),
+        Err(NetConnPoolError::InvalidConfig { .. })
+    ));
+}
+
+#[test]
+fn test_get_tcp_balances_ipv4_and_ipv6_buckets() {
+    let addr_v4 = start_tcp_server();
+    let listener_v6 = TcpListener::bind("[::1]:0").unwrap();
+    let addr_v6 = listener_v6.local_addr().unwrap();
+    thread::spawn(move || {
+        let mut held = Vec::new();
+        for stream in listener_v6.incoming().flatten() {
+            held.push(stream);
+        }
+    });
+
+    let dials = Arc::new(AtomicUsize::new(0));
+    let mut config = default_config();
+    config.min_connections = 0;
+    config.dialer = Some(Box::new(move |_| {
+        let addr = [addr_v4, addr_v6][dials.fetch_add(1, Ordering::Relaxed) % 2];
+        Ok(ConnectionType::Tcp(TcpStream::connect(addr)?))
+    }));
+    let pool = Pool::new(config).unwrap();
+
+    let warm: Vec<_> = (0..4).map(|_| pool.get_tcp().unwrap()).collect();
+    drop(warm);
+
+    let mut ipv6_hits = 0;
+    for _ in 0..40 {
+        let conn = pool.get_tcp().unwrap();
+        if conn.ip_version() == IPVersion::IPv6 {
+            ipv6_hits += 1;
+        }
+    }
+    assert!((10..=30).contains(&ipv6_hits), "ipv6_hits = {}", ipv6_hits);
+    assert_eq!(pool.stats().total_connections_created, 4);
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn test_fd_budget_fails_before_emfile() {
+    // 调低 RLIMIT_NOFILE 会影响同进程内并行的其他测试，放到单独的子进程中执行
+    const CHILD_ENV: &str = "NETCONNPOOL_FD_BUDGET_CHILD";
+    if std::env::var_os(CHILD_ENV).is_none() {
+        let status = std::process::Command::new(std::env::current_exe().unwrap())
+            .args(["test_fd_budget_fails_before_emfile", "--exact"])
+            .env(CHILD_ENV, "1")
+            .status()
+            .unwrap();
+        assert!(status.success());
+        return;
+    }
+
+    #[repr(C)]
+    struct Rlimit {
+        cur: u64,
+        max: u64,
+    }
+    extern "C" {
+        fn setrlimit(resource: i32, rlim: *const Rlimit) -> i32;
+    }
+    const RLIMIT_NOFILE: i32 = 7;
+
+    let limit = (open_fd_count().unwrap() + 40) as u64;
+    // SAFETY: Rlimit 按 struct rlimit 布局定义，setrlimit 只读取该结构体
+    let ret = unsafe {
+        setrlimit(
+            RLIMIT_NOFILE,
+            &Rlimit {
+                cur: limit,
+                max: limit,
+            },
+        )
+    };
+    assert_eq!(ret, 0);
+    assert_eq!(fd_limit(), Some(limit));
+
+    let target = UdpSocket::bind("127.0.0.1:0").unwrap();
+    let target_addr = target.local_addr().unwrap();
+    let mut config = default_config();
+    config.min_connections = 0;
+    config.max_connections = 1000;
+    config.max_idle_connections = 1000;
+    config.fd_budget_ratio = Some(0.8);
+    config.dialer = Some(Box::new(move |_| {
+        let socket = UdpSocket::bind("127.0.0.1:0")?;
+        socket.connect(target_addr)?;
+        Ok(ConnectionType::Udp(socket))
+    }));
+    let pool = Pool::new(config).unwrap();
+
+    // 在描述符耗尽（EMFILE）之前就以 FdBudgetExceeded 明确失败
+    let mut held = Vec::new();
+    let err = loop {
+        match pool.get_with_timeout(Duration::ZERO) {
+            Ok(conn) => held.push(conn),
+            Err(e) => break e,
+        }
+        assert!(held.len() < limit as usize, "超出 RLIMIT 仍未拒绝建连");
+    };
+    match err {
+        NetConnPoolError::FdBudgetExceeded { open, budget } => {
+            assert_eq!(budget, (limit as f64 * 0.8) as usize);
+            assert!(open >= budget && (open as u64) < limit);
+        }
+        other => panic!("期望 FdBudgetExceeded，实际: {:?}", other),
+    }
+    assert!(!held.is_empty());
+}
+
+#[test]
+fn test_reused_and_created_get_times_are_tracked_separately() {
+    let addr = start_tcp_server();
+    let mut config = tcp_config(addr);
+    config.dialer = Some(Box::new(move |_| {
+        thread::sleep(Duration::from_millis(20));
+        Ok(ConnectionType::Tcp(TcpStream::connect(addr)?))
+    }));
+    let pool = Pool::new(config).unwrap();
+
+    drop(pool.get().unwrap());
+    for _ in 0..10 {
+        drop(pool.get().unwrap());
+    }
+
+    let stats = pool.stats();
+    let (reused, created) = (stats.reused_get_time, stats.created_get_time);
+    assert_eq!(created.count, 1);
+    assert_eq!(reused.count, 10);
+    assert_eq!(created.count + reused.count, stats.successful_gets);
+    assert!(created.average >= Duration::from_millis(20));
+    assert!(created.p50 >= Duration::from_millis(20));
+    assert!(reused.average < Duration::from_millis(20));
+    assert!(reused.p99 < created.p50);
+    assert_eq!(reused.total + created.total, stats.total_get_time);
+}
+
+#[test]
+fn test_adaptive_timeout_follows_dial_latency() {
+    let addr = start_tcp_server();
+    let delay_ms = Arc::new(AtomicUsize::new(5));
+    let delay = delay_ms.clone();
+    let mut config = tcp_config(addr);
+    config.max_connections = 20;
+    config.max_idle_connections = 20;
+    config.get_connection_timeout = Duration::from_secs(1);
+    config.adaptive_timeout = Some(AdaptiveTimeout {
+        window: 10,
+        multiplier: 2.0,
+        min_timeout: Duration::from_millis(5),
+    });
+    config.dialer = Some(Box::new(move |_| {
+        thread::sleep(Duration::from_millis(delay.load(Ordering::Relaxed) as u64));
+        Ok(ConnectionType::Tcp(TcpStream::connect(addr)?))
+    }));
+    let pool = Pool::new(config).unwrap();
+    assert_eq!(pool.current_get_timeout(), Duration::from_secs(1));
+
+    let mut held: Vec<_> = (0..10).map(|_| pool.get().unwrap()).collect();
+    let fast = pool.current_get_timeout();
+    assert!(fast >= Duration::from_millis(10) && fast < Duration::from_millis(100));
+
+    // 建连变慢后，滑动窗口内的 P99 随之上升
+    delay_ms.store(40, Ordering::Relaxed);
+    held.extend((0..10).map(|_| pool.get().unwrap()));
+    let slow = pool.current_get_timeout();
+    assert!(slow >= Duration::from_millis(80) && slow < Duration::from_millis(500));
+
+    // 池满时等待连接归还不受动态超时限制：超过动态超时后归还的连接仍能拿到
+    let returned = held.pop().unwrap();
+    let returned_id = returned.id();
+    let releaser = thread::spawn(move || {
+        thread::sleep(slow + Duration::from_millis(200));
+        drop(returned);
+    });
+    assert_eq!(pool.get().unwrap().id(), returned_id);
+    releaser.join().unwrap();
+
+    // 等待建连许可（暂停建连）时按动态超时返回，而不是 get_connection_timeout
+    held.push(pool.try_get().unwrap());
+    pool.pause_dialing();
+    let start = Instant::now();
+    assert!(matches!(pool.get(), Err(NetConnPoolError::DialingPaused)));
+    let waited = start.elapsed();
+    assert!(waited >= slow && waited < Duration::from_secs(1));
+}
+
+#[test]
+fn test_get_reuses_any_protocol_before_dialing() {
+    let tcp_addr = start_tcp_server();
+    let udp_target = UdpSocket::bind("127.0.0.1:0").unwrap();
+    let udp_addr = udp_target.local_addr().unwrap();
+    let requested = Arc::new(Mutex::new(Vec::new()));
+    let seen = requested.clone();
+
+    let mut config = default_config();
+    config.min_connections = 0;
+    config.dialer = Some(Box::new(move |protocol| {
+        seen.lock().unwrap().push(protocol);
+        match protocol {
+            Some(Protocol::UDP) => {
+                let socket = UdpSocket::bind("127.0.0.1:0")?;
+                socket.connect(udp_addr)?;
+                Ok(ConnectionType::Udp(socket))
+            }
+            _ => Ok(ConnectionType::Tcp(TcpStream::connect(tcp_addr)?)),
+        }
+    }));
+    let pool = Pool::new(config).unwrap();
+
+    // 混合空闲池：一条 TCP、一条 UDP
+    drop((pool.get_tcp().unwrap(), pool.get_udp().unwrap()));
+    requested.lock().unwrap().clear();
+
+    // get() 不固定偏向 TCP：两次借出分别拿到两类空闲连接，且不建连
+    let first = pool.get().unwrap();
+    let second = pool.get().unwrap();
+    let mut protocols = [first.protocol(), second.protocol()];
+    protocols.sort_by_key(|p| format!("{:?}", p));
+    assert_eq!(protocols, [Protocol::TCP, Protocol::UDP]);
+    assert!(requested.lock().unwrap().is_empty());
+
+    // 只剩 UDP 空闲时，get() 直接复用它而不是建新连接
+    let udp_id = if first.protocol() == Protocol::UDP {
+        first.id()
+    } else {
+        second.id()
+    };
+    drop(first);
+    drop(second);
+    let _tcp = pool.get_tcp().unwrap();
+    let conn = pool.get().unwrap();
+    assert_eq!(conn.id(), udp_id);
+    assert!(requested.lock().unwrap().is_empty());
+
+    // 没有空闲连接时以 None 调用 Dialer；Protocol::Unknown 与 get() 语义一致
+    let fresh = pool.get().unwrap();
+    let unknown = pool
+        .get_with_protocol(Protocol::Unknown, Duration::from_secs(1))
+        .unwrap();
+    assert_eq!(fresh.protocol(), Protocol::TCP);
+    assert_eq!(unknown.protocol(), Protocol::TCP);
+    assert_eq!(*requested.lock().unwrap(), vec![None, None]);
+}
+
+#[test]
+fn test_pause_dialing_only_reuses_idle_until_resumed() {
+    let addr = start_tcp_server();
+    let dials = Arc::new(AtomicUsize::new(0));
+    let counter = dials.clone();
+    let mut config = tcp_config(addr);
+    config.dialer = Some(Box::new(move |_| {
+        counter.fetch_add(1, Ordering::SeqCst);
+        Ok(ConnectionType::Tcp(TcpStream::connect(addr)?))
+    }));
+    let pool = Arc::new(Pool::new(config).unwrap());
+
+    let idle_id = pool.get().unwrap().id();
+    pool.pause_dialing();
+    assert!(pool.is_dialing_paused());
+
+    // 暂停期间仍可复用已有空闲连接
+    let held = pool.get().unwrap();
+    assert_eq!(held.id(), idle_id);
+
+    // 空闲耗尽后 get 等待到超时，不会建新连接
+    let err = pool
+        .get_with_timeout(Duration::from_millis(50))
+        .unwrap_err();
+    assert_eq!(err, NetConnPoolError::DialingPaused);
+    assert_eq!(dials.load(Ordering::SeqCst), 1);
+
+    // 等待中的 get 可以拿到归还的连接
+    let waiter = {
+        let pool = pool.clone();
+        thread::spawn(move || {
+            pool.get_with_timeout(Duration::from_secs(2))
+                .map(|c| c.id())
+        })
+    };
+    thread::sleep(Duration::from_millis(50));
+    drop(held);
+    assert_eq!(waiter.join().unwrap().unwrap(), idle_id);
+    assert_eq!(dials.load(Ordering::SeqCst), 1);
+
+    // resume 唤醒等待者并恢复建连
+    let _held = pool.get().unwrap();
+    let waiter = {
+        let pool = pool.clone();
+        thread::spawn(move || {
+            pool.get_with_timeout(Duration::from_secs(2))
+                .map(|c| c.id())
+        })
+    };
+    thread::sleep(Duration::from_millis(50));
+    pool.resume_dialing();
+    assert!(!pool.is_dialing_paused());
+    assert_ne!(waiter.join().unwrap().unwrap(), idle_id);
+    assert_eq!(dials.load(Ordering::SeqCst), 2);
+}
+
+#[test]
+fn test_verify_on_close_passes_after_normal_shutdown() {
+    let addr = start_tcp_server();
+    let mut config = tcp_config(addr);
+    config.verify_on_close = true;
+    config.shutdown_timeout = Some(Duration::from_millis(50));
+    let pool = Pool::new(config).unwrap();
+
+    // 归还的、仍借出的（关闭时被强制回收）、关闭后才归还的连接混在一起
+    let returned: Vec<_> = (0..3).map(|_| pool.get().unwrap()).collect();
+    let leaked = pool.get().unwrap();
+    let late = pool.get().unwrap();
+    drop(returned);
+    std::mem::forget(leaked);
+    assert_eq!(pool.idle_count(), 3);
+    assert_eq!(pool.active_count(), 2);
+
+    pool.close().unwrap();
+    drop(late);
+    assert_eq!(pool.active_count(), 0);
+    assert_eq!(pool.idle_count(), 0);
+    // 幂等：重复关闭不会再次校验失败
+    pool.close().unwrap();
+}
+
+#[test]
+fn test_get_reused_only_never_dials() {
+    let addr = start_tcp_server();
+    let mut config = tcp_config(addr);
+    config.enable_stats = true;
+    let pool = Arc::new(Pool::new(config).unwrap());
+
+    // idle 为空：timeout 为 0 立即失败，非 0 等到超时，均不建连
+    let err = pool.get_reused_only(Duration::ZERO).unwrap_err();
+    assert!(matches!(err, NetConnPoolError::NoIdleConnection { .. }));
+    let start = Instant::now();
+    let err = pool.get_reused_only(Duration::from_millis(50)).unwrap_err();
+    assert!(matches!(err, NetConnPoolError::NoIdleConnection { .. }));
+    assert!(start.elapsed() >= Duration::from_millis(50));
+    assert_eq!(err.wait_reason(), Some(WaitReason::WaitingForIdle));
+    assert_eq!(pool.stats().total_connections_created, 0);
+
+    // 有空闲连接时直接复用
+    let id = pool.get().unwrap().id();
+    assert_eq!(pool.get_reused_only(Duration::ZERO).unwrap().id(), id);
+
+    // 空闲连接均已借出时等待归还而不是新建
+    let held = pool.get().unwrap();
+    let waiter = {
+        let pool = pool.clone();
+        thread::spawn(move || pool.get_reused_only(Duration::from_secs(2)).map(|c| c.id()))
+    };
+    thread::sleep(Duration::from_millis(50));
+    drop(held);
+    assert_eq!(waiter.join().unwrap().unwrap(), id);
+    assert_eq!(pool.stats().total_connections_created, 1);
+}
+
+#[test]
+fn test_async_return_does_not_block_dropping_thread() {
+    let addr = start_tcp_server();
+    let returned = Arc::new(AtomicUsize::new(0));
+    let counter = returned.clone();
+    let mut config = tcp_config(addr);
+    config.async_return = true;
+    config.shutdown_timeout = Some(Duration::ZERO);
+    config.on_return = Some(Box::new(move |_| {
+        thread::sleep(Duration::from_millis(100));
+        counter.fetch_add(1, Ordering::SeqCst);
+    }));
+    let pool = Pool::new(config).unwrap();
+
+    let conn = pool.get().unwrap();
+    let id = conn.id();
+    let start = Instant::now();
+    drop(conn);
+    assert!(start.elapsed() < Duration::from_millis(50));
+    assert_eq!(pool.active_count(), 0);
+
+    // 后台线程完成 on_return 后连接回到空闲池，可被复用
+    let deadline = Instant::now() + Duration::from_secs(2);
+    while pool.idle_count() == 0 && Instant::now() < deadline {
+        thread::sleep(Duration::from_millis(10));
+    }
+    assert_eq!(returned.load(Ordering::SeqCst), 1);
+    assert_eq!(pool.get_reused_only(Duration::ZERO).unwrap().id(), id);
+
+    // 关闭后归还退回同步处理：连接直接关闭
+    let conn = pool.get().unwrap();
+    pool.close().unwrap();
+    drop(conn);
+    assert_eq!(pool.active_count(), 0);
+    assert_eq!(pool.idle_count(), 0);
+}
+
+#[test]
+fn test_high_watermark_fires_once_per_crossing() {
+    let addr = start_tcp_server();
+    let fired = Arc::new(Mutex::new(Vec::new()));
+    let seen = fired.clone();
+    let mut config = tcp_config(addr);
+    config.enable_stats = true;
+    config.max_connections = 4;
+    config.max_idle_connections = 1;
+    config.on_high_watermark = Some((
+        0.75,
+        Box::new(move |stats: &Stats| seen.lock().unwrap().push(stats.current_connections)),
+    ));
+    let pool = Pool::new(config).unwrap();
+
+    // 压到 3/4：触发一次；继续升到 4/4 不重复触发
+    let mut held: Vec<_> = (0..3).map(|_| pool.get().unwrap()).collect();
+    assert_eq!(*fired.lock().unwrap(), vec![3]);
+    held.push(pool.get().unwrap());
+    assert_eq!(*fired.lock().unwrap(), vec![3]);
+
+    // 归还后超出 max_idle 的连接被关闭，回落到 1/4 重新布防
+    held.clear();
+    assert_eq!(pool.stats().current_connections, 1);
+
+    // 再次压到高水位时可再次触发
+    let _held: Vec<_> = (0..3).map(|_| pool.get().unwrap()).collect();
+    assert_eq!(*fired.lock().unwrap(), vec![3, 3]);
+}
+
+#[test]
+fn test_peek_on_borrow_skips_peer_closed_connection() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    let accepted = Arc::new(Mutex::new(Vec::new()));
+    {
+        let accepted = accepted.clone();
+        thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                accepted.lock().unwrap().push(stream);
+            }
+        });
+    }
+
+    let mut config = tcp_config(addr);
+    config.peek_on_borrow = true;
+    let pool = Pool::new(config).unwrap();
+
+    let dead_id = pool.get_tcp().unwrap().id();
+    // 对端关闭这条空闲连接
+    let deadline = Instant::now() + Duration::from_secs(2);
+    while accepted.lock().unwrap().is_empty() && Instant::now() < deadline {
+        thread::sleep(Duration::from_millis(5));
+    }
+    accepted.lock().unwrap().clear();
+    thread::sleep(Duration::from_millis(50));
+
+    let mut conn = pool.get_tcp().unwrap();
+    assert_ne!(conn.id(), dead_id);
+    conn.write_all(b"ping").unwrap();
+    assert_eq!(pool.stats().current_connections, 1);
+}
+
+#[test]
+fn test_waiters_reflects_blocked_gets() {
+    let addr = start_tcp_server();
+    let mut config = tcp_config(addr);
+    config.max_connections = 1;
+    let pool = Arc::new(Pool::new(config).unwrap());
+    assert_eq!(pool.waiters(), 0);
+
+    let held = pool.get().unwrap();
+    let blocked: Vec<_> = (0..3)
+        .map(|_| {
+            let pool = pool.clone();
+            thread::spawn(move || {
+                drop(pool.get_with_timeout(Duration::from_secs(5)).unwrap());
+            })
+        })
+        .collect();
+    let deadline = Instant::now() + Duration::from_secs(2);
+    while pool.waiters() < 3 && Instant::now() < deadline {
+        thread::sleep(Duration::from_millis(5));
+    }
+    assert_eq!(pool.waiters(), 3);
+
+    // 归还后等待者依次拿到连接并退出等待
+    drop(held);
+    for handle in blocked {
+        handle.join().unwrap();
+    }
+    assert_eq!(pool.waiters(), 0);
+}
+
+#[test]
+fn test_get_many_returns_whole_batch_on_drop() {
+    let addr = start_tcp_server();
+    let mut config = tcp_config(addr);
+    config.max_connections = 3;
+    let pool = Arc::new(Pool::new(config).unwrap());
+
+    let mut batch = pool.get_many(3, Duration::from_secs(1)).unwrap();
+    assert_eq!(batch.len(), 3);
+    batch[0].write_all(b"ping").unwrap();
+    assert_eq!(pool.active_count(), 3);
+
+    // 池满时再取一批会超时，已借出的部分整批归还
+    let err = pool.get_many(1, Duration::from_millis(20)).unwrap_err();
+    assert!(matches!(err, NetConnPoolError::PoolExhausted { .. }));
+
+    // 整批归还一次唤醒全部等待者
+    let waiters: Vec<_> = (0..2)
+        .map(|_| {
+            let pool = pool.clone();
+            thread::spawn(move || pool.get_with_timeout(Duration::from_secs(2)).map(drop))
+        })
+        .collect();
+    let deadline = Instant::now() + Duration::from_secs(2);
+    while pool.waiters() < 2 && Instant::now() < deadline {
+        thread::sleep(Duration::from_millis(5));
+    }
+    drop(batch);
+    for waiter in waiters {
+        waiter.join().unwrap().unwrap();
+    }
+    assert_eq!(pool.active_count(), 0);
+    assert_eq!(pool.idle_count(), 3);
+
+    // 拆开后逐条归还
+    let conns = pool.get_many(2, Duration::from_secs(1)).unwrap().into_vec();
+    assert_eq!(pool.active_count(), 2);
+    drop(conns);
+    assert_eq!(pool.active_count(), 0);
+}
+
+#[test]
+fn test_max_concurrent_dials_limits_parallel_dialer_calls() {
+    let addr = start_tcp_server();
+    let in_flight = Arc::new(AtomicUsize::new(0));
+    let peak = Arc::new(AtomicUsize::new(0));
+    let mut config = tcp_config(addr);
+    config.max_connections = 16;
+    config.max_concurrent_dials = 2;
+    let (in_flight_d, peak_d) = (in_flight.clone(), peak.clone());
+    config.dialer = Some(Box::new(move |_| {
+        let now = in_flight_d.fetch_add(1, Ordering::SeqCst) + 1;
+        peak_d.fetch_max(now, Ordering::SeqCst);
+        thread::sleep(Duration::from_millis(30));
+        in_flight_d.fetch_sub(1, Ordering::SeqCst);
+        TcpStream::connect(addr)
+            .map(ConnectionType::Tcp)
+            .map_err(|e| Box::new(e) as Box<dyn std::error::Error + Send + Sync>)
+    }));
+    let pool = Arc::new(Pool::new(config).unwrap());
+
+    let handles: Vec<_> = (0..8)
+        .map(|_| {
+            let pool = pool.clone();
+            thread::spawn(move || pool.get_with_timeout(Duration::from_secs(5)))
+        })
+        .collect();
+    let conns: Vec<_> = handles
+        .into_iter()
+        .map(|h| h.join().unwrap().unwrap())
+        .collect();
+    assert_eq!(conns.len(), 8);
+    assert!(peak.load(Ordering::SeqCst) <= 2);
+    drop(conns);
+
+    // 建连并发占满时，超时内未等到许可的 get 返回 DialConcurrencyLimited
+    let release = Arc::new(AtomicBool::new(false));
+    let dialing = Arc::new(AtomicBool::new(false));
+    let mut config = tcp_config(addr);
+    config.max_concurrent_dials = 1;
+    let (release_d, dialing_d) = (release.clone(), dialing.clone());
+    config.dialer = Some(Box::new(move |_| {
+        dialing_d.store(true, Ordering::SeqCst);
+        while !release_d.load(Ordering::SeqCst) {
+            thread::sleep(Duration::from_millis(1));
+        }
+        TcpStream::connect(addr)
+            .map(ConnectionType::Tcp)
+            .map_err(|e| Box::new(e) as Box<dyn std::error::Error + Send + Sync>)
+    }));
+    let pool = Arc::new(Pool::new(config).unwrap());
+    let blocked = {
+        let pool = pool.clone();
+        thread::spawn(move || pool.get_with_timeout(Duration::from_secs(5)).map(drop))
+    };
+    while !dialing.load(Ordering::SeqCst) {
+        thread::sleep(Duration::from_millis(1));
+    }
+    let err = pool
+        .get_with_timeout(Duration::from_millis(30))
+        .unwrap_err();
+    assert!(matches!(
+        err,
+        NetConnPoolError::DialConcurrencyLimited { .. }
+    ));
+    assert_eq!(err.wait_reason(), Some(WaitReason::WaitingForDial));
+    release.store(true, Ordering::SeqCst);
+    blocked.join().unwrap().unwrap();
+}
+
+#[test]
+fn test_get_oneshot_closes_connection_on_return() {
+    let addr = start_tcp_server();
+    let mut config = tcp_config(addr);
+    config.max_connections = 2;
+    let pool = Pool::new(config).unwrap();
+
+    // 先放一条普通空闲连接：一次性获取不复用它
+    let pooled_id = pool.get().unwrap().id();
+    assert_eq!(pool.idle_count(), 1);
+
+    let oneshot = pool.get_oneshot(Duration::from_secs(1)).unwrap();
+    let oneshot_id = oneshot.id();
+    assert_ne!(oneshot_id, pooled_id);
+    assert!(oneshot.is_oneshot());
+    assert_eq!(pool.stats().current_connections, 2);
+    drop(oneshot);
+
+    // 归还即关闭：不进入空闲池
+    assert_eq!(pool.stats().current_connections, 1);
+    assert_eq!(pool.idle_count(), 1);
+    assert_eq!(pool.active_count(), 0);
+    for _ in 0..3 {
+        assert_ne!(pool.get().unwrap().id(), oneshot_id);
+    }
+
+    // 池被空闲连接占满时，一次性获取关闭一条空闲连接腾出额度
+    drop((pool.get().unwrap(), pool.get().unwrap()));
+    assert_eq!(pool.idle_count(), 2);
+    let second = pool.get_oneshot(Duration::ZERO).unwrap();
+    assert!(second.is_oneshot());
+    assert_eq!(pool.idle_count(), 1);
+    drop(second);
+    assert_eq!(pool.stats().current_connections, 1);
+}
+
+#[test]
+fn test_on_slow_get_fires_once_after_threshold() {
+    let addr = start_tcp_server();
+    let calls: Arc<Mutex<Vec<(Duration, Instant)>>> = Arc::new(Mutex::new(Vec::new()));
+    let mut config = tcp_config(addr);
+    config.max_connections = 1;
+    config.slow_get_threshold = Duration::from_millis(50);
+    let calls_cb = calls.clone();
+    config.on_slow_get = Some(Box::new(move |waited| {
+        calls_cb.lock().unwrap().push((waited, Instant::now()));
+    }));
+    let pool = Arc::new(Pool::new(config).unwrap());
+
+    // 未等待的 get 不触发
+    drop(pool.get().unwrap());
+    assert!(calls.lock().unwrap().is_empty());
+
+    // 池满制造慢 get：持有者 200ms 后归还
+    let held = pool.get().unwrap();
+    let releaser = thread::spawn(move || {
+        thread::sleep(Duration::from_millis(200));
+        drop(held);
+    });
+    let start = Instant::now();
+    let conn = pool.get_with_timeout(Duration::from_secs(2)).unwrap();
+    let returned_at = Instant::now();
+    releaser.join().unwrap();
+    drop(conn);
+
+    let calls = calls.lock().unwrap();
+    assert_eq!(calls.len(), 1);
+    let (waited, fired_at) = calls[0];
+    assert!(waited >= Duration::from_millis(50));
+    assert!(fired_at.duration_since(start) >= Duration::from_millis(50));
+    // 软阈值在等待中途触发，而不是等到 get 结束
+    assert!(returned_at.duration_since(fired_at) >= Duration::from_millis(50));
+}
+
+#[test]
+fn test_partitioned_pool_avoids_failed_partition() {
+    // 宕机实例：端口已释放，建连被拒绝
+    let dead_addr = TcpListener::bind("127.0.0.1:0")
+        .unwrap()
+        .local_addr()
+        .unwrap();
+    let dead_dials = Arc::new(AtomicUsize::new(0));
+    let mut dead_config = tcp_config(dead_addr);
+    let dead_dials_d = dead_dials.clone();
+    dead_config.dialer = Some(Box::new(move |_| {
+        dead_dials_d.fetch_add(1, Ordering::SeqCst);
+        TcpStream::connect(dead_addr)
+            .map(ConnectionType::Tcp)
+            .map_err(|e| Box::new(e) as Box<dyn std::error::Error + Send + Sync>)
+    }));
+
+    let breaker = CircuitBreaker {
+        failure_threshold: 2,
+        open_duration: Duration::from_secs(60),
+    };
+    let pool = PartitionedPool::new(
+        vec![
+            ("a", tcp_config(start_tcp_server())),
+            ("b", dead_config),
+            ("c", tcp_config(start_tcp_server())),
+        ],
+        breaker,
+    )
+    .unwrap();
+    assert_eq!(pool.partition_names(), vec!["a", "b", "c"]);
+
+    let mut succeeded = 0;
+    for _ in 0..30 {
+        if pool.get(Duration::from_millis(200)).is_ok() {
+            succeeded += 1;
+        }
+    }
+    assert_eq!(succeeded, 30);
+
+    // 故障分区熔断后不再被尝试，健康分区分担全部请求
+    assert_eq!(pool.is_partition_open("b"), Some(true));
+    assert_eq!(pool.is_partition_open("a"), Some(false));
+    assert_eq!(dead_dials.load(Ordering::SeqCst), 2);
+    assert!(pool.partition("a").unwrap().stats().successful_gets > 0);
+    assert!(pool.partition("c").unwrap().stats().successful_gets > 0);
+
+    let err = pool.get_from("b", Duration::ZERO).unwrap_err();
+    assert_eq!(
+        err,
+        NetConnPoolError::PartitionUnavailable {
+            partition: "b".to_string()
+        }
+    );
+    assert!(matches!(
+        pool.get_from("x", Duration::ZERO),
+        Err(NetConnPoolError::UnknownPartition { .. })
+    ));
+    pool.close().unwrap();
+}
+
+#[test]
+fn test_connection_expired_while_in_use_is_reclaimed_on_return() {
+    let addr = start_tcp_server();
+    let mut config = tcp_config(addr);
+    config.max_connections = 1;
+    config.max_lifetime = Duration::from_millis(50);
+    config.idle_timeout = Duration::from_millis(50);
+    config.reaper_interval = Some(Duration::from_millis(10));
+    let pool = Pool::new(config).unwrap();
+
+    let conn = pool.get().unwrap();
+    let expired_id = conn.id();
+
+    // 借出期间过期：reaper 只标记不健康，连接仍占用名额
+    let deadline = Instant::now() + Duration::from_secs(2);
+    while conn.health_status() && Instant::now() < deadline {
+        thread::sleep(Duration::from_millis(5));
+    }
+    assert!(!conn.health_status());
+    assert!(!conn.is_closed());
+    assert_eq!(pool.stats().current_connections, 1);
+    assert_eq!(pool.active_count(), 1);
+
+    // 归还时被回收并释放名额
+    drop(conn);
+    let stats = pool.stats();
+    assert_eq!(stats.current_connections, 0);
+    assert_eq!(stats.current_active_connections, 0);
+    assert_eq!(pool.idle_count(), 0);
+    assert_eq!(pool.active_count(), 0);
+
+    // 名额已释放：池满上限为 1 时仍能立即建立新连接
+    let fresh = pool.get_with_timeout(Duration::ZERO).unwrap();
+    assert_ne!(fresh.id(), expired_id);
+}
+
+#[test]
+fn test_event_sink_receives_lifecycle_events() {
+    let addr = start_tcp_server();
+    let (tx, rx) = std::sync::mpsc::sync_channel(64);
+    let mut config = tcp_config(addr);
+    config.event_sink = Some(tx);
+    let pool = Pool::new(config).unwrap();
+
+    let conn = pool.get().unwrap();
+    let id = conn.id();
+    drop(conn);
+    drop(pool.get().unwrap());
+    pool.close().unwrap();
+
+    let events: Vec<PoolEvent> = rx.try_iter().collect();
+    assert_eq!(
+        events,
+        vec![
+            PoolEvent::Created { connection_id: id },
+            PoolEvent::Borrowed { connection_id: id },
+            PoolEvent::Returned { connection_id: id },
+            PoolEvent::Borrowed { connection_id: id },
+            PoolEvent::Returned { connection_id: id },
+            PoolEvent::Closed { connection_id: id },
+        ]
+    );
+    assert_eq!(pool.dropped_events(), 0);
+
+    // channel 已满时丢弃事件而不阻塞
+    let (tx, rx) = std::sync::mpsc::sync_channel(1);
+    let mut config = tcp_config(addr);
+    config.event_sink = Some(tx);
+    let pool = Pool::new(config).unwrap();
+    for _ in 0..3 {
+        drop(pool.get().unwrap());
+    }
+    assert_eq!(rx.try_iter().count(), 1);
+    assert_eq!(pool.dropped_events(), 6);
+
+    // 分区熔断经该分区的 event_sink 发出
+    let dead_addr = TcpListener::bind("127.0.0.1:0")
+        .unwrap()
+        .local_addr()
+        .unwrap();
+    let (tx, rx) = std::sync::mpsc::sync_channel(8);
+    let mut config = tcp_config(dead_addr);
+    config.event_sink = Some(tx);
+    let breaker = CircuitBreaker {
+        failure_threshold: 1,
+        open_duration: Duration::from_secs(60),
+    };
+    let partitioned = PartitionedPool::new(vec![("dead", config)], breaker).unwrap();
+    assert!(partitioned.get(Duration::ZERO).is_err());
+    assert_eq!(
+        rx.try_iter().collect::<Vec<_>>(),
+        vec![PoolEvent::CircuitOpen {
+            partition: "dead".to_string()
+        }]
+    );
+}
+
+#[test]
+fn test_min_availability_degrades_and_recovers() {
+    let addr = start_tcp_server();
+    let transitions: Arc<Mutex<Vec<(bool, usize)>>> = Arc::new(Mutex::new(Vec::new()));
+    let mut config = tcp_config(addr);
+    config.max_connections = 4;
+    config.min_availability = 2;
+    let transitions_cb = transitions.clone();
+    config.on_degradation = Some(Box::new(move |degraded, available| {
+        transitions_cb.lock().unwrap().push((degraded, available));
+    }));
+    let pool = Pool::new(config).unwrap();
+
+    drop(pool.get_sheddable(Duration::ZERO).unwrap());
+    let mut held = vec![pool.get().unwrap(), pool.get().unwrap()];
+    assert!(!pool.is_degraded());
+
+    // 可用连接数 4 - 3 = 1，低于阈值进入降级态
+    held.push(pool.get().unwrap());
+    assert!(pool.is_degraded());
+    assert_eq!(*transitions.lock().unwrap(), vec![(true, 1)]);
+
+    // 降级态下可丢弃的请求快速失败，普通请求不受影响
+    let start = Instant::now();
+    let err = pool.get_sheddable(Duration::from_secs(1)).unwrap_err();
+    assert!(matches!(
+        err,
+        NetConnPoolError::Degraded {
+            available: 1,
+            min_availability: 2
+        }
+    ));
+    assert!(start.elapsed() < Duration::from_millis(100));
+    held.push(pool.get().unwrap());
+
+    // 归还后可用度回到阈值以上，退出降级态
+    held.truncate(2);
+    assert!(!pool.is_degraded());
+    assert_eq!(*transitions.lock().unwrap(), vec![(true, 1), (false, 2)]);
+    drop(pool.get_sheddable(Duration::ZERO).unwrap());
+}
+
+#[test]
+fn test_get_with_cancel_wakes_blocked_waiter() {
+    let addr = start_tcp_server();
+    let mut config = tcp_config(addr);
+    config.max_connections = 1;
+    let pool = Pool::new(config).unwrap();
+    let held = pool.get().unwrap();
+
+    // 已取消的 get 不等待
+    let cancelled = Arc::new(AtomicBool::new(true));
+    assert!(matches!(
+        pool.get_with_cancel(Duration::from_secs(10), cancelled),
+        Err(NetConnPoolError::Cancelled { .. })
+    ));
+
+    let cancel = Arc::new(AtomicBool::new(false));
+    let waiter = {
+        let pool = pool.clone();
+        let cancel = cancel.clone();
+        thread::spawn(move || {
+            let start = Instant::now();
+            let result = pool.get_with_cancel(Duration::from_secs(10), cancel);
+            (result.map(|c| c.id()), start.elapsed())
+        })
+    };
+    thread::sleep(Duration::from_millis(100));
+    cancel.store(true, Ordering::Release);
+
+    let (result, elapsed) = waiter.join().unwrap();
+    assert!(
+        matches!(result, Err(NetConnPoolError::Cancelled { waited }) if waited >= Duration::from_millis(100))
+    );
+    assert!(
+        elapsed < Duration::from_secs(2),
+        "取消后未及时返回: {:?}",
+        elapsed
+    );
+    drop(held);
+}
+
+#[test]
+fn test_soft_max_connections_allows_burst_up_to_hard_max() {
+    let addr = start_tcp_server();
+    let mut config = tcp_config(addr);
+    config.soft_max_connections = 2;
+    config.max_connections = 4;
+    let pool = Pool::new(config).unwrap();
+
+    // 无人等待时立即获取受软上限约束
+    let mut held = vec![pool.get().unwrap(), pool.get().unwrap()];
+    assert!(matches!(
+        pool.get_with_timeout(Duration::ZERO),
+        Err(NetConnPoolError::PoolExhausted { max: 2, .. })
+    ));
+
+    // 突发：等待者出现后允许突破到硬上限
+    let burst: Vec<_> = (0..2)
+        .map(|_| {
+            let pool = pool.clone();
+            thread::spawn(move || pool.get_with_timeout(Duration::from_secs(2)))
+        })
+        .collect();
+    for handle in burst {
+        held.push(handle.join().unwrap().unwrap());
+    }
+    assert_eq!(pool.stats().current_connections, 4);
+
+    // 不超过硬上限
+    let start = Instant::now();
+    assert!(matches!(
+        pool.get_with_timeout(Duration::from_millis(100)),
+        Err(NetConnPoolError::PoolExhausted { max: 4, .. })
+    ));
+    assert!(start.elapsed() >= Duration::from_millis(100));
+    assert_eq!(pool.stats().current_connections, 4);
+
+    // 突发过后超出软上限的连接归还即关闭，连接数回落到软上限
+    drop(held);
+    assert_eq!(pool.stats().current_connections, 2);
+    assert_eq!(pool.idle_count(), 2);
+}
+
+#[test]
+fn test_udp_rebind_after_reuses_changes_local_addr() {
+    let peer = UdpSocket::bind("127.0.0.1:0").unwrap();
+    let peer_addr = peer.local_addr().unwrap();
+    let mut config = default_config();
+    config.min_connections = 0;
+    config.udp_rebind_after_reuses = 2;
+    config.dialer = Some(Box::new(move |_| {
+        let socket = UdpSocket::bind("127.0.0.1:0")?;
+        socket.connect(peer_addr)?;
+        Ok(ConnectionType::Udp(socket))
+    }));
+    let pool = Pool::new(config).unwrap();
+    let local_addr = |conn: &Connection| conn.udp_conn().unwrap().local_addr().unwrap();
+
+    let first = pool.get().unwrap();
+    let (first_id, first_addr) = (first.id(), local_addr(&first));
+    drop(first);
+
+    // 复用未达阈值：仍是同一条连接、同一本地端口
+    for _ in 0..2 {
+        let conn = pool.get().unwrap();
+        assert_eq!(conn.id(), first_id);
+        assert_eq!(local_addr(&conn), first_addr);
+    }
+
+    // 第二次复用后归还即关闭，由后台补建：换成新连接与新本地端口，池中仍保留一条空闲连接
+    let deadline = Instant::now() + Duration::from_secs(5);
+    while pool.idle_count() < 1 && Instant::now() < deadline {
+        thread::sleep(Duration::from_millis(10));
+    }
+    assert_eq!(pool.idle_count(), 1);
+    assert_eq!(pool.stats().current_connections, 1);
+    let rebound = pool.get().unwrap();
+    assert_ne!(rebound.id(), first_id);
+    assert_ne!(local_addr(&rebound), first_addr);
+    assert_eq!(pool.stats().total_connections_closed, 1);
+}
+
+#[test]
+fn test_get_sticky_reuses_same_connection_on_same_thread() {
+    let addr = start_tcp_server();
+    let pool = Pool::new(tcp_config(addr)).unwrap();
+    // 先放 4 条空闲连接：普通 get 会在它们之间轮转
+    drop((0..4).map(|_| pool.get().unwrap()).collect::<Vec<_>>());
+    assert_eq!(pool.idle_count(), 4);
+
+    let sticky_id = pool.get_sticky(Duration::from_secs(1)).unwrap().id();
+    for _ in 0..10 {
+        drop(pool.get().unwrap());
+        assert_eq!(
+            pool.get_sticky(Duration::from_secs(1)).unwrap().id(),
+            sticky_id
+        );
+    }
+
+    // 上次的连接被其他线程拿走时回退到普通 get
+    let taken = {
+        let pool = pool.clone();
+        thread::spawn(move || pool.get_where(|c| c.id() == sticky_id, Duration::from_secs(1)))
+            .join()
+            .unwrap()
+            .unwrap()
+    };
+    assert_eq!(taken.id(), sticky_id);
+    let fallback = pool.get_sticky(Duration::from_secs(1)).unwrap();
+    assert_ne!(fallback.id(), sticky_id);
+    let fallback_id = fallback.id();
+    drop((taken, fallback));
+
+    // 之后粘到新借到的那条连接
+    for _ in 0..3 {
+        drop(pool.get().unwrap());
+        assert_eq!(
+            pool.get_sticky(Duration::from_secs(1)).unwrap().id(),
+            fallback_id
+        );
+    }
+}
+
+#[test]
+fn test_get_with_shadow_returns_primary_and_shadow_connections() {
+    let primary_addr = start_tcp_server();
+    let shadow_addr = start_tcp_server();
+    let mut config = tcp_config(primary_addr);
+    config.shadow_dialer = Some(Box::new(move |_| {
+        TcpStream::connect(shadow_addr)
+            .map(ConnectionType::Tcp)
+            .map_err(|e| Box::new(e) as Box<dyn std::error::Error + Send + Sync>)
+    }));
+    let pool = Pool::new(config).unwrap();
+    let shadow_pool = pool.shadow().unwrap().clone();
+
+    // 影子池为空：不在调用线程上建连，改为后台预建
+    let (primary, shadow) = pool.get_with_shadow(Duration::from_secs(1)).unwrap();
+    assert!(shadow.is_none());
+    drop(primary);
+    let deadline = Instant::now() + Duration::from_secs(2);
+    while shadow_pool.idle_count() == 0 && Instant::now() < deadline {
+        thread::sleep(Duration::from_millis(10));
+    }
+
+    let (primary, shadow) = pool.get_with_shadow(Duration::from_secs(1)).unwrap();
+    let shadow = shadow.unwrap();
+    assert_eq!(primary.connection_type().peer_addr().unwrap(), primary_addr);
+    assert_eq!(shadow.connection_type().peer_addr().unwrap(), shadow_addr);
+    let ids = (primary.id(), shadow.id());
+    assert_eq!((pool.active_count(), shadow_pool.active_count()), (1, 1));
+
+    // 各自归还到自己的连接池，之后分别被复用
+    drop(shadow);
+    assert_eq!((pool.idle_count(), shadow_pool.idle_count()), (0, 1));
+    drop(primary);
+    assert_eq!((pool.idle_count(), shadow_pool.idle_count()), (1, 1));
+    let (primary, shadow) = pool.get_with_shadow(Duration::from_secs(1)).unwrap();
+    assert_eq!((primary.id(), shadow.unwrap().id()), ids);
+    drop(primary);
+
+    // 关闭主池时影子池随之关闭
+    pool.close().unwrap();
+    assert!(shadow_pool.is_closed());
+
+    // 未配置 shadow_dialer 时没有影子连接
+    let plain = Pool::new(tcp_config(primary_addr)).unwrap();
+    assert!(plain.shadow().is_none());
+    assert!(plain
+        .get_with_shadow(Duration::from_secs(1))
+        .unwrap()
+        .1
+        .is_none());
+}
+
+#[test]
+fn test_slow_shadow_dialer_does_not_delay_primary() {
+    let primary_addr = start_tcp_server();
+    let shadow_addr = start_tcp_server();
+    let mut config = tcp_config(primary_addr);
+    config.shadow_dialer = Some(Box::new(move |_| {
+        thread::sleep(Duration::from_millis(500));
+        TcpStream::connect(shadow_addr)
+            .map(ConnectionType::Tcp)
+            .map_err(|e| Box::new(e) as Box<dyn std::error::Error + Send + Sync>)
+    }));
+    let pool = Pool::new(config).unwrap();
+
+    let start = Instant::now();
+    let (_primary, shadow) = pool.get_with_shadow(Duration::from_secs(1)).unwrap();
+    assert!(shadow.is_none());
+    assert!(start.elapsed() < Duration::from_millis(300));
+}
+
+#[test]
+fn test_fallback_dialer_used_when_primary_dialer_fails() {
+    let fallback_addr = start_tcp_server();
+    let primary_up = Arc::new(AtomicBool::new(false));
+    let fallback_calls = Arc::new(AtomicUsize::new(0));
+    let primary_addr = start_tcp_server();
+    let mut config = tcp_config(primary_addr);
+    let up = primary_up.clone();
+    config.dialer = Some(Box::new(move |_| {
+        if !up.load(Ordering::SeqCst) {
+            return Err(Box::new(std::io::Error::from(
+                std::io::ErrorKind::ConnectionRefused,
+            )));
+        }
+        Ok(ConnectionType::Tcp(TcpStream::connect(primary_addr)?))
+    }));
+    let calls = fallback_calls.clone();
+    config.fallback_dialer = Some(Box::new(move |_| {
+        calls.fetch_add(1, Ordering::SeqCst);
+        TcpStream::connect(fallback_addr)
+            .map(ConnectionType::Tcp)
+            .map_err(|e| Box::new(e) as Box<dyn std::error::Error + Send + Sync>)
+    }));
+    let pool = Pool::new(config).unwrap();
+
+    // 主 Dialer 失败：退回降级后端，连接带降级标记
+    let degraded = pool.get().unwrap();
+    assert_eq!(fallback_calls.load(Ordering::SeqCst), 1);
+    assert!(degraded.is_fallback());
+    assert_eq!(
+        degraded.connection_type().peer_addr().unwrap(),
+        fallback_addr
+    );
+
+    // 主 Dialer 恢复后新建的连接不再带降级标记，也不再调用 fallback
+    primary_up.store(true, Ordering::SeqCst);
+    let normal = pool.get().unwrap();
+    assert!(!normal.is_fallback());
+    assert_eq!(normal.connection_type().peer_addr().unwrap(), primary_addr);
+    assert_eq!(fallback_calls.load(Ordering::SeqCst), 1);
+    drop((degraded, normal));
+
+    // 服务器模式不支持 fallback_dialer
+    let mut config = default_config();
+    config.mode = PoolMode::Server;
+    config.listener = Some(TcpListener::bind("127.0.0.1:0").unwrap());
+    config.fallback_dialer = Some(Box::new(|_| {
+        Err(Box::new(std::io::Error::from(std::io::ErrorKind::Other)))
+    }));
+    match config.validate() {
+        Err(NetConnPoolError::InvalidConfig { reason }) => {
+            assert!(reason.contains("fallback_dialer"), "{}", reason)
+        }
+        other => panic!("期望 InvalidConfig，实际: {:?}", other),
+    }
+}
+
+#[test]
+fn test_get_with_lease_overrides_global_leak_timeout() {
+    let (tx, rx) = std::sync::mpsc::sync_channel(64);
+    let mut config = tcp_config(start_tcp_server());
+    config.connection_leak_timeout = Duration::from_millis(200);
+    config.health_check_interval = Duration::from_millis(50);
+    config.health_check_timeout = Duration::from_millis(10);
+    config.event_sink = Some(tx);
+    let pool = Pool::new(config).unwrap();
+
+    let short = pool
+        .get_with_lease(Duration::from_secs(1), Duration::from_millis(50))
+        .unwrap();
+    let long = pool
+        .get_with_lease(Duration::from_secs(1), Duration::from_secs(60))
+        .unwrap();
+    assert_eq!(short.lease(), Some(Duration::from_millis(50)));
+    // 超过全局 connection_leak_timeout，但仍在长租约内
+    thread::sleep(Duration::from_millis(320));
+
+    // 只有短租约的借用被判定泄漏
+    let leaked: Vec<u64> = rx
+        .try_iter()
+        .filter_map(|e| match e {
+            PoolEvent::Leaked { connection_id } => Some(connection_id),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(leaked, vec![short.id()]);
+    assert_eq!(pool.stats().leaked_connections, 1);
+    // 超过租约 2 倍的严重泄漏被强制回收，长租约的连接不受影响
+    assert!(short.is_closed());
+    assert!(!long.is_closed());
+
+    // 再次普通借出时租约清除，沿用全局泄漏判定
+    let long_id = long.id();
+    drop(long);
+    let conn = pool.get().unwrap();
+    assert_eq!(conn.id(), long_id);
+    assert_eq!(conn.lease(), None);
+    drop((short, conn));
+}
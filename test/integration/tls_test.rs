@@ -0,0 +1,171 @@
+// Copyright (c) 2025, vistone
+// All rights reserved.
+
+// TLS 连接（tls feature）测试：握手后的流纳入连接池管理、复用与关闭
+
+use netconnpool::config::default_config;
+use netconnpool::pool::PooledConnection;
+use netconnpool::*;
+use rustls::pki_types::{CertificateDer, PrivateKeyDer, PrivatePkcs8KeyDer};
+use rustls::{ClientConfig, RootCertStore, ServerConfig, ServerConnection, StreamOwned};
+use std::io::{Read, Write};
+use std::net::TcpListener;
+use std::sync::mpsc;
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+/// 启动本地 TLS 回声服务器，返回端口、信任该服务器证书的客户端配置，
+/// 以及每条连接结束时上报"是否收到 close_notify 正常关闭"的通道
+fn start_tls_echo_server() -> (u16, Arc<ClientConfig>, mpsc::Receiver<bool>) {
+    let cert = rcgen::generate_simple_self_signed(vec!["localhost".to_string()]).unwrap();
+    let cert_der: CertificateDer<'static> = cert.cert.der().clone();
+    let key_der = PrivateKeyDer::Pkcs8(PrivatePkcs8KeyDer::from(cert.signing_key.serialize_der()));
+    let server_config = Arc::new(
+        ServerConfig::builder()
+            .with_no_client_auth()
+            .with_single_cert(vec![cert_der.clone()], key_der)
+            .unwrap(),
+    );
+
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let port = listener.local_addr().unwrap().port();
+    let (closed_tx, closed_rx) = mpsc::channel();
+    thread::spawn(move || {
+        for sock in listener.incoming().flatten() {
+            let server_config = server_config.clone();
+            let closed_tx = closed_tx.clone();
+            thread::spawn(move || {
+                let conn = ServerConnection::new(server_config).unwrap();
+                let mut stream = StreamOwned::new(conn, sock);
+                let mut buf = [0u8; 1024];
+                let clean = loop {
+                    match stream.read(&mut buf) {
+                        // rustls 仅在收到 close_notify 后返回 Ok(0)
+                        Ok(0) => break true,
+                        Ok(n) => {
+                            if stream.write_all(&buf[..n]).is_err() {
+                                break false;
+                            }
+                        }
+                        Err(_) => break false,
+                    }
+                };
+                let _ = closed_tx.send(clean);
+            });
+        }
+    });
+
+    let mut roots = RootCertStore::empty();
+    roots.add(cert_der).unwrap();
+    let client_config = Arc::new(
+        ClientConfig::builder()
+            .with_root_certificates(roots)
+            .with_no_client_auth(),
+    );
+    (port, client_config, closed_rx)
+}
+
+fn echo(conn: &mut PooledConnection, msg: &[u8]) {
+    conn.write_all(msg).unwrap();
+    let mut buf = vec![0u8; msg.len()];
+    conn.read_exact(&mut buf).unwrap();
+    assert_eq!(buf, msg);
+}
+
+#[test]
+fn test_tls_dialer_reuses_connection() {
+    let (port, client_config, _closed) = start_tls_echo_server();
+    let mut config = default_config();
+    config.min_connections = 0;
+    config.dialer = Some(tls_dialer("127.0.0.1", port, "localhost", client_config));
+    let pool = Pool::new(config).unwrap();
+
+    let mut conn = pool.get().unwrap();
+    let id = conn.id();
+    assert!(conn.tls_conn().is_some());
+    echo(&mut conn, b"first");
+    drop(conn);
+
+    let mut conn = pool.get_tcp().unwrap();
+    assert_eq!(conn.id(), id);
+    assert_eq!(conn.protocol(), Protocol::TCP);
+    echo(&mut conn, b"second");
+    drop(conn);
+
+    let stats = pool.stats();
+    assert_eq!(stats.total_connections_created, 1);
+    assert_eq!(stats.total_connections_reused, 1);
+    let tcp_v4 = pool.bucket_stats()[0];
+    assert_eq!((tcp_v4.protocol, tcp_v4.idle), (Protocol::TCP, 1));
+}
+
+#[test]
+fn test_tls_close_sends_close_notify() {
+    let (port, client_config, closed) = start_tls_echo_server();
+    let mut config = default_config();
+    config.min_connections = 0;
+    config.dialer = Some(tls_dialer("127.0.0.1", port, "localhost", client_config));
+    let pool = Pool::new(config).unwrap();
+
+    let mut conn = pool.get().unwrap();
+    echo(&mut conn, b"ping");
+    drop(conn);
+    pool.close().unwrap();
+
+    assert!(closed.recv_timeout(Duration::from_secs(5)).unwrap());
+}
+
+#[test]
+fn test_tls_handshake_failure_is_dial_error() {
+    let (port, _, _closed) = start_tls_echo_server();
+    // 客户端不信任服务器的自签名证书
+    let client_config = Arc::new(
+        ClientConfig::builder()
+            .with_root_certificates(RootCertStore::empty())
+            .with_no_client_auth(),
+    );
+    let mut config = default_config();
+    config.min_connections = 0;
+    config.dialer = Some(tls_dialer("127.0.0.1", port, "localhost", client_config));
+    let pool = Pool::new(config).unwrap();
+
+    assert!(matches!(
+        pool.try_get(),
+        Err(NetConnPoolError::DialFailed { .. })
+    ));
+}
+
+#[test]
+fn test_tls_close_unblocks_parked_reader() {
+    let (port, client_config, _closed) = start_tls_echo_server();
+    let mut config = default_config();
+    config.min_connections = 0;
+    config.shutdown_timeout = Some(Duration::ZERO);
+    config.dialer = Some(tls_dialer("127.0.0.1", port, "localhost", client_config));
+    let pool = Pool::new(config).unwrap();
+
+    let mut conn = pool.get().unwrap();
+    // TLS 连接不支持 split：读写共用同一会话，无法真正并发
+    assert_eq!(
+        conn.split().err().map(|e| e.kind()),
+        Some(std::io::ErrorKind::Unsupported)
+    );
+
+    // 读线程阻塞在 read 中持有会话锁，关闭连接池时强制关闭应唤醒它而不是死锁
+    let (done_tx, done_rx) = mpsc::channel();
+    thread::spawn(move || {
+        let mut buf = [0u8; 16];
+        let _ = conn.read(&mut buf);
+        let _ = done_tx.send(());
+    });
+    thread::sleep(Duration::from_millis(100));
+    let closer = pool.clone();
+    let (closed_tx, closed_rx) = mpsc::channel();
+    thread::spawn(move || {
+        let _ = closer.close();
+        let _ = closed_tx.send(());
+    });
+    assert!(closed_rx.recv_timeout(Duration::from_secs(5)).is_ok());
+    assert!(done_rx.recv_timeout(Duration::from_secs(5)).is_ok());
+}
@@ -0,0 +1,83 @@
+// Copyright (c) 2025, vistone
+// All rights reserved.
+
+// 异步连接池压力测试（async feature）
+// 运行: cargo test --features async --test async_stress_test -- --ignored --nocapture
+
+use netconnpool::config::default_config;
+use netconnpool::*;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::net::{TcpListener, TcpStream};
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 8)]
+#[ignore] // 默认忽略，需要较长时间运行
+async fn test_async_concurrent_get() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        let mut held = Vec::new();
+        while let Ok((stream, _)) = listener.accept().await {
+            held.push(stream);
+        }
+    });
+
+    let mut config = default_config();
+    config.min_connections = 0;
+    config.max_connections = 50;
+    config.max_idle_connections = 50;
+    config.get_connection_timeout = Duration::from_secs(10);
+    let pool = Arc::new(
+        AsyncPool::new(config, Box::new(move || Box::pin(TcpStream::connect(addr)))).unwrap(),
+    );
+
+    let num_tasks = 1000;
+    let operations_per_task = 100;
+    let success = Arc::new(AtomicU64::new(0));
+    let failed = Arc::new(AtomicU64::new(0));
+
+    let start = Instant::now();
+    let handles: Vec<_> = (0..num_tasks)
+        .map(|_| {
+            let pool = pool.clone();
+            let success = success.clone();
+            let failed = failed.clone();
+            tokio::spawn(async move {
+                for _ in 0..operations_per_task {
+                    match pool.get().await {
+                        Ok(conn) => {
+                            tokio::task::yield_now().await;
+                            drop(conn);
+                            success.fetch_add(1, Ordering::Relaxed);
+                        }
+                        Err(_) => {
+                            failed.fetch_add(1, Ordering::Relaxed);
+                        }
+                    }
+                }
+            })
+        })
+        .collect();
+    for handle in handles {
+        handle.await.unwrap();
+    }
+    let elapsed = start.elapsed();
+
+    let total = success.load(Ordering::Relaxed) + failed.load(Ordering::Relaxed);
+    let stats = pool.stats();
+    println!("异步并发测试结果:");
+    println!("  总操作数: {}", total);
+    println!("  成功: {}", success.load(Ordering::Relaxed));
+    println!("  失败: {}", failed.load(Ordering::Relaxed));
+    println!("  耗时: {:?}", elapsed);
+    println!(
+        "  吞吐量: {:.0} ops/s",
+        total as f64 / elapsed.as_secs_f64()
+    );
+    println!("  新建连接: {}", stats.total_connections_created);
+
+    assert_eq!(failed.load(Ordering::Relaxed), 0);
+    assert!(stats.total_connections_created <= 50);
+    assert_eq!(pool.active_count(), 0);
+}
@@ -5,7 +5,7 @@
 
 use netconnpool::config::default_config;
 use netconnpool::*;
-use std::net::{TcpListener, TcpStream};
+use std::net::{TcpListener, TcpStream, UdpSocket};
 use std::sync::Arc;
 use std::thread;
 use std::time::{Duration, Instant};
@@ -60,15 +60,9 @@ fn benchmark_get_put_operations() {
         "  吞吐量: {:.2} ops/sec",
         iterations as f64 / duration.as_secs_f64()
     );
-    // average_reuse_count 是平均每个连接的复用次数，不是复用率
-    // 复用率 = total_connections_reused / successful_gets * 100%
-    let reuse_rate = if stats.successful_gets > 0 {
-        stats.total_connections_reused as f64 / stats.successful_gets as f64 * 100.0
-    } else {
-        0.0
-    };
+    let reuse_rate = stats.reuse_ratio * 100.0;
     println!("  连接复用率: {:.2}%", reuse_rate);
-    println!("  平均复用次数: {:.2}", stats.average_reuse_count);
+    println!("  平均复用次数: {:.2}", stats.avg_reuse_per_conn);
 
     // 性能要求：每秒至少10万次操作
     let ops_per_sec = iterations as f64 / duration.as_secs_f64();
@@ -131,15 +125,9 @@ fn benchmark_concurrent_get_put() {
         "  吞吐量: {:.2} ops/sec",
         total_operations as f64 / duration.as_secs_f64()
     );
-    // average_reuse_count 是平均每个连接的复用次数，不是复用率
-    // 复用率 = total_connections_reused / successful_gets * 100%
-    let reuse_rate = if stats.successful_gets > 0 {
-        stats.total_connections_reused as f64 / stats.successful_gets as f64 * 100.0
-    } else {
-        0.0
-    };
+    let reuse_rate = stats.reuse_ratio * 100.0;
     println!("  连接复用率: {:.2}%", reuse_rate);
-    println!("  平均复用次数: {:.2}", stats.average_reuse_count);
+    println!("  平均复用次数: {:.2}", stats.avg_reuse_per_conn);
 
     let ops_per_sec = total_operations as f64 / duration.as_secs_f64();
     assert!(ops_per_sec > 50000.0, "并发吞吐量应该超过50000 ops/sec");
@@ -251,3 +239,217 @@ fn benchmark_stats_collection() {
     let avg_ns = duration.as_nanos() / iterations;
     assert!(avg_ns < 10000, "获取统计信息应该在10微秒内完成");
 }
+
+/// 健康检查耗时基准：1000 个空闲连接、每次检查 10ms，对比串行与并行单轮耗时
+fn measure_health_check_round(concurrency: usize) -> Duration {
+    const CONNS: usize = 1000;
+    let server = std::net::UdpSocket::bind("127.0.0.1:0").unwrap();
+    let server_addr = server.local_addr().unwrap();
+
+    let mut config = default_config();
+    config.dialer = Some(Box::new(move |_| {
+        let socket = std::net::UdpSocket::bind("127.0.0.1:0")?;
+        socket.connect(server_addr)?;
+        Ok(ConnectionType::Udp(socket))
+    }));
+    config.max_connections = CONNS;
+    config.max_idle_connections = CONNS;
+    config.min_connections = 0;
+    config.health_check_interval = Duration::from_millis(200);
+    config.health_check_concurrency = concurrency;
+    config.health_checker = Some(Box::new(|_| {
+        thread::sleep(Duration::from_millis(10));
+        true
+    }));
+
+    let pool = Pool::new(config).unwrap();
+    let conns: Vec<_> = (0..CONNS).map(|_| pool.get().unwrap()).collect();
+    drop(conns);
+
+    let start = Instant::now();
+    while (pool.stats().health_check_attempts as usize) < CONNS {
+        thread::sleep(Duration::from_millis(5));
+    }
+    let elapsed = start.elapsed();
+    pool.close().unwrap();
+    elapsed
+}
+
+#[test]
+#[ignore]
+fn benchmark_parallel_health_check() {
+    let serial = measure_health_check_round(1);
+    let parallel = measure_health_check_round(32);
+
+    println!("健康检查基准测试（1000 个空闲连接，单次检查 10ms）:");
+    println!("  串行单轮耗时: {:?}", serial);
+    println!("  并行(32)单轮耗时: {:?}", parallel);
+
+    assert!(
+        parallel * 5 < serial,
+        "并行健康检查应显著快于串行: {:?} vs {:?}",
+        parallel,
+        serial
+    );
+}
+
+#[test]
+#[ignore]
+fn benchmark_concurrent_connection_creation() {
+    let peer = UdpSocket::bind("127.0.0.1:0").unwrap();
+    let peer_addr = peer.local_addr().unwrap();
+
+    let max_conns = 1000;
+    let mut config = default_config();
+    config.dialer = Some(Box::new(move |_| {
+        let socket = UdpSocket::bind("127.0.0.1:0")?;
+        socket.connect(peer_addr)?;
+        Ok(ConnectionType::Udp(socket))
+    }));
+    config.max_connections = max_conns;
+    config.max_idle_connections = max_conns;
+    config.min_connections = 0;
+    config.get_connection_timeout = Duration::ZERO;
+
+    let pool = Arc::new(Pool::new(config).unwrap());
+
+    // 每个线程只建连并持有，超出上限的请求会快速失败
+    let num_threads = 16;
+    let per_thread = 100;
+    let start = Instant::now();
+    let handles: Vec<_> = (0..num_threads)
+        .map(|_| {
+            let pool = pool.clone();
+            thread::spawn(move || {
+                (0..per_thread)
+                    .filter_map(|_| pool.get().ok())
+                    .collect::<Vec<_>>()
+            })
+        })
+        .collect();
+    let held: Vec<_> = handles
+        .into_iter()
+        .flat_map(|h| h.join().unwrap())
+        .collect();
+    let duration = start.elapsed();
+
+    let stats = pool.stats();
+    println!("并发建连基准测试:");
+    println!("  线程数: {}", num_threads);
+    println!("  请求数: {}", num_threads * per_thread);
+    println!("  成功建连: {}", held.len());
+    println!("  总耗时: {:?}", duration);
+    println!(
+        "  建连速率: {:.2} conn/sec",
+        held.len() as f64 / duration.as_secs_f64()
+    );
+
+    assert_eq!(held.len(), max_conns);
+    assert!(stats.current_connections <= max_conns as i64);
+}
+
+fn measure_udp_return_latency(async_return: bool) -> Duration {
+    let server = UdpSocket::bind("127.0.0.1:0").unwrap();
+    let server_addr = server.local_addr().unwrap();
+
+    let mut config = default_config();
+    config.dialer = Some(Box::new(move |_| {
+        let socket = UdpSocket::bind("127.0.0.1:0")?;
+        socket.connect(server_addr)?;
+        Ok(ConnectionType::Udp(socket))
+    }));
+    config.min_connections = 0;
+    config.async_return = async_return;
+    // 归还时由业务侧排空残留的大数据报（如未读完的响应）
+    config.on_return = Some(Box::new(|conn| {
+        if let ConnectionType::Udp(socket) = conn {
+            let _ = netconnpool::udp_utils::clear_udp_read_buffer(
+                socket,
+                Duration::from_millis(5),
+                1000,
+            );
+        }
+    }));
+    let pool = Pool::new(config).unwrap();
+
+    let payload = vec![0u8; 1400];
+    let rounds = 50;
+    let mut total = Duration::ZERO;
+    for _ in 0..rounds {
+        let conn = pool.get().unwrap();
+        let local = conn.udp_conn().unwrap().local_addr().unwrap();
+        for _ in 0..100 {
+            server.send_to(&payload, local).unwrap();
+        }
+        let start = Instant::now();
+        drop(conn);
+        total += start.elapsed();
+    }
+    pool.close().unwrap();
+    total / rounds
+}
+
+#[test]
+#[ignore]
+fn benchmark_async_return_latency() {
+    let sync_latency = measure_udp_return_latency(false);
+    let async_latency = measure_udp_return_latency(true);
+
+    println!("大数据 UDP 连接归还延迟基准测试:");
+    println!("  同步归还: {:?}/次", sync_latency);
+    println!("  异步归还: {:?}/次", async_latency);
+
+    // 单核环境下被唤醒的归还线程会立即抢占业务线程，异步归还体现不出收益
+    if thread::available_parallelism().map_or(1, |n| n.get()) > 1 {
+        assert!(
+            async_latency * 2 < sync_latency,
+            "异步归还应显著降低业务线程的归还延迟"
+        );
+    }
+}
+
+#[test]
+#[ignore]
+fn benchmark_batch_return() {
+    let listener = create_test_server();
+    let addr = get_server_addr(&listener);
+
+    let fan_out = 64;
+    let mut config = default_config();
+    config.dialer = Some(Box::new(move |_| {
+        TcpStream::connect(&addr)
+            .map(ConnectionType::Tcp)
+            .map_err(|e| Box::new(e) as Box<dyn std::error::Error + Send + Sync>)
+    }));
+    config.max_connections = fan_out;
+    config.max_idle_connections = fan_out;
+    config.min_connections = 0;
+    let pool = Pool::new(config).unwrap();
+    drop(pool.get_many(fan_out, Duration::from_secs(5)).unwrap());
+
+    let rounds = 2000;
+    let mut individual = Duration::ZERO;
+    for _ in 0..rounds {
+        let conns = pool
+            .get_many(fan_out, Duration::from_secs(1))
+            .unwrap()
+            .into_vec();
+        let start = Instant::now();
+        drop(conns);
+        individual += start.elapsed();
+    }
+
+    let mut batched = Duration::ZERO;
+    for _ in 0..rounds {
+        let batch = pool.get_many(fan_out, Duration::from_secs(1)).unwrap();
+        let start = Instant::now();
+        drop(batch);
+        batched += start.elapsed();
+    }
+
+    println!("扇出归还基准测试（每批 {} 条）:", fan_out);
+    println!("  逐个归还: {:?}/批", individual / rounds);
+    println!("  批量归还: {:?}/批", batched / rounds);
+
+    assert!(batched <= individual, "批量归还不应比逐个归还更慢");
+}
@@ -186,15 +186,9 @@ fn test_long_running() {
     println!("    创建连接数: {}", final_stats.total_connections_created);
     println!("    关闭连接数: {}", final_stats.total_connections_closed);
     println!("    当前连接数: {}", final_stats.current_connections);
-    // average_reuse_count 是平均每个连接的复用次数，不是复用率
-    // 复用率 = total_connections_reused / successful_gets * 100%
-    let reuse_rate = if final_stats.successful_gets > 0 {
-        final_stats.total_connections_reused as f64 / final_stats.successful_gets as f64 * 100.0
-    } else {
-        0.0
-    };
+    let reuse_rate = final_stats.reuse_ratio * 100.0;
     println!("    连接复用率: {:.2}%", reuse_rate);
-    println!("    平均复用次数: {:.2}", final_stats.average_reuse_count);
+    println!("    平均复用次数: {:.2}", final_stats.avg_reuse_per_conn);
 
     assert!(total_ops > 0, "应该有成功的操作");
     assert!(final_stats.current_connections <= max_conns as i64);
@@ -361,15 +355,9 @@ fn test_rapid_acquire_release() {
         iterations as f64 / duration.as_secs_f64()
     );
     println!("  创建连接数: {}", stats.total_connections_created);
-    // average_reuse_count 是平均每个连接的复用次数，不是复用率
-    // 复用率 = total_connections_reused / successful_gets * 100%
-    let reuse_rate = if stats.successful_gets > 0 {
-        stats.total_connections_reused as f64 / stats.successful_gets as f64 * 100.0
-    } else {
-        0.0
-    };
+    let reuse_rate = stats.reuse_ratio * 100.0;
     println!("  连接复用率: {:.2}%", reuse_rate);
-    println!("  平均复用次数: {:.2}", stats.average_reuse_count);
+    println!("  平均复用次数: {:.2}", stats.avg_reuse_per_conn);
 
     // 连接复用率应该很高（> 95%）
     assert!(
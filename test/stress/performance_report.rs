@@ -331,15 +331,9 @@ fn generate_performance_report() {
     println!("  成功获取数: {}", final_stats.successful_gets);
     println!("  失败获取数: {}", final_stats.failed_gets);
     println!("  连接复用数: {}", final_stats.total_connections_reused);
-    // average_reuse_count 是平均每个连接的复用次数，不是复用率
-    // 复用率 = total_connections_reused / successful_gets * 100%
-    let reuse_rate = if final_stats.successful_gets > 0 {
-        final_stats.total_connections_reused as f64 / final_stats.successful_gets as f64 * 100.0
-    } else {
-        0.0
-    };
+    let reuse_rate = final_stats.reuse_ratio * 100.0;
     println!("  连接复用率: {:.2}%", reuse_rate);
-    println!("  平均复用次数: {:.2}", final_stats.average_reuse_count);
+    println!("  平均复用次数: {:.2}", final_stats.avg_reuse_per_conn);
     println!("  平均获取时间: {:?}", final_stats.average_get_time);
     println!();
 
@@ -359,10 +353,7 @@ fn generate_performance_report() {
         "统计收集吞吐量:   {:.2} stats/sec",
         stats_iterations as f64 / stats_duration.as_secs_f64()
     );
-    println!(
-        "连接复用率:       {:.2}%",
-        final_stats.average_reuse_count * 100.0
-    );
+    println!("连接复用率:       {:.2}%", final_stats.reuse_ratio * 100.0);
     println!();
 
     // 性能评估
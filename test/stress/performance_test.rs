@@ -4,6 +4,7 @@
 // 全面性能测试套件 - 记录速度、时间、IO吞吐量等关键指标
 
 use netconnpool::config::{default_config, ConnectionType};
+use netconnpool::memory::current_rss_mb;
 use netconnpool::*;
 use std::io::{Read, Write};
 use std::net::{TcpListener, TcpStream};
@@ -50,6 +51,9 @@ impl PerformanceResult {
         println!("P50延迟: {:.2} μs", self.p50_latency_ns as f64 / 1000.0);
         println!("P95延迟: {:.2} μs", self.p95_latency_ns as f64 / 1000.0);
         println!("P99延迟: {:.2} μs", self.p99_latency_ns as f64 / 1000.0);
+        if self.memory_usage_mb > 0.0 {
+            println!("内存占用(RSS): {:.2} MB", self.memory_usage_mb);
+        }
         if self.io_throughput_bytes_per_sec > 0.0 {
             println!(
                 "IO吞吐量: {:.2} MB/s",
@@ -181,7 +185,7 @@ fn test_get_put_throughput() {
         p95_latency_ns: p95_latency,
         p99_latency_ns: p99_latency,
         io_throughput_bytes_per_sec: 0.0,
-        memory_usage_mb: 0.0,
+        memory_usage_mb: current_rss_mb(),
         cpu_usage_percent: 0.0,
     };
 
@@ -263,7 +267,7 @@ fn test_concurrent_throughput() {
         p95_latency_ns: 0,
         p99_latency_ns: 0,
         io_throughput_bytes_per_sec: 0.0,
-        memory_usage_mb: 0.0,
+        memory_usage_mb: current_rss_mb(),
         cpu_usage_percent: 0.0,
     };
 
@@ -344,7 +348,7 @@ fn test_io_throughput() {
         p95_latency_ns: 0,
         p99_latency_ns: 0,
         io_throughput_bytes_per_sec: io_throughput,
-        memory_usage_mb: 0.0,
+        memory_usage_mb: current_rss_mb(),
         cpu_usage_percent: 0.0,
     };
 
@@ -778,15 +782,9 @@ fn test_comprehensive_performance() {
     println!("  创建连接数: {}", stats.total_connections_created);
     println!("  成功获取数: {}", stats.successful_gets);
     println!("  连接复用数: {}", stats.total_connections_reused);
-    // average_reuse_count 是平均每个连接的复用次数，不是复用率
-    // 复用率 = total_connections_reused / successful_gets * 100%
-    let reuse_rate = if stats.successful_gets > 0 {
-        stats.total_connections_reused as f64 / stats.successful_gets as f64 * 100.0
-    } else {
-        0.0
-    };
+    let reuse_rate = stats.reuse_ratio * 100.0;
     println!("  连接复用率: {:.2}%", reuse_rate);
-    println!("  平均复用次数: {:.2}", stats.average_reuse_count);
+    println!("  平均复用次数: {:.2}", stats.avg_reuse_per_conn);
     println!("========================================\n");
 
     // 性能要求
@@ -411,7 +411,7 @@ fn test_stats_calculate_average_reuse_count() {
     println!("统计模块平均复用次数测试结果:");
     println!("  创建连接数: {}", stats.total_connections_created);
     println!("  复用次数: {}", stats.total_connections_reused);
-    println!("  平均复用次数: {:.2}", stats.average_reuse_count);
+    println!("  平均复用次数: {:.2}", stats.avg_reuse_per_conn);
 
     assert_eq!(stats.total_connections_created, 10);
     assert_eq!(stats.total_connections_reused, 50);
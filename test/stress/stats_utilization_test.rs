@@ -428,7 +428,7 @@ fn print_stats(stats: &Stats) {
     println!(
         "    复用: {}, 复用率: {:.2}%",
         stats.total_connections_reused,
-        stats.average_reuse_count * 100.0
+        stats.reuse_ratio * 100.0
     );
     println!(
         "    平均获取时间: {:?}, 总获取时间: {:?}",
@@ -2,6 +2,7 @@
 // All rights reserved.
 
 use netconnpool::*;
+use std::time::Duration;
 
 #[test]
 fn test_stats_collector() {
@@ -28,3 +29,306 @@ fn test_stats_increment() {
     assert_eq!(stats.failed_gets, 1);
     assert_eq!(stats.connection_errors, 1);
 }
+
+#[test]
+fn test_stats_reset_keeps_current_counters() {
+    let collector = StatsCollector::new();
+
+    collector.increment_total_connections_created();
+    collector.increment_successful_gets();
+    collector.increment_current_idle_connections(1);
+    collector.record_health_check_failure_reason("timeout");
+    collector.reset();
+
+    let stats = collector.get_stats();
+    assert_eq!(stats.total_connections_created, 0);
+    assert_eq!(stats.successful_gets, 0);
+    assert!(stats.health_check_failure_reasons.is_empty());
+    assert_eq!(stats.current_connections, 1);
+    assert_eq!(stats.current_idle_connections, 1);
+}
+
+#[test]
+fn test_stats_reuse_ratio_and_avg_reuse_per_conn() {
+    let collector = StatsCollector::new();
+
+    // 2 条连接，共 10 次成功获取，其中 8 次命中空闲连接
+    collector.increment_total_connections_created();
+    collector.increment_total_connections_created();
+    for _ in 0..10 {
+        collector.increment_successful_gets();
+    }
+    for _ in 0..8 {
+        collector.increment_total_connections_reused();
+    }
+
+    let stats = collector.get_stats();
+    assert_eq!(stats.reuse_ratio, 0.8);
+    assert_eq!(stats.avg_reuse_per_conn, 4.0);
+    assert_eq!(stats.average_reuse_count, stats.avg_reuse_per_conn);
+
+    let empty = StatsCollector::new().get_stats();
+    assert_eq!(empty.reuse_ratio, 0.0);
+    assert_eq!(empty.avg_reuse_per_conn, 0.0);
+}
+
+#[test]
+fn test_stats_reconcile_current_fixes_drift() {
+    let collector = StatsCollector::new();
+    collector.increment_current_idle_connections(3);
+    collector.increment_current_active_connections(-1);
+    collector.increment_total_connections_created();
+
+    // 创建计数同时增加 current_connections，此处与真实值一致
+    let actual = Stats {
+        current_connections: 1,
+        current_idle_connections: 1,
+        ..Stats::default()
+    };
+    assert_eq!(collector.reconcile_current(&actual), 2);
+
+    let stats = collector.get_stats();
+    assert_eq!(stats.current_idle_connections, 1);
+    assert_eq!(stats.current_active_connections, 0);
+    assert_eq!(stats.total_connections_created, 1);
+    assert_eq!(collector.reconcile_current(&actual), 0);
+}
+
+#[test]
+fn test_stats_merge_sums_counters_and_recomputes_ratios() {
+    let mut total = Stats {
+        total_connections_created: 2,
+        current_connections: 2,
+        successful_gets: 4,
+        total_connections_reused: 2,
+        total_get_time: std::time::Duration::from_millis(40),
+        ..Stats::default()
+    };
+    let mut other = Stats {
+        total_connections_created: 2,
+        current_connections: 1,
+        successful_gets: 6,
+        total_connections_reused: 6,
+        total_get_time: std::time::Duration::from_millis(60),
+        ..Stats::default()
+    };
+    other
+        .health_check_failure_reasons
+        .insert("timeout".to_string(), 1);
+    total.merge(&other);
+
+    assert_eq!(total.current_connections, 3);
+    assert_eq!(total.successful_gets, 10);
+    assert_eq!(total.reuse_ratio, 0.8);
+    assert_eq!(total.avg_reuse_per_conn, 2.0);
+    assert_eq!(total.average_get_time, std::time::Duration::from_millis(10));
+    assert_eq!(total.health_check_failure_reasons.get("timeout"), Some(&1));
+}
+
+#[test]
+fn test_failure_reasons_are_capped() {
+    use netconnpool::stats::{FAILURE_REASON_OTHER, MAX_FAILURE_REASONS};
+
+    let collector = StatsCollector::new();
+    // 原因中带端口等可变内容时，超出上限的新原因统一计入 other
+    for port in 0..MAX_FAILURE_REASONS + 10 {
+        collector.record_health_check_failure_reason(&format!("connect 10.0.0.1:{} refused", port));
+    }
+    collector.record_health_check_failure_reason("connect 10.0.0.1:0 refused");
+    let mut stats = collector.get_stats();
+    let reasons = &stats.health_check_failure_reasons;
+    assert_eq!(reasons.len(), MAX_FAILURE_REASONS + 1);
+    assert_eq!(reasons.get(FAILURE_REASON_OTHER), Some(&10));
+    assert_eq!(reasons.get("connect 10.0.0.1:0 refused"), Some(&2));
+
+    // 合并时同样受上限约束
+    let mut other = Stats::default();
+    other
+        .health_check_failure_reasons
+        .insert("brand new reason".to_string(), 3);
+    stats.merge(&other);
+    assert_eq!(
+        stats.health_check_failure_reasons.len(),
+        MAX_FAILURE_REASONS + 1
+    );
+    assert_eq!(
+        stats.health_check_failure_reasons.get(FAILURE_REASON_OTHER),
+        Some(&13)
+    );
+}
+
+#[test]
+fn test_stats_merge_average_with_counts_above_u32() {
+    // 样本数超过 u32::MAX：转换为 u32 会截断为 0 而除零
+    let count = i64::from(u32::MAX) + 1;
+    let mut total = Stats {
+        successful_gets: count,
+        total_get_time: Duration::from_micros(2) * u32::MAX,
+        ..Stats::default()
+    };
+    total.merge(&Stats {
+        total_get_time: Duration::from_micros(2),
+        ..Stats::default()
+    });
+    assert_eq!(total.average_get_time, Duration::from_micros(2));
+}
+
+#[test]
+fn test_get_time_stats_merge_with_counts_above_u32() {
+    let mut reused = GetTimeStats {
+        count: i64::from(u32::MAX),
+        total: Duration::from_micros(3) * u32::MAX,
+        ..GetTimeStats::default()
+    };
+    reused.merge(&GetTimeStats {
+        count: 1,
+        total: Duration::from_micros(3),
+        ..GetTimeStats::default()
+    });
+    assert_eq!(reused.count, i64::from(u32::MAX) + 1);
+    assert_eq!(reused.average, Duration::from_micros(3));
+}
+
+#[test]
+fn test_get_time_by_source_reports_percentiles() {
+    let collector = StatsCollector::new();
+    for _ in 0..99 {
+        collector.record_get_time_by_source(Duration::from_micros(100), true);
+    }
+    collector.record_get_time_by_source(Duration::from_millis(50), true);
+    collector.record_get_time_by_source(Duration::from_millis(10), false);
+
+    let stats = collector.get_stats();
+    let reused = stats.reused_get_time;
+    assert_eq!(reused.count, 100);
+    assert_eq!(
+        reused.total,
+        Duration::from_micros(9_900) + Duration::from_millis(50)
+    );
+    // 分位数为所在分桶的上界，误差不超过 25%
+    for p in [reused.p50, reused.p90, reused.p99] {
+        assert!(p >= Duration::from_micros(100) && p <= Duration::from_micros(125));
+    }
+    assert_eq!(stats.created_get_time.count, 1);
+    assert!(stats.created_get_time.p50 >= Duration::from_millis(10));
+    assert!(stats.created_get_time.p50 <= Duration::from_micros(12_500));
+
+    collector.reset();
+    assert_eq!(
+        collector.get_stats().reused_get_time,
+        GetTimeStats::default()
+    );
+}
+
+#[test]
+fn test_stats_to_openmetrics_text() {
+    let collector = StatsCollector::new();
+    collector.increment_total_connections_created();
+    collector.increment_total_connections_created();
+    collector.increment_successful_gets();
+    collector.increment_current_ipv6_connections(2);
+    collector.record_health_check_failure_reason("read \"timeout\"");
+    collector.record_get_time_by_source(Duration::from_millis(3), false);
+
+    let text = collector.get_stats().to_openmetrics("pool");
+    let lines: Vec<&str> = text.lines().collect();
+    for expected in [
+        "# TYPE pool_connections_created counter",
+        "pool_connections_created_total 2",
+        "pool_successful_gets_total 1",
+        "# TYPE pool_current_connections gauge",
+        "pool_current_connections 2",
+        "pool_current_connections_by_ip_version{ip_version=\"ipv4\"} 0",
+        "pool_current_connections_by_ip_version{ip_version=\"ipv6\"} 2",
+        "pool_health_check_failure_reasons_total{reason=\"read \\\"timeout\\\"\"} 1",
+        "# TYPE pool_get_duration_seconds summary",
+        "pool_get_duration_seconds_count{source=\"created\"} 1",
+        "pool_get_duration_seconds_sum{source=\"created\"} 0.003",
+        "pool_get_duration_seconds_count{source=\"reused\"} 0",
+    ] {
+        assert!(lines.contains(&expected), "缺少 `{}`:\n{}", expected, text);
+    }
+    assert_eq!(lines.last(), Some(&"# EOF"));
+    // 每个指标族只声明一次类型
+    let types: Vec<&str> = lines
+        .iter()
+        .filter(|l| l.starts_with("# TYPE"))
+        .copied()
+        .collect();
+    let unique: std::collections::HashSet<_> = types.iter().collect();
+    assert_eq!(types.len(), unique.len());
+
+    // prefix 为空时不加前缀
+    assert!(Stats::default()
+        .to_openmetrics("")
+        .contains("\nconnections_created_total 0\n"));
+}
+
+#[test]
+fn test_last_update_time_tracks_counter_changes_not_snapshots() {
+    let collector = StatsCollector::new();
+    collector.increment_total_get_requests();
+    let first = collector.get_stats().last_update_time;
+
+    // 只读取快照不改变 last_update_time
+    std::thread::sleep(Duration::from_millis(20));
+    let second = collector.get_stats().last_update_time;
+    assert_eq!(first, second);
+    assert!(second.elapsed() >= Duration::from_millis(20));
+
+    // 计数变化后推进到变化发生的时间
+    collector.increment_successful_gets();
+    let third = collector.get_stats().last_update_time;
+    assert!(third >= first + Duration::from_millis(20));
+}
+
+#[test]
+fn test_smoothed_stats_converge_to_steady_rate() {
+    let collector = StatsCollector::with_ewma_window(Duration::from_millis(100));
+    let start = std::time::Instant::now();
+    let mut injected = 0u64;
+    // 按已流逝时间补齐注入量，使速率稳定在约 1000 次/秒、成功率 75%，不受 sleep 抖动影响
+    let mut inject_until_now = || {
+        let target = (start.elapsed().as_secs_f64() * 1000.0) as u64;
+        while injected < target {
+            collector.increment_total_get_requests();
+            if !injected.is_multiple_of(4) {
+                collector.increment_successful_gets();
+            }
+            if injected.is_multiple_of(10) {
+                collector.record_get_time_by_source(Duration::from_millis(2), false);
+            }
+            injected += 1;
+        }
+    };
+
+    // 注入 8 个窗口，期间不定期生成快照
+    while start.elapsed() < Duration::from_millis(800) {
+        inject_until_now();
+        collector.get_stats();
+        std::thread::sleep(Duration::from_millis(5));
+    }
+    inject_until_now();
+
+    let smoothed = collector.get_stats().smoothed;
+    assert!(
+        (700.0..1300.0).contains(&smoothed.get_rate),
+        "get_rate = {}",
+        smoothed.get_rate
+    );
+    assert!(
+        (0.7..0.8).contains(&smoothed.success_ratio),
+        "success_ratio = {}",
+        smoothed.success_ratio
+    );
+    let created = smoothed.created_get_time.as_secs_f64();
+    assert!(
+        (created - 0.002).abs() < 1e-6,
+        "created_get_time = {}",
+        created
+    );
+
+    // 停止注入后速率衰减
+    std::thread::sleep(Duration::from_millis(300));
+    assert!(collector.get_stats().smoothed.get_rate < 100.0);
+}